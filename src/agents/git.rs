@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Git state of an agent's working directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitInfo {
+    /// Top level directory of the checkout
+    pub root: PathBuf,
+    /// Checked out branch (short commit id when detached)
+    pub branch: String,
+    /// Whether the checkout is a linked worktree (`git worktree add`)
+    pub worktree: bool,
+    /// Uncommitted changes (including untracked files)
+    pub dirty: bool,
+    /// Commits ahead of / behind the upstream branch
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+impl GitInfo {
+    /// Reads the git state of `dir`: the branch from the `.git` files, changes and
+    /// upstream distance from `git status` (local only, nothing is fetched).
+    /// None outside a git checkout.
+    pub fn read(dir: &Path) -> Option<Self> {
+        let (root, git_dir) = find_git_dir(dir)?;
+        let mut info = Self {
+            worktree: root.join(".git").is_file() && git_dir.join("commondir").exists(),
+            root,
+            branch: head_branch(&git_dir)?,
+            ..Default::default()
+        };
+
+        let output = Command::new("git")
+            .args([
+                "--no-optional-locks",
+                "status",
+                "--porcelain=v2",
+                "--branch",
+            ])
+            .current_dir(dir)
+            .output()
+            .ok()
+            .filter(|o| o.status.success());
+        if let Some(output) = output {
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                if let Some(ab) = line.strip_prefix("# branch.ab ") {
+                    // "+<ahead> -<behind>"
+                    let mut counts = ab
+                        .split_whitespace()
+                        .map(|n| n.trim_start_matches(['+', '-']).parse().unwrap_or(0));
+                    info.ahead = counts.next().unwrap_or(0);
+                    info.behind = counts.next().unwrap_or(0);
+                } else if !line.starts_with('#') {
+                    info.dirty = true;
+                }
+            }
+        }
+        Some(info)
+    }
+
+    /// Compact state: "*" for changes, "↑n"/"↓n" for commits ahead/behind
+    pub fn status_text(&self) -> String {
+        let mut text = String::new();
+        if self.dirty {
+            text.push('*');
+        }
+        if self.ahead > 0 {
+            text.push_str(&format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            text.push_str(&format!("↓{}", self.behind));
+        }
+        text
+    }
+}
+
+/// Finds the checkout containing `dir`: its top level and git directory.
+/// Worktrees and submodules have a `.git` file pointing to the real git dir.
+fn find_git_dir(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    let root = dir.ancestors().find(|d| d.join(".git").exists())?;
+    let dot_git = root.join(".git");
+    let git_dir = if dot_git.is_file() {
+        let content = std::fs::read_to_string(&dot_git).ok()?;
+        let target = content.trim().strip_prefix("gitdir:")?.trim();
+        root.join(target)
+    } else {
+        dot_git
+    };
+    Some((root.to_path_buf(), git_dir))
+}
+
+/// Branch checked out in a git dir (short commit id when detached)
+fn head_branch(git_dir: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: refs/heads/") {
+        Some(branch) => Some(branch.to_string()),
+        None => Some(head.chars().take(7).collect()),
+    }
+}
+
+/// Top level directory of the git checkout containing `dir`
+pub fn repo_root(dir: &Path) -> Option<PathBuf> {
+    find_git_dir(dir).map(|(root, _)| root)
+}
+
+/// Current branch of the git checkout containing `dir`, read from the `.git` files
+pub fn git_branch(dir: &Path) -> Option<String> {
+    let (_, git_dir) = find_git_dir(dir)?;
+    head_branch(&git_dir)
+}
+
+/// Local branches of the repository containing `dir` (one git call)
+pub fn local_branches(dir: &Path) -> HashSet<String> {
+    Command::new("git")
+        .args(["for-each-ref", "--format=%(refname:short)", "refs/heads"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Creates a worktree at `path` with a new branch, from the HEAD of the repository containing `dir`
+pub fn add_worktree(dir: &Path, path: &Path, branch: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "add", "-b", branch])
+        .arg(path)
+        .current_dir(dir)
+        .output()
+        .context("Failed to execute git worktree add")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree add failed: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Removes a worktree made by `add_worktree` and its branch (cleanup after a failed launch)
+pub fn remove_worktree(dir: &Path, path: &Path, branch: &str) -> Result<()> {
+    for args in [
+        vec!["worktree", "remove", "--force", &*path.to_string_lossy()],
+        vec!["branch", "-D", branch],
+    ] {
+        let output = Command::new("git")
+            .args(&args)
+            .current_dir(dir)
+            .output()
+            .with_context(|| format!("Failed to execute git {}", args[0]))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git {} failed: {}", args[0], stderr.trim());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_git_info_of_repo_and_worktree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        std::fs::create_dir(repo.join("src")).unwrap();
+
+        let info = GitInfo::read(&repo.join("src")).unwrap();
+        assert_eq!((info.branch.as_str(), info.worktree), ("main", false));
+        assert_eq!(info.root, repo);
+        assert_eq!(info.status_text(), "");
+        assert!(GitInfo::read(tmp.path()).is_none());
+
+        // A new worktree on its own branch, with an untracked file
+        let tree = tmp.path().join("tree-1");
+        assert!(!local_branches(&repo).contains("agent-1"));
+        add_worktree(&repo, &tree, "agent-1").unwrap();
+        assert!(local_branches(&repo).contains("agent-1"));
+        assert!(add_worktree(&repo, &tree, "agent-1").is_err());
+        std::fs::write(tree.join("notes.txt"), "wip").unwrap();
+
+        let info = GitInfo::read(&tree).unwrap();
+        assert_eq!((info.branch.as_str(), info.worktree), ("agent-1", true));
+        assert_eq!(info.status_text(), "*");
+        assert_eq!(git_branch(&tree).as_deref(), Some("agent-1"));
+
+        // Undone when the launch fails
+        remove_worktree(&repo, &tree, "agent-1").unwrap();
+        assert!(!tree.exists());
+        assert_eq!(local_branches(&repo), HashSet::from(["main".to_string()]));
+    }
+}
@@ -1,5 +1,12 @@
+pub mod git;
 mod subagent;
+mod transition;
 mod types;
 
-pub use subagent::{Subagent, SubagentStatus, SubagentType};
-pub use types::{AgentStatus, AgentType, ApprovalType, MonitoredAgent};
+pub use git::GitInfo;
+pub use subagent::{repeat_id, split_repeat_id, Subagent, SubagentStatus, SubagentType};
+pub use transition::StatusTransition;
+pub use types::{
+    format_duration_long, format_duration_short, format_tokens, AgentStatus, AgentType,
+    ApprovalType, MonitoredAgent, StatusKind,
+};
@@ -67,6 +67,29 @@ impl fmt::Display for SubagentStatus {
     }
 }
 
+/// Id of the `n`-th most recent start (1 = newest) of subagents sharing `base`
+///
+/// Repeats are numbered from the bottom of the pane, so a start scrolling out
+/// at the top never renames the ones below it.
+pub fn repeat_id(base: &str, n: usize) -> String {
+    if n <= 1 {
+        base.to_string()
+    } else {
+        format!("{}#{}", base, n)
+    }
+}
+
+/// Splits an id built by `repeat_id` into its base and repeat number
+pub fn split_repeat_id(id: &str) -> (&str, usize) {
+    match id.rsplit_once('#') {
+        Some((base, n)) => match n.parse() {
+            Ok(n) if n > 1 => (base, n),
+            _ => (id, 1),
+        },
+        None => (id, 1),
+    }
+}
+
 /// Represents a subagent spawned by a parent agent
 #[derive(Debug, Clone)]
 pub struct Subagent {
@@ -80,6 +103,8 @@ pub struct Subagent {
     pub description: String,
     /// When the subagent was started
     pub started_at: DateTime<Local>,
+    /// When the subagent was seen completed or failed
+    pub finished_at: Option<DateTime<Local>>,
 }
 
 impl Subagent {
@@ -91,6 +116,7 @@ impl Subagent {
             status: SubagentStatus::Running,
             description,
             started_at: Local::now(),
+            finished_at: None,
         }
     }
 
@@ -100,9 +126,15 @@ impl Subagent {
         self
     }
 
-    /// Returns a formatted duration since start
+    /// Returns true while the subagent is running
+    pub fn is_running(&self) -> bool {
+        self.status == SubagentStatus::Running
+    }
+
+    /// Returns the formatted run time (up to now while running)
     pub fn duration_str(&self) -> String {
-        let duration = Local::now().signed_duration_since(self.started_at);
+        let end = self.finished_at.unwrap_or_else(Local::now);
+        let duration = end.signed_duration_since(self.started_at);
         let secs = duration.num_seconds();
 
         if secs < 60 {
@@ -130,6 +162,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_repeat_id_round_trip() {
+        assert_eq!(repeat_id("Plan: a#b", 1), "Plan: a#b");
+        assert_eq!(split_repeat_id("Plan: a#b"), ("Plan: a#b", 1));
+        assert_eq!(repeat_id("Plan: x", 3), "Plan: x#3");
+        assert_eq!(split_repeat_id("Plan: x#3"), ("Plan: x", 3));
+    }
+
     #[test]
     fn test_subagent_creation() {
         let subagent = Subagent::new(
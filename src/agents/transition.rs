@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use super::types::AgentStatus;
+
+/// A change of an agent's status kind, as observed by the monitor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTransition {
+    /// Agent id (target-pid)
+    pub agent_id: String,
+    /// Tmux target (e.g., "main:0.1")
+    pub target: String,
+    /// Status before the change
+    pub from: AgentStatus,
+    /// Status after the change
+    pub to: AgentStatus,
+    /// When the change was observed
+    pub at: DateTime<Local>,
+    /// How long the agent stayed in the previous status
+    pub duration: Duration,
+    /// State rule that produced the new status (e.g. "state_rules[2].refinements[0]")
+    pub rule: Option<String>,
+}
+
+impl StatusTransition {
+    /// Returns a one-line description, e.g. "10:32:05 Working → Idle"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} → {}",
+            self.at.format("%H:%M:%S"),
+            self.from.short_text(),
+            self.to.short_text()
+        )
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Local};
+
+use super::git::GitInfo;
 use super::subagent::Subagent;
+use super::transition::StatusTransition;
 
 /// Types of AI agents that can be monitored
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -75,6 +80,19 @@ impl ApprovalType {
         }
     }
 
+    /// Returns the name used for this type in config (`approval_type = "shell"`)
+    pub fn config_name(&self) -> &str {
+        match self {
+            ApprovalType::FileEdit => "edit",
+            ApprovalType::FileCreate => "create",
+            ApprovalType::FileDelete => "delete",
+            ApprovalType::ShellCommand => "shell",
+            ApprovalType::McpTool => "mcp",
+            ApprovalType::UserQuestion { .. } => "question",
+            ApprovalType::Other(_) => "other",
+        }
+    }
+
     /// Returns true if this is a y/n type approval
     pub fn is_yes_no(&self) -> bool {
         matches!(
@@ -137,6 +155,17 @@ impl AgentStatus {
         )
     }
 
+    /// Returns the coarse status category
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            AgentStatus::Idle { .. } => StatusKind::Idle,
+            AgentStatus::Processing { .. } => StatusKind::Processing,
+            AgentStatus::AwaitingApproval { .. } => StatusKind::AwaitingApproval,
+            AgentStatus::Error { .. } => StatusKind::Error,
+            AgentStatus::Unknown => StatusKind::Unknown,
+        }
+    }
+
     /// Returns a short status text
     pub fn short_text(&self) -> String {
         match self {
@@ -157,6 +186,70 @@ impl AgentStatus {
     }
 }
 
+/// Coarse status category, ignoring labels and activity text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    Idle,
+    Processing,
+    AwaitingApproval,
+    Error,
+    Unknown,
+}
+
+impl StatusKind {
+    /// Returns the snake_case name used in config and machine-readable output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusKind::Idle => "idle",
+            StatusKind::Processing => "processing",
+            StatusKind::AwaitingApproval => "awaiting_approval",
+            StatusKind::Error => "error",
+            StatusKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Formats a duration with seconds precision (e.g. "42s", "4m12s", "1h05m")
+pub fn format_duration_long(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Formats a duration compactly (e.g. "42s", "5m", "1h12m")
+pub fn format_duration_short(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h{}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Formats a token count compactly (e.g. "950", "12.3k", "1.2M")
+pub fn format_tokens(tokens: u64) -> String {
+    if tokens < 1_000 {
+        tokens.to_string()
+    } else if tokens < 1_000_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    }
+}
+
 impl fmt::Display for AgentStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.short_text())
@@ -176,8 +269,10 @@ pub struct MonitoredAgent {
     pub color: Option<String>,
     /// Background color theme (from config, e.g. "black")
     pub background_color: Option<String>,
-    /// Tmux target (e.g., "main:0.1")
+    /// Tmux target (e.g., "main:0.1", or "label/main:0.1" on an additional server)
     pub target: String,
+    /// Label of the tmux server (None for the default server)
+    pub server: Option<String>,
     /// Session name
     pub session: String,
     /// Window index
@@ -204,10 +299,30 @@ pub struct MonitoredAgent {
     pub active_indicators: Vec<String>,
     /// When this agent was first detected
     pub started_at: Instant,
+    /// When the current status kind was entered
+    pub status_since: Instant,
+    /// Wall-clock time when the current status kind was entered
+    pub status_changed_at: DateTime<Local>,
+    /// State rule that produced the current status, if known
+    pub status_rule: Option<String>,
+    /// Recent status transitions (oldest first, bounded)
+    pub transitions: VecDeque<StatusTransition>,
+    /// Last status with a different kind than the current one
+    pub previous_status: Option<AgentStatus>,
+    /// Accumulated time spent in each status kind (excluding the current stint)
+    pub status_durations: HashMap<StatusKind, Duration>,
     /// When the pane content was last updated
     pub last_updated: Instant,
+    /// When the pane content last changed (output activity)
+    pub last_output_at: Instant,
     /// Context remaining percentage (0-100), if detectable
     pub context_remaining: Option<u8>,
+    /// Tokens used in the session, if detectable
+    pub tokens: Option<u64>,
+    /// Session cost in dollars, if detectable
+    pub cost: Option<f64>,
+    /// Git state of the working directory (None outside a checkout or when not read)
+    pub git: Option<GitInfo>,
 }
 
 impl MonitoredAgent {
@@ -237,6 +352,7 @@ impl MonitoredAgent {
             color,
             background_color,
             target,
+            server: None,
             session,
             window,
             window_name,
@@ -250,8 +366,26 @@ impl MonitoredAgent {
             pid,
             active_indicators: Vec::new(),
             started_at: now,
+            status_since: now,
+            status_changed_at: Local::now(),
+            status_rule: None,
+            transitions: VecDeque::new(),
+            previous_status: None,
+            status_durations: HashMap::new(),
             last_updated: now,
+            last_output_at: now,
             context_remaining: None,
+            tokens: None,
+            cost: None,
+            git: None,
+        }
+    }
+
+    /// Returns the session name qualified with the server label, for tmux session commands
+    pub fn qualified_session(&self) -> String {
+        match &self.server {
+            Some(label) => format!("{}{}{}", label, crate::tmux::SERVER_SEPARATOR, self.session),
+            None => self.session.clone(),
         }
     }
 
@@ -262,13 +396,58 @@ impl MonitoredAgent {
 
     /// Returns a human-readable uptime string
     pub fn uptime_str(&self) -> String {
-        let secs = self.uptime().as_secs();
-        if secs < 60 {
-            format!("{}s", secs)
-        } else if secs < 3600 {
-            format!("{}m", secs / 60)
+        format_duration_short(self.uptime())
+    }
+
+    /// Returns how long the agent has been in its current status kind
+    pub fn time_in_status(&self) -> Duration {
+        self.status_since.elapsed()
+    }
+
+    /// Returns a human-readable time-in-status string
+    pub fn time_in_status_str(&self) -> String {
+        format_duration_short(self.time_in_status())
+    }
+
+    /// Returns a time-in-state description, e.g. "Working for 4m12s" or "Idle since 10:32"
+    pub fn status_age_text(&self) -> String {
+        match self.status.kind() {
+            StatusKind::Idle => format!("Idle since {}", self.status_changed_at.format("%H:%M")),
+            StatusKind::Processing => {
+                format!(
+                    "Working for {}",
+                    format_duration_long(self.time_in_status())
+                )
+            }
+            StatusKind::AwaitingApproval => {
+                format!(
+                    "Waiting for {}",
+                    format_duration_long(self.time_in_status())
+                )
+            }
+            StatusKind::Error => {
+                format!("Error for {}", format_duration_long(self.time_in_status()))
+            }
+            StatusKind::Unknown => {
+                format!(
+                    "Unknown for {}",
+                    format_duration_long(self.time_in_status())
+                )
+            }
+        }
+    }
+
+    /// Returns the total time spent in the given status kind, including the current stint
+    pub fn total_time_in(&self, kind: StatusKind) -> Duration {
+        let past = self
+            .status_durations
+            .get(&kind)
+            .copied()
+            .unwrap_or_default();
+        if self.status.kind() == kind {
+            past + self.time_in_status()
         } else {
-            format!("{}h{}m", secs / 3600, (secs % 3600) / 60)
+            past
         }
     }
 
@@ -284,6 +463,16 @@ impl MonitoredAgent {
         }
     }
 
+    /// Returns how long the pane output has been unchanged
+    pub fn output_age(&self) -> Duration {
+        self.last_output_at.elapsed()
+    }
+
+    /// Returns a human-readable time since the last output change
+    pub fn output_age_str(&self) -> String {
+        format_duration_short(self.output_age())
+    }
+
     /// Updates the last_updated timestamp
     pub fn touch(&mut self) {
         self.last_updated = Instant::now();
@@ -341,6 +530,31 @@ impl MonitoredAgent {
     }
 }
 
+#[cfg(test)]
+impl MonitoredAgent {
+    /// Claude agent in pane `target` ("session:window.pane") with id "{target}-{pid}"
+    pub fn test_agent(target: &str, pid: u32) -> Self {
+        let (session, rest) = target.split_once(':').unwrap_or((target, "0.0"));
+        let (window, pane) = rest.split_once('.').unwrap_or((rest, "0"));
+        Self::new(
+            format!("{}-{}", target, pid),
+            "claude".to_string(),
+            "Claude".to_string(),
+            None,
+            target.to_string(),
+            session.to_string(),
+            window.parse().unwrap_or(0),
+            "code".to_string(),
+            pane.parse().unwrap_or(0),
+            "/tmp".to_string(),
+            AgentType::Named("Claude".to_string()),
+            None,
+            pid,
+            true,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +586,14 @@ mod tests {
         .needs_attention());
     }
 
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration_short(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration_short(Duration::from_secs(252)), "4m");
+        assert_eq!(format_duration_long(Duration::from_secs(252)), "4m12s");
+        assert_eq!(format_duration_long(Duration::from_secs(3900)), "1h05m");
+    }
+
     #[test]
     fn test_monitored_agent() {
         let agent = MonitoredAgent::new(
@@ -30,6 +30,10 @@ pub enum Action {
     FocusPane,
     /// Toggle subagent log view
     ToggleSubagentLog,
+    /// Toggle the live pane mirror
+    ToggleZoom,
+    /// Start key passthrough to the current pane
+    StartPassthrough,
     /// Toggle summary detail (TODOs and Tools) view
     ToggleSummaryDetail,
     /// Toggle commands menu
@@ -66,6 +70,18 @@ pub enum Action {
     CursorEnd,
     /// Send a specific number (for choice selection)
     SendNumber(u8),
+    /// Open the choice picker for the selected agent's question
+    ShowChoicePicker,
+    /// Close the choice picker without answering
+    HideChoicePicker,
+    /// Move the choice picker cursor by the given offset
+    ChoiceMove(isize),
+    /// Toggle the highlighted choice (multi-select)
+    ChoiceToggle,
+    /// Highlight (and toggle in multi-select) a choice by index
+    ChoicePick(usize),
+    /// Answer the question with the picked choice(s)
+    ChoiceSubmit,
     /// Increase sidebar width
     SidebarWider,
     /// Decrease sidebar width
@@ -144,6 +160,26 @@ pub enum Action {
     ReloadConfig,
     /// Switch to next color theme
     NextTheme,
+    /// Recall the previous (older) input history entry
+    HistoryPrev,
+    /// Recall the next (newer) input history entry
+    HistoryNext,
+    /// Search input history
+    ShowHistorySearch,
+    /// Pick a file to attach in the editor
+    ShowFilePicker,
+    /// Pick a paste buffer or pane screen to insert in the editor
+    ShowPanePicker,
+    /// Send the last text input to the current agent again
+    ResendLast,
+    /// Edit the prompt queue of the current agent
+    EditQueue,
+    /// Run a workflow by name
+    RunWorkflow(String),
+    /// Stop all running workflows
+    CancelWorkflows,
+    /// Start a new agent from a launcher by name
+    Launch(String),
 }
 
 impl Action {
@@ -163,6 +199,8 @@ impl Action {
             Action::ApproveAll => "Approve all pending requests",
             Action::FocusPane => "Focus on selected pane in tmux",
             Action::ToggleSubagentLog => "Toggle subagent log",
+            Action::ToggleZoom => "Toggle live pane mirror",
+            Action::StartPassthrough => "Pass keys through to pane",
             Action::ToggleSummaryDetail => "Toggle TODO/Tools display",
             Action::ToggleMenu => "Toggle command menu",
             Action::TogglePrompts => "Toggle prompts menu",
@@ -181,6 +219,12 @@ impl Action {
             Action::CursorHome => "Move cursor to start",
             Action::CursorEnd => "Move cursor to end",
             Action::SendNumber(_) => "Send choice number",
+            Action::ShowChoicePicker => "Pick question choice",
+            Action::HideChoicePicker => "Close choice picker",
+            Action::ChoiceMove(_) => "Move choice cursor",
+            Action::ChoiceToggle => "Toggle choice",
+            Action::ChoicePick(_) => "Pick choice",
+            Action::ChoiceSubmit => "Answer question",
             Action::SidebarWider => "Widen sidebar",
             Action::SidebarNarrower => "Narrow sidebar",
             Action::SelectAgent(_) => "Select agent",
@@ -211,6 +255,16 @@ impl Action {
             Action::ToggleFilterSelected => "Toggle selected agents filter",
             Action::ReloadConfig => "Reload configuration",
             Action::NextTheme => "Switch to next color theme",
+            Action::HistoryPrev => "Previous history entry",
+            Action::HistoryNext => "Next history entry",
+            Action::ShowHistorySearch => "Search input history",
+            Action::ShowFilePicker => "Attach file",
+            Action::ShowPanePicker => "Insert from pane",
+            Action::ResendLast => "Resend last input",
+            Action::EditQueue => "Edit prompt queue",
+            Action::RunWorkflow(_) => "Run workflow",
+            Action::CancelWorkflows => "Stop workflows",
+            Action::Launch(_) => "Launch agent",
             Action::None => "",
         }
     }
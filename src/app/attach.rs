@@ -0,0 +1,376 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::config::AttachMode;
+use super::menu_config::{MenuConfig, MenuItem};
+use crate::tmux::TmuxBackend;
+use crate::ui::components::MenuTreeState;
+
+/// Maximum number of files offered by the file picker
+const MAX_FILES: usize = 20_000;
+
+/// Largest file inlined into a prompt
+const MAX_INLINE_BYTES: u64 = 256 * 1024;
+
+/// Directories skipped when listing files outside a git checkout
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "__pycache__"];
+
+/// Something to insert from tmux, offered by the pane picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneSnippet {
+    /// Content of a paste buffer
+    Buffer(String),
+    /// Another pane, captured once picked
+    Pane(String),
+}
+
+/// What an attach picker inserts into the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachSource {
+    /// Files under an agent's directory (item text is the relative path)
+    Files { root: PathBuf, mode: AttachMode },
+    /// Paste buffers and other panes (item text is the index of the snippet)
+    Panes(Vec<PaneSnippet>),
+    /// Lines captured from a pane (item text is the line index); `start` is the
+    /// first line of the range once picked
+    Lines {
+        target: String,
+        lines: Vec<String>,
+        start: Option<usize>,
+    },
+}
+
+/// Result of picking an item
+#[derive(Debug)]
+pub enum AttachPick {
+    /// Text to insert into the editor
+    Insert(String),
+    /// Another picker to continue with
+    Next(AttachPicker),
+}
+
+/// Fuzzy picker of files or pane snippets opened from the editor
+#[derive(Debug)]
+pub struct AttachPicker {
+    pub source: AttachSource,
+    pub menu: MenuConfig,
+    pub tree: MenuTreeState,
+}
+
+impl AttachPicker {
+    /// Picker over the files under `root`; None if there are none
+    pub fn files(root: &Path, mode: AttachMode) -> Option<Self> {
+        let items: Vec<MenuItem> = list_files(root)
+            .into_iter()
+            .map(|path| item(path.clone(), path))
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+        Some(Self::new(
+            AttachSource::Files {
+                root: root.to_path_buf(),
+                mode,
+            },
+            items,
+        ))
+    }
+
+    /// Picker over the paste buffers and all panes except `exclude`; None if
+    /// there is nothing to insert. Panes are only captured once picked.
+    pub fn panes(tmux: &dyn TmuxBackend, exclude: &str) -> Result<Option<Self>> {
+        let mut snippets = Vec::new();
+        let mut items = Vec::new();
+        for buffer in tmux.paste_buffers()? {
+            let name = format!("Buffer: {}", first_line(&buffer));
+            items.push(item(name, snippets.len().to_string()));
+            snippets.push(PaneSnippet::Buffer(buffer));
+        }
+        for pane in tmux.list_panes()? {
+            let target = pane.target();
+            if target == exclude {
+                continue;
+            }
+            let name = format!("{} {} {}", target, pane.command, pane.title);
+            items.push(item(
+                name.trim_end().to_string(),
+                snippets.len().to_string(),
+            ));
+            snippets.push(PaneSnippet::Pane(target));
+        }
+        if items.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(AttachSource::Panes(snippets), items)))
+    }
+
+    /// Picker over the captured lines of `target` from `start` on, with the
+    /// last line highlighted
+    fn lines(target: String, lines: Vec<String>, start: Option<usize>) -> Self {
+        let items: Vec<MenuItem> = lines
+            .iter()
+            .enumerate()
+            .skip(start.unwrap_or(0))
+            .map(|(i, line)| item(format!("{:>4} {}", i + 1, line), i.to_string()))
+            .collect();
+        let last = items.len().saturating_sub(1);
+        let mut picker = Self::new(
+            AttachSource::Lines {
+                target,
+                lines,
+                start,
+            },
+            items,
+        );
+        picker.tree.list_state.select(Some(last));
+        picker
+    }
+
+    fn new(source: AttachSource, items: Vec<MenuItem>) -> Self {
+        Self {
+            source,
+            menu: MenuConfig {
+                items,
+                merge_with_defaults: false,
+            },
+            tree: MenuTreeState::new(),
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.source {
+            AttachSource::Files { .. } => "Attach File",
+            AttachSource::Panes(_) => "Insert from Pane",
+            AttachSource::Lines { start: None, .. } => "First Line to Insert",
+            AttachSource::Lines { start: Some(_), .. } => "Last Line to Insert",
+        }
+    }
+
+    /// Text inserted into the editor for a picked item, or the picker of the
+    /// next step (a pane's lines, then the end of the range)
+    pub fn pick(&self, text: &str, tmux: &dyn TmuxBackend) -> Result<AttachPick> {
+        let index = || -> Result<usize> { text.parse().context("Invalid picker item") };
+        match &self.source {
+            AttachSource::Files { root, mode } => {
+                attach_file(root, text, *mode).map(AttachPick::Insert)
+            }
+            AttachSource::Panes(snippets) => match snippets.get(index()?) {
+                Some(PaneSnippet::Buffer(buffer)) => Ok(AttachPick::Insert(buffer.clone())),
+                Some(PaneSnippet::Pane(target)) => {
+                    let content = tmux.capture_pane(target)?;
+                    let lines: Vec<String> = content
+                        .trim_end()
+                        .lines()
+                        .map(|l| l.trim_end().to_string())
+                        .collect();
+                    if lines.iter().all(|l| l.is_empty()) {
+                        anyhow::bail!("{} is empty", target);
+                    }
+                    Ok(AttachPick::Next(Self::lines(target.clone(), lines, None)))
+                }
+                None => anyhow::bail!("Invalid picker item"),
+            },
+            AttachSource::Lines {
+                target,
+                lines,
+                start: None,
+            } => Ok(AttachPick::Next(Self::lines(
+                target.clone(),
+                lines.clone(),
+                Some(index()?),
+            ))),
+            AttachSource::Lines {
+                lines,
+                start: Some(start),
+                ..
+            } => {
+                let range = lines
+                    .get(*start..=index()?)
+                    .context("Invalid picker item")?;
+                Ok(AttachPick::Insert(range.join("\n")))
+            }
+        }
+    }
+}
+
+fn item(name: String, text: String) -> MenuItem {
+    MenuItem {
+        name,
+        description: None,
+        execute_command: None,
+        text: Some(text),
+        items: Vec::new(),
+    }
+}
+
+fn first_line(text: &str) -> String {
+    let mut lines = text.trim_start().lines();
+    let mut line = lines.next().unwrap_or_default().to_string();
+    if lines.next().is_some() {
+        line.push_str(" …");
+    }
+    line
+}
+
+/// Files under `root` relative to it: tracked and untracked but not ignored
+/// ones in a git checkout (as listed, without checking each one), otherwise a
+/// walk skipping hidden and build directories and not following symlinks
+pub fn list_files(root: &Path) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["ls-files", "--cached", "--others", "--exclude-standard"])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success());
+    if let Some(output) = output {
+        return String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .take(MAX_FILES)
+            .map(str::to_string)
+            .collect();
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_str()) {
+                    dirs.push(path);
+                }
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_string_lossy().into_owned());
+                if files.len() >= MAX_FILES {
+                    dirs.clear();
+                    break;
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Prompt text attaching a file: an `@path` reference, or the content in a
+/// fenced block headed by the path
+pub fn attach_file(root: &Path, path: &str, mode: AttachMode) -> Result<String> {
+    if mode == AttachMode::Reference {
+        return Ok(format!("@{} ", path));
+    }
+
+    let full = root.join(path);
+    let size = std::fs::metadata(&full)
+        .with_context(|| format!("Failed to read {}", path))?
+        .len();
+    if size > MAX_INLINE_BYTES {
+        anyhow::bail!("{} is too large to inline ({} KiB)", path, size / 1024);
+    }
+    let content = std::fs::read(&full).with_context(|| format!("Failed to read {}", path))?;
+    if content.contains(&0) {
+        anyhow::bail!("{} is a binary file", path);
+    }
+    let content = String::from_utf8_lossy(&content);
+
+    // A fence longer than any backtick run in the content
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let lang = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy())
+        .unwrap_or_default();
+    Ok(format!(
+        "{}:\n{}{}\n{}\n{}\n",
+        path,
+        fence,
+        lang,
+        content.trim_end_matches('\n'),
+        fence
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::FakeTmux;
+
+    #[test]
+    fn test_attach_files_and_pane_snippets() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("node_modules/x")).unwrap();
+        std::fs::write(tmp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(tmp.path().join("README.md"), "```sh\nls\n```\n").unwrap();
+        std::fs::write(tmp.path().join("node_modules/x/index.js"), "").unwrap();
+        std::fs::write(tmp.path().join(".env"), "SECRET=1").unwrap();
+
+        // A symlink back to the root is listed, not followed
+        std::os::unix::fs::symlink(tmp.path(), tmp.path().join("src/loop")).unwrap();
+
+        assert_eq!(
+            list_files(tmp.path()),
+            ["README.md", "src/loop", "src/main.rs"]
+        );
+        let tmux = FakeTmux::new();
+        let picker = AttachPicker::files(tmp.path(), AttachMode::Reference).unwrap();
+        let inserted = |pick: Result<AttachPick>| match pick.unwrap() {
+            AttachPick::Insert(text) => text,
+            AttachPick::Next(_) => panic!("expected text to insert"),
+        };
+        assert_eq!(inserted(picker.pick("src/main.rs", &tmux)), "@src/main.rs ");
+        assert_eq!(
+            attach_file(tmp.path(), "src/main.rs", AttachMode::Inline).unwrap(),
+            "src/main.rs:\n```rs\nfn main() {}\n```\n"
+        );
+        assert!(attach_file(tmp.path(), "README.md", AttachMode::Inline)
+            .unwrap()
+            .starts_with("README.md:\n````md\n```sh"));
+
+        tmux.add_pane("main:0.1", "claude").unwrap();
+        tmux.add_pane("main:0.2", "bash").unwrap();
+        tmux.script(
+            "main:0.2",
+            ["$ cargo test\nerror[E0308]: mismatched types\n  --> src/lib.rs\n\n"],
+        )
+        .unwrap();
+        tmux.add_buffer("selected\ntext");
+
+        let picker = AttachPicker::panes(&tmux, "main:0.1").unwrap().unwrap();
+        let names: Vec<_> = picker.menu.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["Buffer: selected …", "main:0.2 bash"]);
+        assert_eq!(inserted(picker.pick("0", &tmux)), "selected\ntext");
+
+        // A pane is captured once picked, then a range of its lines is picked
+        let next = |pick: Result<AttachPick>| match pick.unwrap() {
+            AttachPick::Next(picker) => picker,
+            AttachPick::Insert(_) => panic!("expected another picker"),
+        };
+        let lines = next(picker.pick("1", &tmux));
+        assert_eq!(lines.menu.items.len(), 3);
+        assert_eq!(lines.tree.list_state.selected(), Some(2));
+        let end = next(lines.pick("1", &tmux));
+        let names: Vec<_> = end.menu.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "   2 error[E0308]: mismatched types",
+                "   3   --> src/lib.rs"
+            ]
+        );
+        assert_eq!(
+            inserted(end.pick("2", &tmux)),
+            "error[E0308]: mismatched types\n  --> src/lib.rs"
+        );
+    }
+}
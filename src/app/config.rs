@@ -1,12 +1,13 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 
 use super::config_override::ConfigOverride;
 use super::key_binding::KeyBindings;
 use super::menu_config::MenuConfig;
 use super::session_pattern::SessionPattern;
+use crate::tmux::KeyArg;
 
 /// Notification mode for desktop notifications
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -35,6 +36,15 @@ pub struct Config {
     #[serde(default)]
     pub show_detached_sessions: bool,
 
+    /// Watch panes through tmux control mode (`tmux -C`) and only re-capture panes
+    /// that produced output, instead of capturing every pane on each poll
+    #[serde(default)]
+    pub tmux_control_mode: bool,
+
+    /// Additional tmux servers (sockets) to monitor next to the default one
+    #[serde(default)]
+    pub tmux_servers: Vec<TmuxServerConfig>,
+
     /// Enable extra logging in the TUI
     #[serde(default)]
     pub debug_mode: bool,
@@ -63,6 +73,10 @@ pub struct Config {
     #[serde(default)]
     pub popup_trigger_key: String,
 
+    /// Key ending key passthrough to the mirrored pane (default: "C-q")
+    #[serde(default)]
+    pub passthrough_exit_key: String,
+
     /// Sessions to ignore (supports fixed, glob, regex patterns)
     #[serde(default)]
     pub ignore_sessions: Vec<String>,
@@ -75,6 +89,14 @@ pub struct Config {
     #[serde(default)]
     pub hide_bottom_input: bool,
 
+    /// Delay between targets when text is sent to several selected agents (0 = all at once)
+    #[serde(default)]
+    pub broadcast_stagger_ms: u64,
+
+    /// Queue text sent to a working agent and deliver it once the agent is idle
+    #[serde(default)]
+    pub queue_when_busy: bool,
+
     /// Whether to log all actions to the status bar (default: true)
     #[serde(default)]
     pub log_actions: bool,
@@ -175,6 +197,36 @@ pub struct Config {
     #[serde(default)]
     pub notification_mode: NotificationMode,
 
+    /// Shell commands run on agent status transitions
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Rules deciding which approvals are sent automatically, need confirmation
+    /// or are never sent by tmuxx
+    #[serde(default)]
+    pub approval_policies: Vec<ApprovalPolicyRule>,
+
+    /// Audit log of actions sent to panes
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    /// Input history per agent type
+    #[serde(default)]
+    pub history: HistoryConfig,
+
+    /// Multi-step workflows run on agents (`[[workflows]]`)
+    #[serde(default)]
+    pub workflows: Vec<WorkflowConfig>,
+
+    /// Profiles for starting new agents (`[[launchers]]`)
+    #[serde(default)]
+    pub launchers: Vec<LauncherConfig>,
+
+    /// Unix socket path for the control server (`tmuxx ctl`). Disabled when unset.
+    /// A leading `~/` is expanded to the home directory.
+    #[serde(default)]
+    pub control_socket: Option<String>,
+
     /// Name of the active theme
     #[serde(default = "default_theme_name")]
     pub theme: String,
@@ -184,6 +236,286 @@ pub struct Config {
     pub themes: std::collections::HashMap<String, ThemeConfig>,
 }
 
+/// An additional tmux server to monitor (`[[tmux_servers]]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TmuxServerConfig {
+    /// Shown in the agent tree and used to qualify targets ("label/session:0.1")
+    pub label: String,
+    /// Socket name (`tmux -L NAME`)
+    #[serde(default)]
+    pub socket_name: Option<String>,
+    /// Socket path (`tmux -S PATH`), takes precedence over `socket_name`
+    #[serde(default)]
+    pub socket_path: Option<String>,
+    /// Remote host; tmux commands then run through `remote_command`
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Command template for remote hosts. Placeholders: {host}, {cmd} (both shell-escaped).
+    /// Default: ssh with BatchMode and a persistent ControlMaster connection.
+    #[serde(default)]
+    pub remote_command: Option<String>,
+}
+
+/// Append-only JSONL log of actions sent to panes (`[audit]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfig {
+    /// Write the audit log. Default: true
+    pub enabled: bool,
+    /// Log file, a leading `~/` is expanded. Default: `<state dir>/tmuxx/audit.jsonl`
+    pub path: Option<String>,
+    /// Rotate the log once it grows past this size. Default: 10 MiB
+    pub max_bytes: u64,
+    /// Number of rotated files kept (`audit.jsonl.1`, `.2`, ...). Default: 5
+    pub max_files: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+/// Persistent input history, one file per agent type (`[history]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Remember sent input across restarts. Default: true
+    pub enabled: bool,
+    /// Directory of the history files, a leading `~/` is expanded.
+    /// Default: `<state dir>/tmuxx/history`
+    pub path: Option<String>,
+    /// Entries kept per agent type. Default: 500
+    pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            max_entries: 500,
+        }
+    }
+}
+
+/// A named sequence of steps run on each selected agent (`[[workflows]]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowConfig {
+    /// Name used by the `run_workflow` key action
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStepConfig>,
+}
+
+/// One workflow step. Exactly one action must be set (`timeout_secs` qualifies
+/// `wait` and `command`, `goto` qualifies `branch` or jumps on its own).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkflowStepConfig {
+    /// Text submitted with the agent's `input` keys (prompt variables are expanded)
+    pub send: Option<String>,
+    /// Raw tmux keys, space separated (e.g. "Escape")
+    pub keys: Option<String>,
+    /// Choice number sent with the agent's `number` keys
+    pub number: Option<u8>,
+    /// Approve the pending request (honours `[[approval_policies]]`)
+    pub approve: bool,
+    pub reject: bool,
+    /// Wait for a status kind: idle, processing, awaiting_approval or error
+    pub wait: Option<String>,
+    /// Seconds before a `wait` or `command` step fails. Default: 600
+    pub timeout_secs: Option<u64>,
+    /// Shell command run in the agent's directory; a non-zero exit fails the workflow
+    pub command: Option<String>,
+    /// Message for the status bar (prompt variables are expanded)
+    pub report: Option<String>,
+    /// Regex matched against the agent's status text (e.g. an idle label)
+    pub branch: Option<String>,
+    /// Mark to continue at (with `branch`, only when it matches)
+    pub goto: Option<String>,
+    /// Names this position for `goto`
+    pub mark: Option<String>,
+    /// Ends the workflow
+    pub stop: bool,
+}
+
+/// A profile for starting a new agent from the dashboard (`[[launchers]]`).
+/// `session`, `window`, `cwd`, `worktree` and `branch` expand prompt variables of
+/// the current agent and `${N}`, the lowest number giving names not in use.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LauncherConfig {
+    /// Name used by the `launch` key action
+    pub name: String,
+    /// Shell command started in the new window (e.g. "claude --model opus")
+    pub command: String,
+    /// Working directory, a leading `~/` is expanded. Default: the current agent's
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Session of the new window, created if missing.
+    /// Default: the current agent's session, else the session tmuxx runs in
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Name of the new window
+    #[serde(default)]
+    pub window: Option<String>,
+    /// Path of a new git worktree the agent starts in, created from the repository
+    /// of `cwd` (relative paths are resolved against its top level directory)
+    #[serde(default)]
+    pub worktree: Option<String>,
+    /// Branch created for the worktree. Default: the worktree's directory name
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Extra environment variables of the agent
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Prompt sent once the agent is idle (prompt variables of the new agent are expanded)
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// Seconds to wait for the agent to become idle. Default: 120
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// How tmuxx handles an approval matched by a policy rule.
+/// Ordered from least to most restrictive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyAction {
+    /// The monitor approves the request on its own
+    Auto,
+    /// Approving from the dashboard asks for confirmation first
+    Confirm,
+    /// tmuxx never sends the approval; answer in the pane itself
+    Never,
+}
+
+impl PolicyAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyAction::Auto => "auto",
+            PolicyAction::Confirm => "confirm",
+            PolicyAction::Never => "never",
+        }
+    }
+}
+
+/// An approval policy rule (`[[approval_policies]]`).
+/// All set conditions must match; among matching rules the most restrictive action wins.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalPolicyRule {
+    /// Name shown in messages and the audit trail (default: "approval_policies[N]")
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Regex matched against the agent id (e.g. "claude")
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Approval type: edit, create, delete, shell, mcp, question or other
+    #[serde(default)]
+    pub approval_type: Option<String>,
+    /// Regex matched against the approval details (status text)
+    #[serde(default)]
+    pub details: Option<String>,
+    /// Regex matched against the bottom of the pane (where the command is shown)
+    #[serde(default)]
+    pub content: Option<String>,
+    pub action: PolicyAction,
+}
+
+/// What to do with a hook process that exceeds its timeout
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookTimeoutAction {
+    /// Send SIGTERM, then SIGKILL if it is still running after a grace period
+    #[default]
+    Term,
+    /// Send SIGKILL immediately
+    Kill,
+    /// Leave the process running
+    Detach,
+}
+
+/// Hook commands for status transitions.
+/// Placeholders: {event}, {agent}, {session}, {target}, {path}, {from}, {to}, {status},
+/// {approval_type}, {rule}, {duration}
+///
+/// Also used per agent (`[agents.hooks]`), where set fields override the global ones
+/// and an empty command disables the global hook for that agent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Agent starts waiting for approval
+    pub on_approval_needed: Option<String>,
+    /// Agent reports an error
+    pub on_error: Option<String>,
+    /// Agent becomes idle
+    pub on_idle: Option<String>,
+    /// Agent finishes work (Working → Idle)
+    pub on_done: Option<String>,
+    /// Agent starts working
+    pub on_working: Option<String>,
+    /// Any status change
+    pub on_change: Option<String>,
+    /// Minimum time between two runs of the same hook for the same agent. Default: 2000
+    pub debounce_ms: Option<u64>,
+    /// Maximum hook run time before `timeout_action` applies. Default: 30000
+    pub timeout_ms: Option<u64>,
+    /// What to do when a hook times out: "term" (default), "kill" or "detach"
+    pub timeout_action: Option<HookTimeoutAction>,
+}
+
+impl HooksConfig {
+    /// Returns a copy with the fields set in `other` taking precedence
+    pub fn overlay(&self, other: &HooksConfig) -> HooksConfig {
+        HooksConfig {
+            on_approval_needed: other
+                .on_approval_needed
+                .clone()
+                .or_else(|| self.on_approval_needed.clone()),
+            on_error: other.on_error.clone().or_else(|| self.on_error.clone()),
+            on_idle: other.on_idle.clone().or_else(|| self.on_idle.clone()),
+            on_done: other.on_done.clone().or_else(|| self.on_done.clone()),
+            on_working: other.on_working.clone().or_else(|| self.on_working.clone()),
+            on_change: other.on_change.clone().or_else(|| self.on_change.clone()),
+            debounce_ms: other.debounce_ms.or(self.debounce_ms),
+            timeout_ms: other.timeout_ms.or(self.timeout_ms),
+            timeout_action: other.timeout_action.or(self.timeout_action),
+        }
+    }
+
+    /// Returns true if no hook command is configured
+    pub fn is_empty(&self) -> bool {
+        [
+            &self.on_approval_needed,
+            &self.on_error,
+            &self.on_idle,
+            &self.on_done,
+            &self.on_working,
+            &self.on_change,
+        ]
+        .iter()
+        .all(|c| c.as_deref().is_none_or(|c| c.trim().is_empty()))
+    }
+
+    pub fn debounce(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.debounce_ms.unwrap_or(2000))
+    }
+
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.timeout_ms.unwrap_or(30000))
+    }
+}
+
 fn default_theme_name() -> String {
     "default".to_string()
 }
@@ -322,6 +654,12 @@ pub struct TimingConfig {
     /// Status hysteresis in milliseconds
     #[serde(default = "default_hysteresis")]
     pub hysteresis_ms: u64,
+    /// How often the git state of agent directories is re-read (0 = off)
+    #[serde(default = "default_git_refresh")]
+    pub git_refresh_ms: u64,
+    /// How often the zoomed pane mirror is re-captured
+    #[serde(default = "default_mirror_refresh")]
+    pub mirror_refresh_ms: u64,
 }
 
 fn default_tick_interval() -> u64 {
@@ -330,12 +668,20 @@ fn default_tick_interval() -> u64 {
 fn default_hysteresis() -> u64 {
     2000
 }
+fn default_git_refresh() -> u64 {
+    10000
+}
+fn default_mirror_refresh() -> u64 {
+    100
+}
 
 impl Default for TimingConfig {
     fn default() -> Self {
         Self {
             tick_interval_ms: default_tick_interval(),
             hysteresis_ms: default_hysteresis(),
+            git_refresh_ms: default_git_refresh(),
+            mirror_refresh_ms: default_mirror_refresh(),
         }
     }
 }
@@ -356,6 +702,8 @@ pub struct MessageConfig {
     pub label_tasks: String,
     #[serde(default = "default_label_tools")]
     pub label_tools: String,
+    #[serde(default = "default_label_history")]
+    pub label_history: String,
 
     /// Agent Tree Header Labels
     #[serde(default = "default_label_sel")]
@@ -383,6 +731,9 @@ fn default_label_tasks() -> String {
 fn default_label_tools() -> String {
     "Tools:".to_string()
 }
+fn default_label_history() -> String {
+    "History:".to_string()
+}
 fn default_label_sel() -> String {
     "sel".to_string()
 }
@@ -404,6 +755,7 @@ impl Default for MessageConfig {
             label_todo: default_label_todo(),
             label_tasks: default_label_tasks(),
             label_tools: default_label_tools(),
+            label_history: default_label_history(),
             label_sel: default_label_sel(),
             label_pending: default_label_pending(),
             label_subs: default_label_subs(),
@@ -512,14 +864,19 @@ struct PartialConfig {
     poll_interval_ms: Option<u64>,
     capture_lines: Option<u32>,
     show_detached_sessions: Option<bool>,
+    tmux_control_mode: Option<bool>,
+    tmux_servers: Option<Vec<TmuxServerConfig>>,
     debug_mode: Option<bool>,
     truncate_long_lines: Option<bool>,
     max_line_width: Option<u16>,
     key_bindings: Option<KeyBindings>,
     popup_trigger_key: Option<String>,
+    passthrough_exit_key: Option<String>,
     ignore_sessions: Option<Vec<String>>,
     ignore_self: Option<bool>,
     hide_bottom_input: Option<bool>,
+    broadcast_stagger_ms: Option<u64>,
+    queue_when_busy: Option<bool>,
     log_actions: Option<bool>,
     todo_full_width: Option<bool>,
     agents: Vec<AgentConfig>,
@@ -548,6 +905,13 @@ struct PartialConfig {
     notification_command: Option<String>,
     notification_delay_ms: Option<u64>,
     notification_mode: Option<NotificationMode>,
+    hooks: Option<HooksConfig>,
+    approval_policies: Option<Vec<ApprovalPolicyRule>>,
+    audit: Option<AuditConfig>,
+    history: Option<HistoryConfig>,
+    workflows: Option<Vec<WorkflowConfig>>,
+    launchers: Option<Vec<LauncherConfig>>,
+    control_socket: Option<String>,
     theme: Option<String>,
     #[serde(rename = "theme_override")]
     theme_overrides: Option<PartialThemeConfig>,
@@ -651,6 +1015,12 @@ impl PartialConfig {
         if let Some(v) = self.show_detached_sessions {
             config.show_detached_sessions = v;
         }
+        if let Some(v) = self.tmux_control_mode {
+            config.tmux_control_mode = v;
+        }
+        if let Some(v) = self.tmux_servers {
+            config.tmux_servers = v;
+        }
         if let Some(v) = self.debug_mode {
             config.debug_mode = v;
         }
@@ -663,6 +1033,9 @@ impl PartialConfig {
         if let Some(v) = self.popup_trigger_key {
             config.popup_trigger_key = v;
         }
+        if let Some(v) = self.passthrough_exit_key {
+            config.passthrough_exit_key = v;
+        }
         if let Some(v) = self.ignore_sessions {
             config.ignore_sessions = v;
         }
@@ -672,6 +1045,12 @@ impl PartialConfig {
         if let Some(v) = self.hide_bottom_input {
             config.hide_bottom_input = v;
         }
+        if let Some(v) = self.broadcast_stagger_ms {
+            config.broadcast_stagger_ms = v;
+        }
+        if let Some(v) = self.queue_when_busy {
+            config.queue_when_busy = v;
+        }
         if let Some(v) = self.log_actions {
             config.log_actions = v;
         }
@@ -755,6 +1134,27 @@ impl PartialConfig {
         if let Some(v) = self.notification_mode {
             config.notification_mode = v;
         }
+        if let Some(v) = self.hooks {
+            config.hooks = config.hooks.overlay(&v);
+        }
+        if let Some(v) = self.approval_policies {
+            config.approval_policies = v;
+        }
+        if let Some(v) = self.audit {
+            config.audit = v;
+        }
+        if let Some(v) = self.history {
+            config.history = v;
+        }
+        if let Some(v) = self.workflows {
+            config.workflows = v;
+        }
+        if let Some(v) = self.launchers {
+            config.launchers = v;
+        }
+        if let Some(v) = self.control_socket {
+            config.control_socket = Some(v);
+        }
         if let Some(v) = self.theme {
             config.theme = v;
         }
@@ -768,10 +1168,7 @@ impl PartialConfig {
 
         if let Some(v) = self.themes {
             for (name, partial_theme) in v {
-                let theme = config
-                    .themes
-                    .entry(name)
-                    .or_insert_with(ThemeConfig::default);
+                let theme = config.themes.entry(name).or_default();
                 partial_theme.apply(theme);
             }
         }
@@ -928,6 +1325,10 @@ pub struct AgentConfig {
     #[serde(default)]
     pub summary_rules: Option<SummaryRules>,
 
+    /// Regexes extracting context, token and cost figures from the pane
+    #[serde(default)]
+    pub metrics_rules: Option<MetricsRules>,
+
     /// Rules for syntax highlighting in detailed preview
     #[serde(default)]
     pub highlight_rules: Vec<HighlightRule>,
@@ -935,6 +1336,30 @@ pub struct AgentConfig {
     /// Key bindings
     #[serde(default)]
     pub keys: AgentKeys,
+
+    /// Per-agent hook overrides (see `HooksConfig`)
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
+    /// Report a processing agent as stalled (error) when its pane output
+    /// has not changed for this many seconds
+    #[serde(default)]
+    pub stalled_after_secs: Option<u64>,
+
+    /// How files picked in the editor are added to a prompt
+    #[serde(default)]
+    pub attach: AttachMode,
+}
+
+/// How an attached file is inserted into a prompt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachMode {
+    /// `@path` reference the agent resolves itself
+    #[default]
+    Reference,
+    /// The file content in a fenced code block
+    Inline,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -945,6 +1370,21 @@ pub struct SummaryRules {
     pub tool_use: Option<String>,
 }
 
+/// Regexes for usage figures; the last match in the pane wins and the value is
+/// taken from the capture groups
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsRules {
+    /// Context remaining in percent
+    pub context_remaining: Option<String>,
+    /// Context used in percent (reported as 100 - used), for agents showing usage
+    pub context_used: Option<String>,
+    /// Tokens used; numbers may have a k/M suffix and all groups are summed (e.g. input + output)
+    pub tokens: Option<String>,
+    /// Session cost in dollars
+    pub cost: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HighlightRule {
     pub pattern: String,
@@ -1001,6 +1441,31 @@ pub struct AgentKeys {
     /// Template for text input - {input} replaced with text (e.g., ["{input}", "Enter"])
     #[serde(default = "default_input_keys")]
     pub input: Vec<String>,
+
+    /// Template for toggling a multi-select choice - {n} replaced with number
+    #[serde(default = "default_toggle_keys")]
+    pub toggle: Vec<String>,
+
+    /// Keys confirming a multi-select question after toggling
+    #[serde(default = "default_submit_keys")]
+    pub submit: Vec<String>,
+
+    /// How the text of `{input}` entries reaches the pane
+    #[serde(default)]
+    pub delivery: InputDelivery,
+}
+
+/// Delivery of input text to a pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputDelivery {
+    /// Passed to `send-keys` like the other keys (words such as "Enter" are key names)
+    #[default]
+    Keys,
+    /// Typed literally with `send-keys -l`
+    Literal,
+    /// Pasted from a tmux buffer with `paste-buffer -p`, so newlines do not submit
+    BracketedPaste,
 }
 
 fn default_approve_keys() -> Vec<String> {
@@ -1019,20 +1484,66 @@ fn default_input_keys() -> Vec<String> {
     vec!["{input}".into(), "Enter".into()]
 }
 
+fn default_toggle_keys() -> Vec<String> {
+    vec!["{n}".into()]
+}
+
+fn default_submit_keys() -> Vec<String> {
+    vec!["Enter".into()]
+}
+
 impl AgentKeys {
     /// Expand number template, replacing {n} with the actual number
-    pub fn expand_number(&self, num: u8) -> Vec<String> {
+    pub fn expand_number(&self, num: u8) -> Vec<KeyArg> {
         self.number
             .iter()
-            .map(|k| k.replace("{n}", &num.to_string()))
+            .map(|k| KeyArg::key(k.replace("{n}", &num.to_string())))
             .collect()
     }
 
     /// Expand input template, replacing {input} with the actual text
-    pub fn expand_input(&self, text: &str) -> Vec<String> {
+    /// (typed or pasted according to `delivery`)
+    pub fn expand_input(&self, text: &str) -> Vec<KeyArg> {
         self.input
             .iter()
-            .map(|k| k.replace("{input}", text))
+            .filter_map(|k| {
+                if !k.contains("{input}") {
+                    return Some(KeyArg::key(k.as_str()));
+                }
+                self.deliver_text(&k.replace("{input}", text))
+            })
+            .collect()
+    }
+
+    /// Key-list entry carrying `text` as `delivery` says (None for empty text
+    /// that would only be typed or pasted)
+    pub fn deliver_text(&self, text: &str) -> Option<KeyArg> {
+        match self.delivery {
+            InputDelivery::Keys => Some(KeyArg::key(text)),
+            _ if text.is_empty() => None,
+            InputDelivery::Literal => Some(KeyArg::literal(text)),
+            InputDelivery::BracketedPaste => Some(KeyArg::paste(text)),
+        }
+    }
+
+    /// Keys answering a question with the picked choices (numbered from 1).
+    /// Single select sends the number template for the first pick; multi-select
+    /// toggles every pick and then submits.
+    pub fn expand_choices(&self, picked: &[u8], multi_select: bool) -> Vec<KeyArg> {
+        if !multi_select {
+            return picked
+                .first()
+                .map(|&n| self.expand_number(n))
+                .unwrap_or_default();
+        }
+        picked
+            .iter()
+            .flat_map(|n| {
+                self.toggle
+                    .iter()
+                    .map(|k| KeyArg::key(k.replace("{n}", &n.to_string())))
+            })
+            .chain(KeyArg::names(&self.submit))
             .collect()
     }
 }
@@ -1044,6 +1555,9 @@ impl Default for AgentKeys {
             reject: default_reject_keys(),
             number: default_number_keys(),
             input: default_input_keys(),
+            toggle: default_toggle_keys(),
+            submit: default_submit_keys(),
+            delivery: InputDelivery::default(),
         }
     }
 }
@@ -1067,6 +1581,21 @@ pub struct StateRule {
     /// Refine the status based on capture groups in the pattern
     #[serde(default)]
     pub refinements: Vec<Refinement>,
+    /// Extract menu choices (approval rules become `UserQuestion`)
+    #[serde(default)]
+    pub choices: Option<ChoiceRule>,
+}
+
+/// Extracts a numbered choice menu from the pane content.
+///
+/// `pattern` is matched per line and needs a `label` group; with a `number` group
+/// only the last menu (numbered from 1) is used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceRule {
+    pub pattern: String,
+    /// Marks the menu as multi-select if it matches any choice line (e.g. `\[ \]`)
+    #[serde(default)]
+    pub multi_select: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -1088,13 +1617,22 @@ pub struct Refinement {
     pub approval_type: Option<String>,
     /// Where to apply pattern: "anywhere" (default), "last_line", "last_block"
     pub location: Option<String>,
+    /// Extract menu choices when this refinement matches (overrides the rule's)
+    #[serde(default)]
+    pub choices: Option<ChoiceRule>,
 }
 
+/// Regexes for the subagent lifecycle. Group 1 is the subagent type; `start`
+/// also captures the description (group 2). `running`, `complete` and `failed`
+/// apply to the oldest unfinished subagent of that type started above them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubagentRules {
     pub start: String,
+    /// Also tracks subagents whose start line scrolled out (group 2: activity)
     pub running: String,
     pub complete: String,
+    #[serde(default)]
+    pub failed: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1111,6 +1649,10 @@ pub struct PaneTreeConfig {
     #[serde(default)]
     pub header_template: String,
 
+    /// Header shown above each tmux server's sessions when several servers are monitored
+    #[serde(default = "default_server_header_template")]
+    pub server_header_template: String,
+
     #[serde(default = "default_header_fg")]
     pub session_header_fg_color: String,
 
@@ -1122,6 +1664,10 @@ fn default_pane_tree_mode() -> String {
     "full".to_string()
 }
 
+fn default_server_header_template() -> String {
+    "◆ {server}".to_string()
+}
+
 fn default_header_fg() -> String {
     "cyan".to_string()
 }
@@ -1131,8 +1677,9 @@ impl Default for PaneTreeConfig {
         Self {
             mode: "full".to_string(),
             compact_template: "  {selection}{window_id}:{window_name} │ {status_char} {name} {status_text}".to_string(),
-            full_template: "  {selection}{status_char} {name}\n    {status_text} | pid:{pid} | {uptime}\n    {path} {context}\n{subagents}".to_string(),
+            full_template: "  {selection}{status_char} {name}\n    {status_text} {status_time} | pid:{pid} | {uptime}\n    {path} {context}\n{subagents}".to_string(),
             header_template: " ▼ {session}".to_string(),
+            server_header_template: default_server_header_template(),
             session_header_fg_color: "cyan".to_string(),
             session_header_bg_color: Some("darkgray".to_string()),
         }
@@ -1316,6 +1863,44 @@ impl Config {
         dirs::config_dir().map(|p| p.join("tmuxx").join("config.toml"))
     }
 
+    /// Returns the input history directory (None if history is disabled)
+    pub fn history_dir(&self) -> Option<PathBuf> {
+        if !self.history.enabled {
+            return None;
+        }
+        match self.history.path.as_deref().filter(|p| !p.is_empty()) {
+            Some(path) => match path.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+                None => Some(PathBuf::from(path)),
+            },
+            None => dirs::state_dir()
+                .or_else(dirs::data_local_dir)
+                .map(|dir| dir.join("tmuxx").join("history")),
+        }
+    }
+
+    /// Returns the audit log path (configured, or in the user's state directory)
+    pub fn audit_log_path(&self) -> Option<PathBuf> {
+        match self.audit.path.as_deref().filter(|p| !p.is_empty()) {
+            Some(path) => match path.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+                None => Some(PathBuf::from(path)),
+            },
+            None => dirs::state_dir()
+                .or_else(dirs::data_local_dir)
+                .map(|dir| dir.join("tmuxx").join("audit.jsonl")),
+        }
+    }
+
+    /// Returns the control socket path with a leading `~/` expanded
+    pub fn control_socket_path(&self) -> Option<PathBuf> {
+        let path = self.control_socket.as_deref().filter(|p| !p.is_empty())?;
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(path)),
+        }
+    }
+
     /// Loads config from the default path or returns defaults
     ///
     /// # Panics
@@ -1499,13 +2084,15 @@ mod tests {
 
     #[test]
     fn test_should_ignore_session_patterns() {
-        let mut config = Config::default();
-        config.ignore_self = false; // Disable to test patterns only
-        config.ignore_sessions = vec![
-            "prod-*".to_string(),       // glob
-            "/^vpn-\\d+$/".to_string(), // regex
-            "ssh-tunnel".to_string(),   // fixed
-        ];
+        let config = Config {
+            ignore_self: false, // Disable to test patterns only
+            ignore_sessions: vec![
+                "prod-*".to_string(),       // glob
+                "/^vpn-\\d+$/".to_string(), // regex
+                "ssh-tunnel".to_string(),   // fixed
+            ],
+            ..Default::default()
+        };
 
         // Fixed match
         assert!(config.should_ignore_session("ssh-tunnel", None));
@@ -1527,9 +2114,11 @@ mod tests {
 
     #[test]
     fn test_should_ignore_session_combined() {
-        let mut config = Config::default();
-        config.ignore_self = true;
-        config.ignore_sessions = vec!["test-*".to_string()];
+        let config = Config {
+            ignore_self: true,
+            ignore_sessions: vec!["test-*".to_string()],
+            ..Default::default()
+        };
 
         // Both ignore_self and patterns work together
         assert!(config.should_ignore_session("tmuxx", Some("tmuxx"))); // ignore_self
@@ -1564,11 +2153,11 @@ mod tests {
 
         // Default template with Enter
         let expanded = keys.expand_number(5);
-        assert_eq!(expanded, vec!["5", "Enter"]);
+        assert_eq!(expanded, KeyArg::names(&["5", "Enter"]));
 
         // Test single digit
         let expanded = keys.expand_number(0);
-        assert_eq!(expanded, vec!["0", "Enter"]);
+        assert_eq!(expanded, KeyArg::names(&["0", "Enter"]));
 
         // Custom template without Enter (Claude style)
         let claude_keys = AgentKeys {
@@ -1576,7 +2165,7 @@ mod tests {
             ..Default::default()
         };
         let expanded = claude_keys.expand_number(3);
-        assert_eq!(expanded, vec!["3"]);
+        assert_eq!(expanded, KeyArg::names(&["3"]));
     }
 
     #[test]
@@ -1585,15 +2174,50 @@ mod tests {
 
         // Simple text
         let expanded = keys.expand_input("hello");
-        assert_eq!(expanded, vec!["hello", "Enter"]);
+        assert_eq!(expanded, KeyArg::names(&["hello", "Enter"]));
 
         // Text with spaces
         let expanded = keys.expand_input("hello world");
-        assert_eq!(expanded, vec!["hello world", "Enter"]);
+        assert_eq!(expanded, KeyArg::names(&["hello world", "Enter"]));
 
         // Empty text
         let expanded = keys.expand_input("");
-        assert_eq!(expanded, vec!["", "Enter"]);
+        assert_eq!(expanded, KeyArg::names(&["", "Enter"]));
+    }
+
+    #[test]
+    fn test_agent_keys_input_delivery() {
+        let keys: AgentKeys = toml::from_str(r#"delivery = "bracketed_paste""#).unwrap();
+        let expanded = keys.expand_input("line one\nEnter");
+        assert_eq!(
+            expanded,
+            vec![KeyArg::paste("line one\nEnter"), KeyArg::key("Enter")]
+        );
+
+        let keys = AgentKeys {
+            delivery: InputDelivery::Literal,
+            ..Default::default()
+        };
+        let expanded = keys.expand_input("C-c");
+        assert_eq!(expanded[0], KeyArg::literal("C-c"));
+        // Nothing to type, just submit
+        assert_eq!(keys.expand_input(""), KeyArg::names(&["Enter"]));
+    }
+
+    #[test]
+    fn test_agent_keys_expand_choices() {
+        let keys = AgentKeys {
+            number: vec!["{n}".into()],
+            submit: vec!["Right".into(), "Enter".into()],
+            ..Default::default()
+        };
+
+        assert_eq!(keys.expand_choices(&[2], false), KeyArg::names(&["2"]));
+        assert_eq!(
+            keys.expand_choices(&[1, 3], true),
+            KeyArg::names(&["1", "3", "Right", "Enter"])
+        );
+        assert!(keys.expand_choices(&[], false).is_empty());
     }
 
     #[test]
@@ -11,6 +11,7 @@ pub enum ConfigOverride {
     PollInterval(u64),
     CaptureLines(u32),
     ShowDetachedSessions(bool),
+    TmuxControlMode(bool),
     DebugMode(bool),
     TruncateLongLines(bool),
     MaxLineWidth(Option<u16>),
@@ -24,6 +25,7 @@ pub enum ConfigOverride {
     NotificationCommand(Option<String>),
     NotificationDelayMs(u64),
     NotificationMode(NotificationMode),
+    ControlSocket(Option<String>),
 }
 
 impl ConfigOverride {
@@ -62,6 +64,14 @@ impl ConfigOverride {
                     ))?;
                 Ok(ConfigOverride::ShowDetachedSessions(val))
             }
+            "tmuxcontrolmode" | "controlmode" => {
+                let val = parse_bool(value)
+                    .ok_or_else(|| anyhow!(
+                        "Invalid value for tmux_control_mode: '{}'. Expected: true/false, 1/0, yes/no, on/off",
+                        value
+                    ))?;
+                Ok(ConfigOverride::TmuxControlMode(val))
+            }
             "debugmode" | "debug" => {
                 let val = parse_bool(value)
                     .ok_or_else(|| anyhow!(
@@ -168,8 +178,16 @@ impl ConfigOverride {
                 };
                 Ok(ConfigOverride::NotificationMode(mode))
             }
+            "controlsocket" | "socket" => {
+                let val = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.to_string())
+                };
+                Ok(ConfigOverride::ControlSocket(val))
+            }
             _ => Err(anyhow!(
-                "Unknown config key: '{}'. Valid keys: poll_interval_ms, capture_lines, show_detached_sessions, debug_mode, truncate_long_lines, max_line_width, popup_trigger_key, ignore_sessions, ignore_self, log_actions, sidebar_width, terminal_wrapper, notification_command, notification_delay_ms, notification_mode, keybindings.KEY (or kb.KEY)",
+                "Unknown config key: '{}'. Valid keys: poll_interval_ms, capture_lines, show_detached_sessions, tmux_control_mode, debug_mode, truncate_long_lines, max_line_width, popup_trigger_key, ignore_sessions, ignore_self, log_actions, sidebar_width, terminal_wrapper, notification_command, notification_delay_ms, notification_mode, control_socket, keybindings.KEY (or kb.KEY)",
                 key
             )),
         }
@@ -181,6 +199,7 @@ impl ConfigOverride {
             ConfigOverride::PollInterval(val) => config.poll_interval_ms = val,
             ConfigOverride::CaptureLines(val) => config.capture_lines = val,
             ConfigOverride::ShowDetachedSessions(val) => config.show_detached_sessions = val,
+            ConfigOverride::TmuxControlMode(val) => config.tmux_control_mode = val,
             ConfigOverride::DebugMode(val) => config.debug_mode = val,
             ConfigOverride::TruncateLongLines(val) => config.truncate_long_lines = val,
             ConfigOverride::MaxLineWidth(val) => config.max_line_width = val,
@@ -196,6 +215,7 @@ impl ConfigOverride {
             ConfigOverride::NotificationCommand(val) => config.notification_command = val,
             ConfigOverride::NotificationDelayMs(val) => config.notification_delay_ms = val,
             ConfigOverride::NotificationMode(val) => config.notification_mode = val,
+            ConfigOverride::ControlSocket(val) => config.control_socket = val,
         }
     }
 }
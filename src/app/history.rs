@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Input history per agent type, stored as one JSON string per line in
+/// `<dir>/<agent type>.jsonl` (no files are written without a directory)
+#[derive(Debug)]
+pub struct InputHistory {
+    dir: Option<PathBuf>,
+    max_entries: usize,
+    /// Loaded entries per agent type, oldest first
+    entries: HashMap<String, Vec<String>>,
+}
+
+impl InputHistory {
+    pub fn new(dir: Option<PathBuf>, max_entries: usize) -> Self {
+        Self {
+            dir,
+            max_entries,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn file(&self, agent_type: &str) -> Option<PathBuf> {
+        let name: String = agent_type
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.jsonl", name)))
+    }
+
+    /// Entries of an agent type, oldest first (loaded from disk on first use)
+    pub fn entries(&mut self, agent_type: &str) -> &[String] {
+        if !self.entries.contains_key(agent_type) {
+            let loaded = self
+                .file(agent_type)
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|content| {
+                    content
+                        .lines()
+                        .filter_map(|line| serde_json::from_str::<String>(line).ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.entries.insert(agent_type.to_string(), loaded);
+        }
+        &self.entries[agent_type]
+    }
+
+    /// Adds an entry (moving a repeated one to the end) and saves the file
+    pub fn push(&mut self, agent_type: &str, text: &str) -> Result<()> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        self.entries(agent_type);
+        let max_entries = self.max_entries;
+        let entries = self
+            .entries
+            .get_mut(agent_type)
+            .expect("history entries are loaded");
+        entries.retain(|e| e != text);
+        entries.push(text.to_string());
+        let excess = entries.len().saturating_sub(max_entries);
+        entries.drain(..excess);
+
+        let Some(path) = self.file(agent_type) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut content = Vec::new();
+        for entry in &self.entries[agent_type] {
+            writeln!(content, "{}", serde_json::to_string(entry)?)?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write history {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_persists_per_agent_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = InputHistory::new(Some(dir.path().to_path_buf()), 3);
+        for text in [
+            "run the tests",
+            "fix it\nplease",
+            "commit",
+            "run the tests",
+            "push",
+        ] {
+            history.push("claude", text).unwrap();
+        }
+        history.push("pi", "hello").unwrap();
+        history.push("claude", "  ").unwrap();
+
+        // Reloaded from disk: newest last, duplicates moved, trimmed to max_entries
+        let mut reloaded = InputHistory::new(Some(dir.path().to_path_buf()), 3);
+        assert_eq!(
+            reloaded.entries("claude"),
+            ["commit", "run the tests", "push"]
+        );
+        assert_eq!(reloaded.entries("pi"), ["hello"]);
+        assert!(reloaded.entries("gemini").is_empty());
+    }
+}
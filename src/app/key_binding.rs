@@ -38,6 +38,8 @@ pub enum KeyAction {
     ApproveAll,
     /// Send a number choice (0-9)
     SendNumber(u8),
+    /// Open the choice picker for a question
+    PickChoice,
     /// Send raw text/keys to tmux pane
     SendKeys(String),
     /// Kill the application in target pane
@@ -58,6 +60,10 @@ pub enum KeyAction {
     TogglePrompts,
     /// Toggle subagent log display
     ToggleSubagentLog,
+    /// Toggle the live mirror of the current pane in the preview
+    ToggleZoom,
+    /// Forward keys to the current pane (zooming it) until the exit key
+    Passthrough,
     /// Toggle pane tree mode
     TogglePaneTreeMode,
     /// Toggle filter for active agents (non-idle)
@@ -68,6 +74,16 @@ pub enum KeyAction {
     ReloadConfig,
     /// Switch to next color theme
     NextTheme,
+    /// Send the last text input to the current agent again
+    ResendLast,
+    /// Edit the prompt queue of the current agent
+    EditQueue,
+    /// Run a `[[workflows]]` entry (by name) on the current/selected agent(s)
+    RunWorkflow(String),
+    /// Stop all running workflows
+    CancelWorkflows,
+    /// Start a new agent from a `[[launchers]]` entry (by name)
+    Launch(String),
 }
 
 /// Configuration for command execution
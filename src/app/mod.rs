@@ -1,13 +1,20 @@
 mod actions;
+mod attach;
 pub mod config;
 mod config_override;
+pub mod history;
 pub mod key_binding;
 pub mod menu_config;
 mod session_pattern;
 mod state;
+pub mod template;
 
 pub use actions::Action;
+pub use attach::{AttachPick, AttachPicker, AttachSource, PaneSnippet};
 pub use config::Config;
 pub use key_binding::{KeyAction, KeyBindings, KillMethod, NavAction};
 pub use session_pattern::SessionPattern;
-pub use state::{AgentTree, AppState, FocusedPanel, MessageKind, PopupInputState, PopupType};
+pub use state::{
+    AgentTree, AppState, ApprovalPlan, Broadcast, ChoicePickerState, FocusedPanel, HistoryRecall,
+    HistorySearch, InputField, MessageKind, PendingSend, PopupInputState, PopupType,
+};
@@ -1,15 +1,26 @@
-use crate::agents::MonitoredAgent;
-use crate::monitor::SystemStats;
+use crate::agents::{AgentStatus, ApprovalType, MonitoredAgent, StatusTransition};
+use crate::app::config::PolicyAction;
+use crate::app::history::InputHistory;
+use crate::app::menu_config::{MenuConfig, MenuItem};
+use crate::audit::{AuditAction, AuditEntry, AuditLog, AuditSource};
+use crate::monitor::{ApprovalPolicy, PolicyDecision, SystemStats};
+use crate::tmux::{KeyArg, TmuxBackend};
 use crate::ui::components::{MenuTreeState, ModalTextareaState};
-use crate::ui::Styles;
+use crate::ui::{PaneMirror, Styles};
+use crate::workflow::{PendingLaunch, WorkflowRun};
 // use ratatui::style::{Color, Style};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
 use std::sync::OnceLock;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use super::attach::{AttachPick, AttachPicker};
 use super::config::{AgentKeys, SidebarWidth};
 use super::Config;
 
+/// Maximum number of auto-approval decisions kept for display
+pub const POLICY_DECISION_LIMIT: usize = 50;
+
 /// Static default keys for agents without explicit config
 static DEFAULT_KEYS: OnceLock<AgentKeys> = OnceLock::new();
 
@@ -33,7 +44,7 @@ pub enum PopupType {
     GeneralInput,
     /// Rename a tmux session
     RenameSession {
-        /// The current session name to rename
+        /// The current session name to rename (server-qualified)
         session: String,
     },
     /// Capture test case with expected status
@@ -43,9 +54,26 @@ pub enum PopupType {
     },
     /// Confirmation dialog for killing session
     KillConfirmation {
-        /// The session name to kill
+        /// The session name to kill (server-qualified)
         session: String,
     },
+    /// Confirmation dialog for approvals covered by a `confirm` policy
+    ApproveConfirmation {
+        /// Agent ids to approve once confirmed
+        agent_ids: Vec<String>,
+    },
+    /// Value for a `{{ask:Label}}` placeholder of a prompt
+    PromptParameter {
+        /// Prompt name and template
+        name: String,
+        text: String,
+        /// Labels still to ask, the current one first
+        labels: Vec<String>,
+        /// Values entered so far (label, value)
+        values: Vec<(String, String)>,
+        /// Open the filled prompt in the editor instead of sending it
+        edit: bool,
+    },
 }
 
 /// State for popup input dialog
@@ -63,6 +91,148 @@ pub struct PopupInputState {
     pub popup_type: PopupType,
 }
 
+/// State of the choice picker for an agent's question
+#[derive(Debug, Clone)]
+pub struct ChoicePickerState {
+    /// ID of the agent asking the question
+    pub agent_id: String,
+    /// Target of the agent's pane
+    pub target: String,
+    /// Choice labels, numbered from 1 in the agent's menu
+    pub choices: Vec<String>,
+    /// Whether several choices can be toggled
+    pub multi_select: bool,
+    /// Highlighted choice (index into choices)
+    pub cursor: usize,
+    /// Toggled choices (multi-select only)
+    pub marked: BTreeSet<usize>,
+}
+
+impl ChoicePickerState {
+    /// Moves the cursor by delta, clamped to the list
+    pub fn move_cursor(&mut self, delta: isize) {
+        let last = self.choices.len().saturating_sub(1) as isize;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+    }
+
+    /// Moves the cursor to a choice, toggling it in multi-select questions
+    pub fn pick(&mut self, index: usize) {
+        if index >= self.choices.len() {
+            return;
+        }
+        self.cursor = index;
+        if self.multi_select {
+            self.toggle();
+        }
+    }
+
+    /// Toggles the highlighted choice (multi-select only)
+    pub fn toggle(&mut self) {
+        if self.multi_select && !self.marked.remove(&self.cursor) {
+            self.marked.insert(self.cursor);
+        }
+    }
+
+    /// Choice numbers (from 1) to answer with: the toggled choices, or the
+    /// highlighted one if nothing is toggled
+    pub fn picked_numbers(&self) -> Vec<u8> {
+        let picked: Vec<usize> = if self.multi_select && !self.marked.is_empty() {
+            self.marked.iter().copied().collect()
+        } else {
+            vec![self.cursor]
+        };
+        picked
+            .into_iter()
+            .filter_map(|i| u8::try_from(i + 1).ok())
+            .collect()
+    }
+}
+
+/// Keys waiting to be sent to one agent of a broadcast
+#[derive(Debug, Clone)]
+pub struct PendingSend {
+    pub target: String,
+    /// Id of the agent at `target` when the send was queued
+    pub agent_id: String,
+    pub keys: Vec<KeyArg>,
+    /// When the keys are due (spaced by `broadcast_stagger_ms`)
+    pub due: Instant,
+    /// What is sent (e.g. a prompt name), for the audit log
+    pub label: Option<String>,
+}
+
+/// Text input being sent to one or more agents
+#[derive(Debug, Clone, Default)]
+pub struct Broadcast {
+    /// Sends not yet made, in due order
+    pub pending: VecDeque<PendingSend>,
+    /// Number of targets
+    pub total: usize,
+    /// Targets whose send failed, with the error
+    pub failures: Vec<(String, String)>,
+    /// What is sent, for the final report (None if the queued sends differ)
+    pub label: Option<String>,
+}
+
+/// Text field that input history is recalled into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputField {
+    /// Bottom input panel
+    Bottom,
+    /// General input popup
+    Popup,
+    /// Editable modal textarea
+    Modal,
+}
+
+/// Position while stepping through input history with Up/Down
+#[derive(Debug, Clone)]
+pub struct HistoryRecall {
+    pub field: InputField,
+    /// Entries of the agent type, oldest first
+    pub entries: Vec<String>,
+    /// Entry shown in the field (`entries.len()` = the draft)
+    pub index: usize,
+    /// Text typed before recalling
+    pub draft: String,
+}
+
+/// Ctrl-R search through input history
+#[derive(Debug)]
+pub struct HistorySearch {
+    /// Field the picked entry is inserted into
+    pub field: InputField,
+    /// Entries as menu items, newest first
+    pub menu: MenuConfig,
+    pub tree: MenuTreeState,
+}
+
+/// Returns the choices of an agent's pending question
+fn question_choices(agent: &MonitoredAgent) -> Option<(&[String], bool)> {
+    match &agent.status {
+        AgentStatus::AwaitingApproval {
+            approval_type:
+                ApprovalType::UserQuestion {
+                    choices,
+                    multi_select,
+                },
+            ..
+        } if !choices.is_empty() => Some((choices.as_slice(), *multi_select)),
+        _ => None,
+    }
+}
+
+/// Agents to approve, split by approval policy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovalPlan {
+    /// Agent indices to approve right away
+    pub approve: Vec<usize>,
+    /// Agent indices needing confirmation, with the deciding rule
+    pub confirm: Vec<(usize, String)>,
+    /// Agent indices tmuxx must not approve, with the deciding rule
+    pub blocked: Vec<(usize, String)>,
+}
+
 /// Tree structure containing all monitored agents
 #[derive(Debug, Clone, Default)]
 pub struct AgentTree {
@@ -78,9 +248,12 @@ impl AgentTree {
         }
     }
 
-    /// Returns the total number of agents (including subagents)
+    /// Returns the total number of agents (including running subagents)
     pub fn total_count(&self) -> usize {
-        self.root_agents.iter().map(|a| 1 + a.subagents.len()).sum()
+        self.root_agents
+            .iter()
+            .map(|a| 1 + a.active_subagent_count())
+            .sum()
     }
 
     /// Returns the number of AI agents
@@ -101,6 +274,21 @@ impl AgentTree {
             .count()
     }
 
+    /// Returns the summed session cost and tokens of agents reporting them
+    pub fn usage_totals(&self) -> (Option<f64>, Option<u64>) {
+        let cost = self
+            .root_agents
+            .iter()
+            .filter_map(|a| a.cost)
+            .reduce(|a, b| a + b);
+        let tokens = self
+            .root_agents
+            .iter()
+            .filter_map(|a| a.tokens)
+            .reduce(|a, b| a + b);
+        (cost, tokens)
+    }
+
     /// Returns the total number of running subagents
     pub fn running_subagent_count(&self) -> usize {
         use crate::agents::SubagentStatus;
@@ -186,6 +374,37 @@ pub struct AppState {
     pub popup_input: Option<PopupInputState>,
     /// Modal textarea dialog state (None = not shown)
     pub modal_textarea: Option<ModalTextareaState>,
+    /// Choice picker for a question (None = not shown)
+    pub choice_picker: Option<ChoicePickerState>,
+    /// Compiled `[[approval_policies]]`
+    pub approval_policy: ApprovalPolicy,
+    /// Recent auto-approval decisions (oldest first, bounded by POLICY_DECISION_LIMIT)
+    pub policy_decisions: VecDeque<PolicyDecision>,
+    /// Text input in progress to the selected agents (None = idle)
+    pub broadcast: Option<Broadcast>,
+    /// Audit log of actions sent to panes (None = disabled)
+    pub audit_log: Option<AuditLog>,
+    /// Sent input per agent type
+    pub input_history: InputHistory,
+    /// Up/Down history recall in progress
+    pub history_recall: Option<HistoryRecall>,
+    /// Ctrl-R history search (None = not shown)
+    pub history_search: Option<HistorySearch>,
+    /// Ctrl-O / Ctrl-G picker inserting files or pane content into the editor
+    pub attach_picker: Option<AttachPicker>,
+    /// Live mirror of the current pane in the preview (None = normal preview)
+    pub mirror: Option<PaneMirror>,
+    /// Keys last sent as text input, by agent id (for resend)
+    pub last_sent: HashMap<String, Vec<KeyArg>>,
+    /// Prompts waiting for working agents to become idle, by agent id (oldest first).
+    /// Keyed by id so a new process in the same pane does not inherit the queue.
+    pub prompt_queues: HashMap<String, VecDeque<String>>,
+    /// Agent whose prompt queue is open in the modal editor
+    pub queue_editor: Option<String>,
+    /// Workflows in progress, one per agent
+    pub workflow_runs: Vec<WorkflowRun>,
+    /// Launched agents waiting to become idle
+    pub pending_launches: Vec<PendingLaunch>,
     /// Current filter pattern (None = no filter, Some("") = show all, Some("text") = filter)
     pub filter_pattern: Option<String>,
     /// Whether subagent log is shown
@@ -250,6 +469,9 @@ impl AppState {
             .cloned()
             .unwrap_or_default();
         let styles = Styles::new(&active_theme);
+        let approval_policy = ApprovalPolicy::new(&config.approval_policies);
+        // Kept in memory only until run_app points it at the history directory
+        let input_history = InputHistory::new(None, config.history.max_entries);
 
         Self {
             config,
@@ -268,6 +490,21 @@ impl AppState {
             show_help: false,
             popup_input: None,
             modal_textarea: None,
+            choice_picker: None,
+            approval_policy,
+            policy_decisions: VecDeque::new(),
+            broadcast: None,
+            audit_log: None,
+            input_history,
+            history_recall: None,
+            history_search: None,
+            attach_picker: None,
+            mirror: None,
+            last_sent: HashMap::new(),
+            prompt_queues: HashMap::new(),
+            queue_editor: None,
+            workflow_runs: Vec::new(),
+            pending_launches: Vec::new(),
             filter_pattern: None,
             show_subagent_log: false,
             show_summary_detail: true,
@@ -717,11 +954,713 @@ impl AppState {
         self.show_subagent_log = !self.show_subagent_log;
     }
 
+    /// Toggles the live mirror of the current pane in the preview
+    pub fn toggle_zoom(&mut self) {
+        self.mirror = match self.mirror {
+            Some(_) => None,
+            None => Some(PaneMirror::default()),
+        };
+    }
+
+    /// Zooms the current pane and forwards the following keys to it
+    pub fn start_passthrough(&mut self) {
+        let Some((target, agent_id)) = self
+            .selected_agent()
+            .map(|a| (a.target.clone(), a.id.clone()))
+        else {
+            return;
+        };
+        let mirror = self.mirror.get_or_insert_with(PaneMirror::default);
+        mirror.passthrough = true;
+        mirror.target = target.clone();
+        mirror.agent_id = agent_id;
+        mirror.invalidate();
+        self.set_status(format!(
+            "Keys go to {}, {} to leave",
+            target, self.config.passthrough_exit_key
+        ));
+    }
+
     /// Toggles summary detail (TODOs and Tools) display
     pub fn toggle_summary_detail(&mut self) {
         self.show_summary_detail = !self.show_summary_detail;
     }
 
+    /// Opens the choice picker for the selected agent's question.
+    /// Returns false if the agent is not asking a question with known choices.
+    pub fn open_choice_picker(&mut self) -> bool {
+        let Some(agent) = self.selected_visible_agent() else {
+            return false;
+        };
+        let Some((choices, multi_select)) = question_choices(agent) else {
+            return false;
+        };
+        self.choice_picker = Some(ChoicePickerState {
+            agent_id: agent.id.clone(),
+            target: agent.target.clone(),
+            choices: choices.to_vec(),
+            multi_select,
+            cursor: 0,
+            marked: BTreeSet::new(),
+        });
+        self.show_summary_detail = true;
+        true
+    }
+
+    /// Closes the choice picker once its agent stopped asking the question
+    pub fn sync_choice_picker(&mut self) {
+        let Some(picker) = &self.choice_picker else {
+            return;
+        };
+        let still_asking = self
+            .agents
+            .root_agents
+            .iter()
+            .find(|a| a.id == picker.agent_id)
+            .and_then(question_choices)
+            .is_some_and(|(choices, _)| choices == picker.choices.as_slice());
+        if !still_asking {
+            self.choice_picker = None;
+        }
+    }
+
+    /// Splits the given agents into approvals to send, to confirm and to refuse.
+    /// Agents that are not awaiting approval (e.g. errors) are skipped.
+    pub fn plan_approvals(&self, indices: &[usize]) -> ApprovalPlan {
+        let mut plan = ApprovalPlan::default();
+        for &idx in indices {
+            let Some(agent) = self.agents.get_agent(idx) else {
+                continue;
+            };
+            if !matches!(agent.status, AgentStatus::AwaitingApproval { .. }) {
+                continue;
+            }
+            match self.approval_policy.decide(agent) {
+                Some(m) if m.action == PolicyAction::Never => plan.blocked.push((idx, m.rule)),
+                Some(m) if m.action == PolicyAction::Confirm => plan.confirm.push((idx, m.rule)),
+                _ => plan.approve.push(idx),
+            }
+        }
+        plan
+    }
+
+    /// Asks for the value of the first of `labels` of a prompt's `{{ask:...}}` placeholders
+    pub fn ask_prompt_parameter(
+        &mut self,
+        name: String,
+        text: String,
+        labels: Vec<String>,
+        values: Vec<(String, String)>,
+        edit: bool,
+    ) {
+        let Some(label) = labels.first() else {
+            return;
+        };
+        let title = match self.broadcast_label() {
+            Some(target) => format!("Prompt: {} → {}", name, target),
+            None => format!("Prompt: {}", name),
+        };
+        self.popup_input = Some(PopupInputState {
+            title,
+            prompt: format!("{}:", label),
+            buffer: String::new(),
+            cursor: 0,
+            popup_type: PopupType::PromptParameter {
+                name,
+                text,
+                labels,
+                values,
+                edit,
+            },
+        });
+    }
+
+    /// "sending to N agents" when text input goes to more than one agent
+    pub fn broadcast_label(&self) -> Option<String> {
+        let count = self.get_operation_indices().len();
+        (count > 1).then(|| format!("sending to {} agents", count))
+    }
+
+    /// Queues text input for every agent in the operation set (multiselection or
+    /// cursor). `text_for` builds the text for each agent and `keys_for` the keys that
+    /// type it, from the agent's key config. Sends are spaced by `broadcast_stagger_ms`;
+    /// the first is due immediately. With `queue_when_busy`, non-empty text for a
+    /// working agent goes to its prompt queue instead.
+    pub fn broadcast_input(
+        &mut self,
+        label: Option<String>,
+        text_for: impl Fn(&MonitoredAgent) -> String,
+        keys_for: impl Fn(&AgentKeys, &str) -> Vec<KeyArg>,
+    ) {
+        let mut sends: Vec<(String, String, Vec<KeyArg>)> = Vec::new();
+        let mut queued: Vec<(String, String)> = Vec::new();
+        for idx in self.get_operation_indices() {
+            let Some(agent) = self.agents.get_agent(idx) else {
+                continue;
+            };
+            let text = text_for(agent);
+            let busy = matches!(agent.status, AgentStatus::Processing { .. });
+            if busy && self.config.queue_when_busy && !text.trim().is_empty() {
+                self.prompt_queues
+                    .entry(agent.id.clone())
+                    .or_default()
+                    .push_back(text);
+                queued.push((agent.id.clone(), agent.target.clone()));
+            } else {
+                let keys = keys_for(self.get_agent_keys(agent), &text);
+                sends.push((agent.id.clone(), agent.target.clone(), keys));
+            }
+        }
+
+        match queued.as_slice() {
+            [] => {}
+            [(id, target)] => {
+                let waiting = self.queued_prompt_count(id);
+                self.set_status(format!(
+                    "Queued for {} until idle ({} waiting)",
+                    target, waiting
+                ));
+            }
+            targets => self.set_status(format!(
+                "Queued for {} working agents until idle",
+                targets.len()
+            )),
+        }
+        self.queue_sends(sends, label);
+    }
+
+    /// Sends editor text to the selected agents without submitting it, typed or
+    /// pasted as each agent's `delivery` says
+    pub fn broadcast_text(&mut self, text: String) {
+        self.record_history(&text);
+        self.broadcast_input(
+            None,
+            |_| text.clone(),
+            |keys, text| keys.deliver_text(text).into_iter().collect(),
+        );
+    }
+
+    /// Removes the next queued prompt of an agent and returns its send (the
+    /// agent's `input` keys, so the prompt is submitted)
+    fn pop_queued_prompt(&mut self, agent_id: &str) -> Option<(String, String, Vec<KeyArg>)> {
+        let agent = self.agents.root_agents.iter().find(|a| a.id == agent_id)?;
+        let queue = self.prompt_queues.get_mut(agent_id)?;
+        let text = queue.pop_front()?;
+        if queue.is_empty() {
+            self.prompt_queues.remove(agent_id);
+        }
+        Some((
+            agent.id.clone(),
+            agent.target.clone(),
+            self.get_agent_keys(agent).expand_input(&text),
+        ))
+    }
+
+    /// Delivers the next queued prompt to each agent that has just become idle and
+    /// drops the queues of agents that are gone
+    pub fn deliver_queued_prompts(&mut self, transitions: &[StatusTransition]) {
+        let agents = &self.agents.root_agents;
+        self.prompt_queues
+            .retain(|id, _| agents.iter().any(|a| &a.id == id));
+
+        let sends: Vec<_> = transitions
+            .iter()
+            .filter(|t| matches!(t.to, AgentStatus::Idle { .. }))
+            .filter_map(|t| self.pop_queued_prompt(&t.agent_id))
+            .collect();
+        self.queue_sends(sends, Some("queued prompt".to_string()));
+    }
+
+    /// Opens the prompt queue of the cursor agent in the editor, one prompt per
+    /// block separated by `---` lines
+    pub fn edit_prompt_queue(&mut self) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        let id = agent.id.clone();
+        let title = format!("Prompt Queue: {} ({})", agent.name, agent.target);
+        let text = self
+            .prompt_queues
+            .get(&id)
+            .map(|q| q.iter().cloned().collect::<Vec<_>>().join("\n---\n"))
+            .unwrap_or_default();
+        self.modal_textarea = Some(ModalTextareaState::new(
+            title,
+            "Prompts sent one by one when the agent becomes idle, separated by --- lines"
+                .to_string(),
+            text,
+            false, // multiline
+            false, // editable
+            &self.styles,
+        ));
+        self.queue_editor = Some(id);
+    }
+
+    /// Replaces an agent's prompt queue with the edited text (empty cancels all).
+    /// An idle agent gets the first prompt right away.
+    pub fn save_prompt_queue(&mut self, agent_id: &str, text: &str) {
+        let Some(agent) = self.agents.root_agents.iter().find(|a| a.id == agent_id) else {
+            self.set_error("The agent of this prompt queue is gone".to_string());
+            return;
+        };
+        let target = agent.target.clone();
+        let idle = matches!(agent.status, AgentStatus::Idle { .. });
+
+        let mut queue: VecDeque<String> = VecDeque::new();
+        let mut current: Vec<&str> = Vec::new();
+        for line in text.lines().chain(std::iter::once("---")) {
+            if line.trim() == "---" {
+                let prompt = current.join("\n").trim().to_string();
+                if !prompt.is_empty() {
+                    queue.push_back(prompt);
+                }
+                current.clear();
+            } else {
+                current.push(line);
+            }
+        }
+
+        let count = queue.len();
+        if queue.is_empty() {
+            self.prompt_queues.remove(agent_id);
+            self.set_status(format!("Prompt queue of {} cleared", target));
+            return;
+        }
+        self.prompt_queues.insert(agent_id.to_string(), queue);
+        self.set_status(format!("{} prompt(s) queued for {}", count, target));
+
+        if idle {
+            if let Some(send) = self.pop_queued_prompt(agent_id) {
+                self.queue_sends(vec![send], Some("queued prompt".to_string()));
+            }
+        }
+    }
+
+    /// Number of prompts queued for an agent
+    pub fn queued_prompt_count(&self, agent_id: &str) -> usize {
+        self.prompt_queues.get(agent_id).map_or(0, |q| q.len())
+    }
+
+    /// Queues keys for the given (agent id, target, keys), spaced by
+    /// `broadcast_stagger_ms`
+    pub fn queue_sends(
+        &mut self,
+        sends: Vec<(String, String, Vec<KeyArg>)>,
+        label: Option<String>,
+    ) {
+        if sends.is_empty() {
+            return;
+        }
+
+        let stagger = Duration::from_millis(self.config.broadcast_stagger_ms);
+        let broadcast = self.broadcast.get_or_insert_with(Broadcast::default);
+        let mut due = match broadcast.pending.back() {
+            Some(last) => last.due + stagger,
+            None => Instant::now(),
+        };
+        // Sends queued while others are pending keep their own label
+        if broadcast.total == 0 {
+            broadcast.label = label.clone();
+        } else if broadcast.label != label {
+            broadcast.label = None;
+        }
+        broadcast.total += sends.len();
+        for (agent_id, target, keys) in sends {
+            broadcast.pending.push_back(PendingSend {
+                target,
+                agent_id,
+                keys,
+                due,
+                label: label.clone(),
+            });
+            due += stagger;
+        }
+        if broadcast.total > 1 && !stagger.is_zero() {
+            let text = format!(
+                "Sending to {} agents, one every {} ms",
+                broadcast.total,
+                stagger.as_millis()
+            );
+            self.set_status(text);
+        }
+    }
+
+    /// Removes and returns the broadcast sends due at `now`
+    pub fn take_due_sends(&mut self, now: Instant) -> Vec<PendingSend> {
+        let Some(broadcast) = &mut self.broadcast else {
+            return Vec::new();
+        };
+        let mut due = Vec::new();
+        while broadcast.pending.front().is_some_and(|p| p.due <= now) {
+            due.extend(broadcast.pending.pop_front());
+        }
+        due
+    }
+
+    /// Records the result of one broadcast send and reports the outcome once
+    /// every target has been sent to
+    pub fn finish_send(&mut self, send: &PendingSend, result: anyhow::Result<()>) {
+        let mut entry = AuditEntry::new(AuditSource::Ui, AuditAction::Input, Some(&send.target))
+            .keys(&send.keys)
+            .result(&result);
+        if let Some(label) = &send.label {
+            entry = entry.detail(label.clone());
+        }
+        self.audit(entry);
+        // A lone Enter (empty input) is not worth resending
+        if result.is_ok() && send.keys.iter().any(|k| k.text() != "Enter") {
+            self.last_sent
+                .insert(send.agent_id.clone(), send.keys.clone());
+        }
+
+        let Some(broadcast) = &mut self.broadcast else {
+            return;
+        };
+        if let Err(e) = result {
+            broadcast
+                .failures
+                .push((send.target.clone(), e.to_string()));
+        }
+        if !broadcast.pending.is_empty() {
+            return;
+        }
+
+        let Some(done) = self.broadcast.take() else {
+            return;
+        };
+        let failed = done.failures.len();
+        if done.total == 1 {
+            match (done.failures.first(), done.label) {
+                (Some((_, e)), _) => self.set_error(format!("Failed to send input: {}", e)),
+                (None, Some(label)) => self.set_status(format!("Sent: {}", label)),
+                (None, None) => {}
+            }
+        } else if failed == 0 {
+            let what = done.label.map(|l| format!("{} ", l)).unwrap_or_default();
+            self.set_status(format!("Sent {}to {} agents", what, done.total));
+        } else {
+            let details: Vec<String> = done
+                .failures
+                .iter()
+                .map(|(target, e)| format!("{} ({})", target, e))
+                .collect();
+            self.set_error(format!(
+                "Failed to send to {} of {} agents: {}",
+                failed,
+                done.total,
+                details.join(", ")
+            ));
+        }
+    }
+
+    /// Remembers sent text in the history of each target agent's type
+    pub fn record_history(&mut self, text: &str) {
+        self.history_recall = None;
+        let mut agent_types: Vec<String> = self
+            .get_operation_indices()
+            .into_iter()
+            .filter_map(|idx| self.agents.get_agent(idx))
+            .map(|agent| agent.config_id.clone())
+            .collect();
+        agent_types.sort();
+        agent_types.dedup();
+        for agent_type in agent_types {
+            if let Err(e) = self.input_history.push(&agent_type, text) {
+                tracing::warn!("Failed to save input history: {:#}", e);
+            }
+        }
+    }
+
+    /// Field that history keys act on: the editable modal, the general input popup
+    /// or the bottom input
+    pub fn active_input_field(&self) -> InputField {
+        if self.modal_textarea.as_ref().is_some_and(|m| !m.readonly) {
+            InputField::Modal
+        } else if self
+            .popup_input
+            .as_ref()
+            .is_some_and(|p| p.popup_type == PopupType::GeneralInput)
+        {
+            InputField::Popup
+        } else {
+            InputField::Bottom
+        }
+    }
+
+    fn input_field_text(&self, field: InputField) -> String {
+        match field {
+            InputField::Bottom => self.input_buffer.clone(),
+            InputField::Popup => self
+                .popup_input
+                .as_ref()
+                .map(|p| p.buffer.clone())
+                .unwrap_or_default(),
+            InputField::Modal => self
+                .modal_textarea
+                .as_ref()
+                .map(|m| m.get_text())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn set_input_field_text(&mut self, field: InputField, text: &str) {
+        match field {
+            InputField::Bottom => {
+                self.input_buffer = text.to_string();
+                self.cursor_position = self.input_buffer.len();
+            }
+            InputField::Popup => {
+                if let Some(popup) = &mut self.popup_input {
+                    popup.buffer = text.to_string();
+                    popup.cursor = popup.buffer.len();
+                }
+            }
+            InputField::Modal => {
+                if let Some(modal) = &mut self.modal_textarea {
+                    modal.set_text(text);
+                }
+            }
+        }
+    }
+
+    /// Agent type whose history the input fields use (the cursor agent's)
+    fn history_agent_type(&self) -> Option<String> {
+        self.selected_agent().map(|agent| agent.config_id.clone())
+    }
+
+    /// Replaces the active input with an older or newer history entry. Past the
+    /// newest entry the text typed before recalling comes back.
+    pub fn recall_history(&mut self, older: bool) {
+        let field = self.active_input_field();
+        let current = self.input_field_text(field);
+        // Start over when the field changed or the recalled text was edited
+        let in_progress = self.history_recall.as_ref().is_some_and(|r| {
+            r.field == field && r.entries.get(r.index).unwrap_or(&r.draft) == &current
+        });
+        if !in_progress {
+            let Some(agent_type) = self.history_agent_type() else {
+                return;
+            };
+            let entries = self.input_history.entries(&agent_type).to_vec();
+            self.history_recall = Some(HistoryRecall {
+                field,
+                index: entries.len(),
+                entries,
+                draft: current,
+            });
+        }
+
+        let Some(recall) = &mut self.history_recall else {
+            return;
+        };
+        let index = if older {
+            recall.index.checked_sub(1)
+        } else {
+            (recall.index < recall.entries.len()).then_some(recall.index + 1)
+        };
+        let Some(index) = index else {
+            return;
+        };
+        recall.index = index;
+        let text = recall.entries.get(index).unwrap_or(&recall.draft).clone();
+        self.set_input_field_text(field, &text);
+    }
+
+    /// Opens the history search for the active input
+    pub fn show_history_search(&mut self) {
+        let Some(agent_type) = self.history_agent_type() else {
+            return;
+        };
+        let items: Vec<MenuItem> = self
+            .input_history
+            .entries(&agent_type)
+            .iter()
+            .rev()
+            .map(|text| {
+                let mut lines = text.lines();
+                let mut name = lines.next().unwrap_or_default().to_string();
+                if lines.next().is_some() {
+                    name.push_str(" …");
+                }
+                MenuItem {
+                    name,
+                    description: None,
+                    execute_command: None,
+                    text: Some(text.clone()),
+                    items: Vec::new(),
+                }
+            })
+            .collect();
+        if items.is_empty() {
+            self.set_status(format!("No input history for {}", agent_type));
+            return;
+        }
+        self.history_search = Some(HistorySearch {
+            field: self.active_input_field(),
+            menu: MenuConfig {
+                items,
+                merge_with_defaults: false,
+            },
+            tree: MenuTreeState::new(),
+        });
+    }
+
+    /// Closes the history search, putting the highlighted entry into its field
+    pub fn pick_history_search(&mut self) {
+        use crate::ui::components::menu_tree::find_flat_menu_item_by_index;
+
+        let Some(search) = self.history_search.take() else {
+            return;
+        };
+        let text = search
+            .tree
+            .list_state
+            .selected()
+            .and_then(|index| find_flat_menu_item_by_index(&search.menu, &search.tree, index))
+            .and_then(|flat| flat.item.text.clone());
+        if let Some(text) = text {
+            self.history_recall = None;
+            self.set_input_field_text(search.field, &text);
+        }
+    }
+
+    /// Opens the file picker over the cursor agent's directory
+    pub fn show_file_picker(&mut self) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        let mode = self
+            .config
+            .agents
+            .iter()
+            .find(|a| a.id == agent.config_id)
+            .map(|a| a.attach)
+            .unwrap_or_default();
+        match AttachPicker::files(Path::new(&agent.path), mode) {
+            Some(picker) => self.attach_picker = Some(picker),
+            None => self.set_status(format!("No files under {}", agent.path)),
+        }
+    }
+
+    /// Opens the picker of paste buffers and other panes
+    pub fn show_pane_picker(&mut self, tmux: &dyn TmuxBackend) {
+        let exclude = self
+            .selected_agent()
+            .map(|a| a.target.clone())
+            .unwrap_or_default();
+        match AttachPicker::panes(tmux, &exclude) {
+            Ok(Some(picker)) => self.attach_picker = Some(picker),
+            Ok(None) => self.set_status("Nothing to insert from other panes".to_string()),
+            Err(e) => self.set_error(format!("Failed to read panes: {}", e)),
+        }
+    }
+
+    /// Closes the attach picker, inserting the highlighted item at the editor's
+    /// cursor or moving on to the picker of the next step
+    pub fn pick_attachment(&mut self, tmux: &dyn TmuxBackend) {
+        use crate::ui::components::menu_tree::find_flat_menu_item_by_index;
+
+        let Some(picker) = self.attach_picker.take() else {
+            return;
+        };
+        let Some(text) = picker
+            .tree
+            .list_state
+            .selected()
+            .and_then(|index| find_flat_menu_item_by_index(&picker.menu, &picker.tree, index))
+            .and_then(|flat| flat.item.text.clone())
+        else {
+            return;
+        };
+        match picker.pick(&text, tmux) {
+            Ok(AttachPick::Insert(insertion)) => {
+                if let Some(modal) = &mut self.modal_textarea {
+                    modal.textarea.insert_str(insertion);
+                }
+            }
+            Ok(AttachPick::Next(next)) => self.attach_picker = Some(next),
+            Err(e) => self.set_error(format!("Failed to attach: {}", e)),
+        }
+    }
+
+    /// Queues the text input last sent to the cursor agent again, falling back to
+    /// the newest history entry of its type
+    pub fn resend_last(&mut self) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        let id = agent.id.clone();
+        let target = agent.target.clone();
+        let name = agent.name.clone();
+        let keys = match self.last_sent.get(&id) {
+            Some(keys) => Some(keys.clone()),
+            None => {
+                let agent_keys = self.get_agent_keys(agent).clone();
+                let agent_type = agent.config_id.clone();
+                self.input_history
+                    .entries(&agent_type)
+                    .last()
+                    .map(|text| agent_keys.expand_input(text))
+            }
+        };
+        match keys {
+            Some(keys) => {
+                let send = (id, target, keys);
+                self.queue_sends(vec![send], Some("last input".to_string()))
+            }
+            None => self.set_status(format!("Nothing sent to {} yet", name)),
+        }
+    }
+
+    /// Writes an entry to the audit log, filling in the agent at its target
+    pub fn audit(&self, mut entry: AuditEntry) {
+        let Some(log) = &self.audit_log else {
+            return;
+        };
+        if let Some(agent) = entry
+            .target
+            .as_deref()
+            .and_then(|t| self.agents.root_agents.iter().find(|a| a.target == t))
+        {
+            entry.agent_id = Some(agent.id.clone());
+            entry.agent = Some(agent.config_id.clone());
+            entry.status = Some(format!(
+                "{}: {}",
+                agent.status.kind(),
+                agent.status.short_text()
+            ));
+        }
+        if let Err(e) = log.append(&entry) {
+            tracing::warn!("Failed to write audit log: {:#}", e);
+        }
+    }
+
+    /// Records auto-approval decisions from the monitor and reports the latest one
+    pub fn record_policy_decisions(&mut self, decisions: Vec<PolicyDecision>) {
+        for decision in decisions {
+            let mut entry = AuditEntry::new(
+                AuditSource::Policy,
+                AuditAction::Approve,
+                Some(&decision.target),
+            )
+            .keys(&decision.keys)
+            .detail(format!(
+                "{} by policy '{}'",
+                decision.approval, decision.rule
+            ));
+            entry.error = decision.error.clone();
+            self.audit(entry);
+            match decision.error {
+                None => self.set_status(decision.summary()),
+                Some(_) => self.set_error(decision.summary()),
+            }
+            self.policy_decisions.push_back(decision);
+        }
+        while self.policy_decisions.len() > POLICY_DECISION_LIMIT {
+            self.policy_decisions.pop_front();
+        }
+    }
+
     /// Toggles command menu display
     pub fn toggle_menu(&mut self) {
         self.show_menu = !self.show_menu;
@@ -1020,6 +1959,7 @@ impl AppState {
         self.styles = Styles::new(&active_theme);
         // Some state depends on config, refresh it
         self.sidebar_width = self.config.sidebar_width.clone();
+        self.approval_policy = ApprovalPolicy::new(&self.config.approval_policies);
         // Clear cached menus so they are rebuilt from new config
         self.menu_tree.filter.clear();
         self.prompts_tree.filter.clear();
@@ -1075,8 +2015,10 @@ mod tests {
 
     #[test]
     fn test_app_state_navigation() {
-        let mut config = Config::default();
-        config.cyclic_navigation = true;
+        let config = Config {
+            cyclic_navigation: true,
+            ..Default::default()
+        };
         let mut state = AppState::new(config);
 
         // Add some agents
@@ -1455,8 +2397,10 @@ mod tests {
     #[test]
     fn test_reload_config_success() {
         let mut state = AppState::default();
-        let mut new_config = Config::default();
-        new_config.poll_interval_ms = 999;
+        let new_config = Config {
+            poll_interval_ms: 999,
+            ..Default::default()
+        };
         state.reload_config(new_config);
         assert_eq!(state.config.poll_interval_ms, 999);
         assert_eq!(
@@ -1472,4 +2416,339 @@ mod tests {
         // We can't easily trigger a real file error here without temp files,
         // but the logic is now verified by type system (it returns Result).
     }
+
+    #[test]
+    fn test_choice_picker_follows_question() {
+        let mut state = AppState::default();
+        let mut agent = create_test_agent("1", "main", 0);
+        agent.status = AgentStatus::AwaitingApproval {
+            approval_type: ApprovalType::UserQuestion {
+                choices: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+                multi_select: true,
+            },
+            details: String::new(),
+        };
+        state.agents.root_agents.push(agent);
+        state.update_visible_indices();
+
+        assert!(state.open_choice_picker());
+        let picker = state.choice_picker.as_mut().unwrap();
+        // Nothing toggled: answer with the highlighted choice
+        assert_eq!(picker.picked_numbers(), vec![1]);
+        picker.pick(2);
+        picker.move_cursor(-5);
+        picker.toggle();
+        picker.pick(7); // Out of range, ignored
+        assert_eq!(picker.cursor, 0);
+        assert_eq!(picker.picked_numbers(), vec![1, 3]);
+
+        // Same question on the next poll keeps the picker open
+        state.sync_choice_picker();
+        assert!(state.choice_picker.is_some());
+
+        // Question answered: the picker closes
+        state.agents.root_agents[0].status = AgentStatus::Processing {
+            activity: String::new(),
+        };
+        state.sync_choice_picker();
+        assert!(state.choice_picker.is_none());
+        assert!(!state.open_choice_picker());
+    }
+
+    #[test]
+    fn test_plan_approvals_skips_errors_and_applies_policy() {
+        use crate::app::config::ApprovalPolicyRule;
+
+        let rule = |approval_type: &str, action| ApprovalPolicyRule {
+            name: None,
+            agent: None,
+            approval_type: Some(approval_type.to_string()),
+            details: None,
+            content: None,
+            action,
+        };
+        let config = Config {
+            approval_policies: vec![
+                rule("shell", PolicyAction::Never),
+                rule("delete", PolicyAction::Confirm),
+            ],
+            ..Default::default()
+        };
+        let mut state = AppState::new(config);
+
+        let statuses = [
+            AgentStatus::Error {
+                message: "boom".to_string(),
+            },
+            AgentStatus::AwaitingApproval {
+                approval_type: ApprovalType::FileEdit,
+                details: String::new(),
+            },
+            AgentStatus::AwaitingApproval {
+                approval_type: ApprovalType::ShellCommand,
+                details: String::new(),
+            },
+            AgentStatus::AwaitingApproval {
+                approval_type: ApprovalType::FileDelete,
+                details: String::new(),
+            },
+        ];
+        for (i, status) in statuses.into_iter().enumerate() {
+            let mut agent = create_test_agent(&i.to_string(), "main", i as u32);
+            agent.status = status;
+            state.agents.root_agents.push(agent);
+        }
+
+        let plan = state.plan_approvals(&[0, 1, 2, 3]);
+        assert_eq!(plan.approve, vec![1]);
+        assert_eq!(plan.blocked, vec![(2, "approval_policies[0]".to_string())]);
+        assert_eq!(plan.confirm, vec![(3, "approval_policies[1]".to_string())]);
+    }
+
+    #[test]
+    fn test_broadcast_input_staggers_and_reports_failures() {
+        let config = Config {
+            broadcast_stagger_ms: 100,
+            ..Default::default()
+        };
+        let mut state = AppState::new(config);
+        for i in 0..3 {
+            state
+                .agents
+                .root_agents
+                .push(create_test_agent(&i.to_string(), "main", i));
+        }
+        state.update_visible_indices();
+
+        // Without a multiselection only the cursor agent is targeted
+        assert_eq!(state.broadcast_label(), None);
+        state.select_all();
+        assert_eq!(
+            state.broadcast_label().as_deref(),
+            Some("sending to 3 agents")
+        );
+
+        let start = Instant::now();
+        state.broadcast_input(
+            Some("review".to_string()),
+            |_| "hello".to_string(),
+            |keys, text| keys.expand_input(text),
+        );
+        let first = state.take_due_sends(start + Duration::from_millis(50));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].target, "main:0.0");
+        state.finish_send(&first[0], Ok(()));
+        assert!(state.broadcast.is_some());
+
+        // A second broadcast while the first is pending keeps both labels
+        state.queue_sends(
+            vec![(
+                "0".to_string(),
+                "main:0.0".to_string(),
+                KeyArg::names(&["Enter"]),
+            )],
+            Some("confirm".to_string()),
+        );
+        assert_eq!(state.broadcast.as_ref().unwrap().label, None);
+
+        let rest = state.take_due_sends(start + Duration::from_secs(1));
+        assert_eq!(
+            rest.iter()
+                .map(|s| (s.target.as_str(), s.label.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("main:0.1", Some("review")),
+                ("main:0.2", Some("review")),
+                ("main:0.0", Some("confirm")),
+            ]
+        );
+        state.finish_send(&rest[0], Err(anyhow::anyhow!("pane gone")));
+        state.finish_send(&rest[1], Ok(()));
+        state.finish_send(&rest[2], Ok(()));
+
+        assert!(state.broadcast.is_none());
+        let message = state.last_message.as_ref().unwrap();
+        assert_eq!(message.kind, MessageKind::Error);
+        assert_eq!(
+            message.text,
+            "Failed to send to 1 of 4 agents: main:0.1 (pane gone)"
+        );
+    }
+
+    #[test]
+    fn test_history_recall_and_resend() {
+        let mut state = AppState::new(Config::default());
+        state
+            .agents
+            .root_agents
+            .push(create_test_agent("0", "main", 0));
+        state.update_visible_indices();
+        state.record_history("run the tests");
+        state.record_history("commit");
+
+        // Up walks back through the history, Down returns to the draft
+        state.input_buffer = "dra".to_string();
+        state.recall_history(true);
+        assert_eq!(state.get_input(), "commit");
+        state.recall_history(true);
+        state.recall_history(true);
+        assert_eq!(state.get_input(), "run the tests");
+        state.recall_history(false);
+        state.recall_history(false);
+        assert_eq!(state.get_input(), "dra");
+        assert_eq!(state.get_cursor_position(), 3);
+
+        // Search inserts the highlighted (newest first) entry
+        state.show_history_search();
+        state.history_search.as_mut().unwrap().tree.filter = "tests".to_string();
+        state.pick_history_search();
+        assert_eq!(state.get_input(), "run the tests");
+
+        // Resend uses the newest entry until something was sent
+        state.resend_last();
+        let sends = state.take_due_sends(Instant::now());
+        assert_eq!(sends[0].keys, KeyArg::names(&["commit", "Enter"]));
+        state.finish_send(&sends[0], Ok(()));
+        state
+            .last_sent
+            .insert("0".to_string(), KeyArg::names(&["again", "Enter"]));
+        state.resend_last();
+        assert_eq!(
+            state.take_due_sends(Instant::now())[0].keys,
+            KeyArg::names(&["again", "Enter"])
+        );
+
+        // A new agent in the same pane does not get the previous agent's input
+        state.agents.root_agents[0] = create_test_agent("1", "main", 0);
+        state.resend_last();
+        assert_eq!(
+            state.take_due_sends(Instant::now())[0].keys,
+            KeyArg::names(&["commit", "Enter"])
+        );
+    }
+
+    #[test]
+    fn test_editor_text_follows_input_delivery() {
+        use crate::tmux::FakeTmux;
+
+        let agent_config: crate::app::config::AgentConfig = toml::from_str(
+            r#"
+id = "test"
+name = "Test"
+
+[keys]
+delivery = "bracketed_paste"
+"#,
+        )
+        .unwrap();
+        let mut state = AppState::new(Config {
+            agents: vec![agent_config],
+            ..Default::default()
+        });
+        state
+            .agents
+            .root_agents
+            .push(create_test_agent("0", "main", 0));
+        state.update_visible_indices();
+        let tmux = FakeTmux::new();
+        tmux.add_pane("main:0.0", "claude").unwrap();
+
+        // Multi-line text (or text that looks like a key name) is pasted as is
+        let modal = crate::ui::components::ModalTextareaState::new(
+            "Input".to_string(),
+            String::new(),
+            "fix the tests\nEnter\nthen commit".to_string(),
+            false, // multiline
+            false, // editable
+            &state.styles,
+        );
+        state.broadcast_text(modal.get_text());
+        for send in state.take_due_sends(Instant::now()) {
+            tmux.send_keys_many(&send.target, &send.keys).unwrap();
+        }
+        assert_eq!(
+            tmux.take_sent_keys()[0].keys,
+            [KeyArg::paste("fix the tests\nEnter\nthen commit")]
+        );
+    }
+
+    #[test]
+    fn test_input_for_busy_agent_sent_by_default() {
+        let mut state = AppState::new(Config::default());
+        let mut busy = create_test_agent("0", "main", 0);
+        busy.status = AgentStatus::Processing {
+            activity: "Thinking".to_string(),
+        };
+        state.agents.root_agents.push(busy);
+        state.update_visible_indices();
+
+        // Queueing is opt-in; without it the text goes straight to the pane
+        state.broadcast_input(
+            None,
+            |_| "run the tests".to_string(),
+            |keys, text| keys.expand_input(text),
+        );
+        let sends = state.take_due_sends(Instant::now());
+        assert_eq!(sends.len(), 1);
+        assert_eq!(sends[0].keys, KeyArg::names(&["run the tests", "Enter"]));
+        assert_eq!(state.queued_prompt_count("0"), 0);
+    }
+
+    #[test]
+    fn test_prompt_queue_delivered_when_idle() {
+        let mut state = AppState::new(Config {
+            queue_when_busy: true,
+            ..Default::default()
+        });
+        let mut busy = create_test_agent("0", "main", 0);
+        busy.status = AgentStatus::Processing {
+            activity: "Thinking".to_string(),
+        };
+        state.agents.root_agents.push(busy);
+        state.update_visible_indices();
+
+        // Text for a working agent is queued, not sent
+        for text in ["run the tests", "then commit"] {
+            state.broadcast_input(
+                None,
+                |_| text.to_string(),
+                |keys, text| keys.expand_input(text),
+            );
+        }
+        assert!(state.take_due_sends(Instant::now()).is_empty());
+        assert_eq!(state.queued_prompt_count("0"), 2);
+
+        // Becoming idle delivers one prompt at a time
+        let idle = AgentStatus::Idle { label: None };
+        state.agents.root_agents[0].status = idle.clone();
+        let transition = StatusTransition {
+            agent_id: "0".to_string(),
+            target: "main:0.0".to_string(),
+            from: AgentStatus::Processing {
+                activity: "Thinking".to_string(),
+            },
+            to: idle,
+            at: chrono::Local::now(),
+            duration: Duration::from_secs(5),
+            rule: None,
+        };
+        state.deliver_queued_prompts(std::slice::from_ref(&transition));
+        let sends = state.take_due_sends(Instant::now());
+        assert_eq!(sends[0].keys, KeyArg::names(&["run the tests", "Enter"]));
+        assert_eq!(state.queued_prompt_count("0"), 1);
+
+        // Editing replaces the queue; the idle agent gets the first prompt right away
+        state.save_prompt_queue("0", "first\nline two\n---\n\n---\nsecond");
+        let sends = state.take_due_sends(Instant::now());
+        assert_eq!(sends[0].keys, KeyArg::names(&["first\nline two", "Enter"]));
+        assert_eq!(state.queued_prompt_count("0"), 1);
+        state.save_prompt_queue("0", "  ");
+        assert_eq!(state.queued_prompt_count("0"), 0);
+
+        // A new process in the same pane does not inherit the queue
+        state.save_prompt_queue("0", "stale");
+        state.agents.root_agents[0] = create_test_agent("1", "main", 0);
+        state.deliver_queued_prompts(&[]);
+        assert_eq!(state.queued_prompt_count("0"), 0);
+    }
 }
@@ -0,0 +1,166 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::agents::{git, AgentStatus, MonitoredAgent};
+use crate::util::shell_escape;
+
+/// Matches `{{ask:Label}}` placeholders
+fn ask_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{ask:([^}]+)\}\}").expect("valid ask regex"))
+}
+
+/// Matches `${ENV:VAR}` placeholders
+fn env_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$\{ENV:([^}]+)\}").expect("valid env regex"))
+}
+
+/// Matches `${NAME}` and `${ENV:VAR}` placeholders
+fn variable_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$\{(ENV:[^}]+|[A-Z_]+)\}").expect("valid variable regex"))
+}
+
+/// Expand variables in a prompt template using agent context
+///
+/// Supported variables:
+/// - `${SESSION_NAME}` - Agent's tmux session name
+/// - `${SESSION_DIR}` - Agent's working directory path
+/// - `${WINDOW_INDEX}` - Agent's tmux window index
+/// - `${WINDOW_NAME}` - Agent's tmux window name
+/// - `${PANE_INDEX}` - Agent's tmux pane index
+/// - `${PANE_TARGET}` - Agent's tmux target (session:window.pane)
+/// - `${AGENT_NAME}` - Agent's name
+/// - `${ACTIVITY}` - Agent's current status text (e.g. "Reading file")
+/// - `${LAST_ERROR}` - Error message, or the last pane line mentioning an error
+/// - `${GIT_BRANCH}` - Branch checked out in the working directory
+/// - `${ENV:VAR}` - Environment variable value
+///
+/// All variables are replaced in one pass, so text taken from the pane is
+/// never expanded itself. Unknown variables are left as they are.
+pub fn expand_variables(template: &str, agent: &MonitoredAgent) -> String {
+    expand_with(template, agent, str::to_string)
+}
+
+/// Expand variables in a shell command template; every value is shell-escaped
+pub fn expand_shell_variables(template: &str, agent: &MonitoredAgent) -> String {
+    expand_with(template, agent, shell_escape)
+}
+
+fn expand_with(template: &str, agent: &MonitoredAgent, quote: fn(&str) -> String) -> String {
+    variable_regex()
+        .replace_all(template, |caps: &regex::Captures| {
+            let value = match &caps[1] {
+                "SESSION_NAME" => agent.session.clone(),
+                "SESSION_DIR" => agent.path.clone(),
+                "WINDOW_INDEX" => agent.window.to_string(),
+                "WINDOW_NAME" => agent.window_name.clone(),
+                "PANE_INDEX" => agent.pane.to_string(),
+                "PANE_TARGET" => agent.target.clone(),
+                "AGENT_NAME" => agent.name.clone(),
+                "ACTIVITY" => agent.status.short_text(),
+                "LAST_ERROR" => last_error(agent).unwrap_or_default(),
+                "GIT_BRANCH" => match &agent.git {
+                    Some(git) => git.branch.clone(),
+                    None => git::git_branch(Path::new(&agent.path)).unwrap_or_default(),
+                },
+                name => match name.strip_prefix("ENV:") {
+                    Some(var) => std::env::var(var).unwrap_or_default(),
+                    None => return caps[0].to_string(),
+                },
+            };
+            quote(&value)
+        })
+        .into_owned()
+}
+
+/// Expand only `${ENV:VAR}` placeholders (for templates without an agent)
+pub fn expand_env(template: &str) -> String {
+    env_regex()
+        .replace_all(template, |caps: &regex::Captures| {
+            std::env::var(&caps[1]).unwrap_or_default()
+        })
+        .to_string()
+}
+
+/// Error message of an errored agent, else the last pane line mentioning an error
+fn last_error(agent: &MonitoredAgent) -> Option<String> {
+    if let AgentStatus::Error { message } = &agent.status {
+        return Some(message.clone());
+    }
+    agent
+        .last_content
+        .lines()
+        .rev()
+        .find(|line| line.to_lowercase().contains("error"))
+        .map(|line| line.trim().to_string())
+}
+
+/// Labels of the `{{ask:Label}}` placeholders in a prompt, in order, without duplicates
+pub fn ask_labels(text: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for caps in ask_regex().captures_iter(text) {
+        let label = caps[1].trim().to_string();
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Replaces `{{ask:Label}}` placeholders with the values given for their labels
+pub fn fill_asks(text: &str, values: &[(String, String)]) -> String {
+    ask_regex()
+        .replace_all(text, |caps: &regex::Captures| {
+            let label = caps[1].trim();
+            values
+                .iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_prompt_template() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/feature/x\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.path = dir.path().join("src").to_string_lossy().to_string();
+        agent.last_content =
+            "build\nerror[E0308]: mismatched types\n  --> src/lib.rs\n".to_string();
+
+        let text =
+            "Fix ${LAST_ERROR} on ${GIT_BRANCH} in ${PANE_TARGET} ({{ask:Scope}}, {{ask:Scope}})";
+        assert_eq!(ask_labels(text), vec!["Scope"]);
+        let expanded = expand_variables(text, &agent);
+        assert_eq!(
+            fill_asks(
+                &expanded,
+                &[("Scope".to_string(), "tests only".to_string())]
+            ),
+            "Fix error[E0308]: mismatched types on feature/x in main:0.1 (tests only, tests only)"
+        );
+
+        // Pane text is neither expanded again nor left unquoted in commands
+        agent.last_content = "error: ${ENV:HOME} $(rm -rf ~)\n".to_string();
+        assert_eq!(
+            expand_variables("${LAST_ERROR} ${N}", &agent),
+            "error: ${ENV:HOME} $(rm -rf ~) ${N}"
+        );
+        assert_eq!(
+            expand_shell_variables("echo ${LAST_ERROR}", &agent),
+            "echo 'error: ${ENV:HOME} $(rm -rf ~)'"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::app::config::AuditConfig;
+
+/// Where an audited action came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    /// Key press or menu in the dashboard
+    Ui,
+    /// Control socket (`tmuxx ctl`)
+    Ctl,
+    /// `[[approval_policies]]` auto-approval
+    Policy,
+    /// Step of a `[[workflows]]` run
+    Workflow,
+}
+
+impl AuditSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSource::Ui => "ui",
+            AuditSource::Ctl => "ctl",
+            AuditSource::Policy => "policy",
+            AuditSource::Workflow => "workflow",
+        }
+    }
+}
+
+/// Kind of audited action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Approve,
+    Reject,
+    /// Text typed or sent as a prompt
+    Input,
+    /// Choice number
+    Number,
+    /// Answer picked in the choice picker
+    Choice,
+    /// Raw tmux keys
+    Keys,
+    KillApp,
+    KillSession,
+    RenameSession,
+    /// Shell command run from the menu or a key binding
+    Command,
+    /// Agent started from a `[[launchers]]` entry
+    Launch,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Approve => "approve",
+            AuditAction::Reject => "reject",
+            AuditAction::Input => "input",
+            AuditAction::Number => "number",
+            AuditAction::Choice => "choice",
+            AuditAction::Keys => "keys",
+            AuditAction::KillApp => "kill_app",
+            AuditAction::KillSession => "kill_session",
+            AuditAction::RenameSession => "rename_session",
+            AuditAction::Command => "command",
+            AuditAction::Launch => "launch",
+        }
+    }
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 local time
+    pub timestamp: String,
+    /// Login name of the tmuxx user ($USER)
+    pub user: String,
+    pub source: AuditSource,
+    pub action: AuditAction,
+    /// tmux target (or session for session actions)
+    pub target: Option<String>,
+    pub agent_id: Option<String>,
+    /// Agent definition id (e.g. "claude")
+    pub agent: Option<String>,
+    /// Agent status when the action was sent (e.g. "awaiting_approval: menu")
+    pub status: Option<String>,
+    /// Key sequence sent to the pane
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<String>,
+    /// Extra context: command line, policy rule, new session name, kill method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    /// Creates an entry stamped with the current time and user
+    pub fn new(source: AuditSource, action: AuditAction, target: Option<&str>) -> Self {
+        Self {
+            timestamp: Local::now().to_rfc3339(),
+            user: std::env::var("USER").unwrap_or_default(),
+            source,
+            action,
+            target: target.map(|t| t.to_string()),
+            agent_id: None,
+            agent: None,
+            status: None,
+            keys: Vec::new(),
+            detail: None,
+            error: None,
+        }
+    }
+
+    /// Sets the key sequence sent
+    pub fn keys<S: AsRef<str>>(mut self, keys: &[S]) -> Self {
+        self.keys = keys.iter().map(|k| k.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Sets the extra context
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Records the error of a failed action
+    pub fn result<T>(mut self, result: &Result<T>) -> Self {
+        self.error = result.as_ref().err().map(|e| e.to_string());
+        self
+    }
+
+    /// Parses the timestamp
+    pub fn time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        chrono::DateTime::parse_from_rfc3339(&self.timestamp).ok()
+    }
+}
+
+/// Append-only JSONL audit log with size-based rotation
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Mutex<Option<File>>,
+}
+
+impl AuditLog {
+    /// Opens the log configured in `[audit]`; None if auditing is disabled
+    pub fn from_config(config: &AuditConfig, path: Option<PathBuf>) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let path = path.context("No directory for the audit log, set audit.path")?;
+        Self::open(path, config.max_bytes, config.max_files).map(Some)
+    }
+
+    /// Opens (creating if needed) the log at `path`
+    pub fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let file = Self::open_file(&path)?;
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file: Mutex::new(Some(file)),
+        })
+    }
+
+    fn open_file(path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log {}", path.display()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry, rotating the file first if it would grow past `max_bytes`
+    pub fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = self.file.lock();
+        let size = match file.as_ref() {
+            Some(f) => f.metadata().map(|m| m.len()).unwrap_or(0),
+            None => 0,
+        };
+        if file.is_none() || (size > 0 && size + line.len() as u64 > self.max_bytes) {
+            *file = None;
+            if size > 0 {
+                rotate(&self.path, self.max_files)?;
+            }
+            *file = Some(Self::open_file(&self.path)?);
+        }
+        let f = file.as_mut().expect("audit log file is open");
+        f.write_all(line.as_bytes())?;
+        f.flush()?;
+        Ok(())
+    }
+}
+
+/// Path of the n-th rotated file (`audit.jsonl.1` is the newest)
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// Shifts `path` to `path.1`, `path.1` to `path.2`, ... dropping the oldest
+fn rotate(path: &Path, max_files: usize) -> Result<()> {
+    if max_files == 0 {
+        return std::fs::remove_file(path)
+            .with_context(|| format!("Failed to truncate {}", path.display()));
+    }
+    let _ = std::fs::remove_file(rotated_path(path, max_files));
+    for n in (1..max_files).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    std::fs::rename(path, rotated_path(path, 1))
+        .with_context(|| format!("Failed to rotate {}", path.display()))
+}
+
+/// Reads all entries from the log and its rotated files, oldest first.
+/// Lines that fail to parse are skipped.
+pub fn read_entries(path: &Path, max_files: usize) -> Result<Vec<AuditEntry>> {
+    let mut files: Vec<PathBuf> = (1..=max_files)
+        .rev()
+        .map(|n| rotated_path(path, n))
+        .collect();
+    files.push(path.to_path_buf());
+
+    let mut entries = Vec::new();
+    for file in files.into_iter().filter(|f| f.exists()) {
+        let reader = BufReader::new(
+            File::open(&file).with_context(|| format!("Failed to open {}", file.display()))?,
+        );
+        for line in reader.lines() {
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line?) {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_rotate_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let entry = |n: usize| {
+            AuditEntry::new(AuditSource::Ui, AuditAction::Input, Some("main:0.1"))
+                .keys(&[format!("prompt {}", n), "Enter".to_string()])
+        };
+        let line_len = serde_json::to_string(&entry(0)).unwrap().len() as u64 + 1;
+
+        // Room for two entries per file, two rotated files
+        let log = AuditLog::open(path.clone(), line_len * 2, 2).unwrap();
+        for n in 0..7 {
+            log.append(&entry(n)).unwrap();
+        }
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        // The oldest file was dropped; the rest reads back in order
+        let keys: Vec<String> = read_entries(&path, 2)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.keys[0].clone())
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["prompt 2", "prompt 3", "prompt 4", "prompt 5", "prompt 6"]
+        );
+    }
+}
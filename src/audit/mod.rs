@@ -0,0 +1,3 @@
+mod log;
+
+pub use log::{read_entries, AuditAction, AuditEntry, AuditLog, AuditSource};
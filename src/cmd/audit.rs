@@ -0,0 +1,183 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, TimeZone};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::app::Config;
+use crate::audit::{read_entries, AuditEntry};
+
+pub struct AuditArgs {
+    /// Only entries at or after this time (e.g. "2h", "3d", "2024-05-01")
+    pub since: Option<String>,
+    /// Substring of the tmux target or session
+    pub target: Option<String>,
+    /// Agent definition id or agent id
+    pub agent: Option<String>,
+    /// Action name (approve, input, kill_app, ...)
+    pub action: Option<String>,
+    pub user: Option<String>,
+    /// Show only the last N matching entries
+    pub limit: Option<usize>,
+    /// Print raw JSON lines
+    pub json: bool,
+    /// Log file (defaults to audit.path from config)
+    pub path: Option<PathBuf>,
+}
+
+/// Parses a relative age ("30s", "10m", "2h", "3d") or an RFC 3339 / YYYY-MM-DD time
+pub fn parse_since(value: &str) -> Result<DateTime<FixedOffset>> {
+    let now = Local::now().fixed_offset();
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        if let Ok(n) = value[..value.len() - 1].parse::<i64>() {
+            let age = match unit {
+                's' => Duration::seconds(n),
+                'm' => Duration::minutes(n),
+                'h' => Duration::hours(n),
+                'd' => Duration::days(n),
+                _ => bail!("Invalid --since unit '{}', use s, m, h or d", unit),
+            };
+            return Ok(now - age);
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        if let Some(time) = Local.from_local_datetime(&midnight).earliest() {
+            return Ok(time.fixed_offset());
+        }
+    }
+    bail!(
+        "Invalid --since '{}', use e.g. 30m, 2h, 3d, 2024-05-01 or an RFC 3339 time",
+        value
+    )
+}
+
+/// Entry filter built from the command line
+struct AuditFilter {
+    since: Option<DateTime<FixedOffset>>,
+    target: Option<String>,
+    agent: Option<String>,
+    action: Option<String>,
+    user: Option<String>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since
+            .is_none_or(|since| entry.time().is_some_and(|t| t >= since))
+            && self.target.as_deref().is_none_or(|t| {
+                entry
+                    .target
+                    .as_deref()
+                    .is_some_and(|target| target.contains(t))
+            })
+            && self.agent.as_deref().is_none_or(|a| {
+                entry.agent.as_deref() == Some(a) || entry.agent_id.as_deref() == Some(a)
+            })
+            && self
+                .action
+                .as_deref()
+                .is_none_or(|a| entry.action.as_str() == a)
+            && self.user.as_deref().is_none_or(|u| entry.user == u)
+    }
+}
+
+/// Prints audit log entries, oldest first
+pub fn run_audit(config: &Config, args: AuditArgs) -> Result<()> {
+    let path = args
+        .path
+        .or_else(|| config.audit_log_path())
+        .context("No audit log path. Set audit.path in config or pass --path")?;
+    let filter = AuditFilter {
+        since: args.since.as_deref().map(parse_since).transpose()?,
+        target: args.target,
+        agent: args.agent,
+        action: args.action,
+        user: args.user,
+    };
+
+    let mut entries: Vec<AuditEntry> = read_entries(&path, config.audit.max_files)?
+        .into_iter()
+        .filter(|e| filter.matches(e))
+        .collect();
+    if let Some(limit) = args.limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    let mut stdout = std::io::stdout();
+    for entry in &entries {
+        if args.json {
+            writeln!(stdout, "{}", serde_json::to_string(entry)?)?;
+        } else {
+            print_entry(&mut stdout, entry)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_entry(out: &mut impl Write, entry: &AuditEntry) -> Result<()> {
+    let time = entry
+        .time()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| entry.timestamp.clone());
+    let mut what = entry.keys.join(" ");
+    if let Some(detail) = &entry.detail {
+        if !what.is_empty() {
+            what.push_str("  ");
+        }
+        what.push_str(detail);
+    }
+    if let Some(error) = &entry.error {
+        what.push_str(&format!("  FAILED: {}", error));
+    }
+    writeln!(
+        out,
+        "{} {:<6} {:<14} {:<20} {:<10} {}",
+        time,
+        entry.source.as_str(),
+        entry.action.as_str(),
+        entry.target.as_deref().unwrap_or("-"),
+        entry.agent.as_deref().unwrap_or("-"),
+        what
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditAction, AuditSource};
+
+    #[test]
+    fn test_filter_entries() {
+        let mut old = AuditEntry::new(AuditSource::Ui, AuditAction::Approve, Some("main:0.1"));
+        old.timestamp = "2024-05-01T10:00:00+00:00".to_string();
+        old.agent = Some("claude".to_string());
+        let recent = AuditEntry::new(AuditSource::Ctl, AuditAction::Input, Some("work:1.0"));
+
+        let filter = AuditFilter {
+            since: Some(parse_since("1h").unwrap()),
+            target: None,
+            agent: None,
+            action: None,
+            user: None,
+        };
+        assert!(!filter.matches(&old));
+        assert!(filter.matches(&recent));
+
+        let filter = AuditFilter {
+            since: Some(parse_since("2024-04-30").unwrap()),
+            target: Some("main:".to_string()),
+            agent: Some("claude".to_string()),
+            action: Some("approve".to_string()),
+            user: None,
+        };
+        assert!(filter.matches(&old));
+        assert!(!filter.matches(&recent));
+
+        assert!(parse_since("5x").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+
+use crate::app::{Config, KillMethod};
+use crate::control::{send_request, ControlRequest};
+
+pub struct CtlArgs {
+    /// Socket path (defaults to `control_socket` from config)
+    pub socket: Option<PathBuf>,
+    pub request: ControlRequest,
+}
+
+/// Parses a kill method name as accepted on the command line
+pub fn parse_kill_method(value: &str) -> Result<KillMethod> {
+    match value {
+        "sigterm" => Ok(KillMethod::Sigterm),
+        "ctrlc_ctrld" | "ctrl_c_ctrl_d" => Ok(KillMethod::CtrlCCtrlD),
+        "respawn" => Ok(KillMethod::Respawn),
+        _ => Err(anyhow!(
+            "Invalid kill method '{}', use 'sigterm', 'ctrlc_ctrld' or 'respawn'",
+            value
+        )),
+    }
+}
+
+/// Sends one request to a running tmuxx dashboard and prints the result
+pub async fn run_ctl(config: &Config, args: CtlArgs) -> Result<()> {
+    let socket = args
+        .socket
+        .or_else(|| config.control_socket_path())
+        .context("No control socket configured. Set control_socket in config or pass --socket")?;
+
+    let response = send_request(&socket, &args.request).await?;
+    if !response.ok {
+        eprintln!(
+            "Error: {}",
+            response.error.as_deref().unwrap_or("request failed")
+        );
+        std::process::exit(1);
+    }
+    if let Some(data) = response.data {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
-use crate::app::config::{AgentConfig, AgentKeys, MatcherConfig, StateRule};
-use crate::tmux::TmuxClient;
+use crate::app::config::{AgentConfig, AgentKeys, AttachMode, MatcherConfig, StateRule};
+use crate::tmux::{TmuxBackend, TmuxClient};
 use anyhow::{anyhow, Result};
 use std::io::{self, Write};
 
@@ -102,6 +102,7 @@ pub async fn run_learn(args: LearnArgs) -> Result<()> {
             last_lines: None,
             splitter: None,
             refinements: Vec::new(),
+            choices: None,
         }],
         subagent_rules: None,
         process_indicators: Vec::new(),
@@ -113,7 +114,11 @@ pub async fn run_learn(args: LearnArgs) -> Result<()> {
         keys: AgentKeys::default(),
         layout: None,
         summary_rules: None,
+        metrics_rules: None,
         highlight_rules: Vec::new(),
+        hooks: None,
+        stalled_after_secs: None,
+        attach: AttachMode::default(),
     };
 
     // Output TOML
@@ -1,2 +1,5 @@
+pub mod audit;
+pub mod ctl;
 pub mod learn;
+pub mod status;
 pub mod test;
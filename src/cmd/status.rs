@@ -0,0 +1,228 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::agents::{AgentStatus, MonitoredAgent};
+use crate::app::config::HooksConfig;
+use crate::app::{AgentTree, Config};
+use crate::monitor::MonitorTask;
+use crate::parsers::ParserRegistry;
+use crate::tmux::{TmuxBackend, TmuxClient};
+
+pub struct StatusArgs {
+    /// Print JSON instead of a human-readable table
+    pub json: bool,
+    /// Keep polling and print one NDJSON line per poll
+    pub watch: bool,
+}
+
+/// Machine-readable view of a detected agent
+#[derive(Debug, Serialize)]
+pub struct AgentReport {
+    pub target: String,
+    pub id: String,
+    /// Label of the tmux server (None for the default server)
+    pub server: Option<String>,
+    pub config_id: String,
+    pub name: String,
+    pub session: String,
+    pub window: u32,
+    pub window_name: String,
+    pub pane: u32,
+    pub pid: u32,
+    pub path: String,
+    /// Branch checked out in `path`, if it is a git checkout
+    pub git_branch: Option<String>,
+    /// Uncommitted changes / commits ahead and behind, e.g. "*↑2"
+    pub git_status: Option<String>,
+    pub is_ai: bool,
+    /// Status kind: idle, processing, awaiting_approval, error, unknown
+    pub status: String,
+    /// Status label / activity / error message
+    pub label: Option<String>,
+    pub approval_type: Option<String>,
+    pub approval_details: Option<String>,
+    pub context_remaining: Option<u8>,
+    pub tokens: Option<u64>,
+    /// Session cost in dollars
+    pub cost: Option<f64>,
+    /// Seconds spent in the current status
+    pub status_secs: u64,
+    pub subagents: Vec<SubagentReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubagentReport {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub subagent_type: String,
+    pub status: String,
+    pub description: String,
+}
+
+/// One poll worth of agents (a single NDJSON line in watch mode)
+#[derive(Debug, Serialize)]
+pub struct StatusSnapshot {
+    pub timestamp: String,
+    pub agents: Vec<AgentReport>,
+}
+
+impl From<&MonitoredAgent> for AgentReport {
+    fn from(agent: &MonitoredAgent) -> Self {
+        let (label, approval_type, approval_details) = match &agent.status {
+            AgentStatus::Idle { label } => (label.clone(), None, None),
+            AgentStatus::Processing { activity } => (Some(activity.clone()), None, None),
+            AgentStatus::AwaitingApproval {
+                approval_type,
+                details,
+            } => (
+                Some(approval_type.to_string()),
+                Some(approval_type.short_desc().to_lowercase()),
+                Some(details.clone()),
+            ),
+            AgentStatus::Error { message } => (Some(message.clone()), None, None),
+            AgentStatus::Unknown => (None, None, None),
+        };
+
+        Self {
+            target: agent.target.clone(),
+            id: agent.id.clone(),
+            server: agent.server.clone(),
+            config_id: agent.config_id.clone(),
+            name: agent.name.clone(),
+            session: agent.session.clone(),
+            window: agent.window,
+            window_name: agent.window_name.clone(),
+            pane: agent.pane,
+            pid: agent.pid,
+            path: agent.path.clone(),
+            git_branch: agent.git.as_ref().map(|g| g.branch.clone()),
+            git_status: agent.git.as_ref().map(|g| g.status_text()),
+            is_ai: agent.is_ai,
+            status: agent.status.kind().to_string(),
+            label,
+            approval_type,
+            approval_details,
+            context_remaining: agent.context_remaining,
+            tokens: agent.tokens,
+            cost: agent.cost,
+            status_secs: agent.time_in_status().as_secs(),
+            subagents: agent
+                .subagents
+                .iter()
+                .map(|s| SubagentReport {
+                    id: s.id.clone(),
+                    subagent_type: s.subagent_type.display_name().to_string(),
+                    status: s.status.to_string().to_lowercase(),
+                    description: s.description.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl StatusSnapshot {
+    pub fn from_tree(tree: &AgentTree) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            agents: tree.root_agents.iter().map(AgentReport::from).collect(),
+        }
+    }
+}
+
+/// Prints detected agents once (or continuously with `--watch`) without starting the TUI
+pub async fn run_status(mut config: Config, args: StatusArgs) -> Result<()> {
+    // Read-only: never fire notifications or hooks from here
+    config.notification_command = None;
+    config.hooks = HooksConfig::default();
+    // ...nor auto-approve: only the dashboard sends keys, so its audit log sees every one
+    config.approval_policies.clear();
+    // There is no dashboard pane to hide, so report the caller's session too
+    config.ignore_self = false;
+
+    let tmux_client = Arc::new(TmuxClient::from_config(&config));
+    if !tmux_client.is_available() {
+        bail!("tmux is not running");
+    }
+    let parser_registry = Arc::new(ParserRegistry::with_config(&config));
+
+    let (tx, mut rx) = mpsc::channel(4);
+    let monitor = MonitorTask::new(
+        tmux_client,
+        parser_registry,
+        tx,
+        Duration::from_millis(config.poll_interval_ms),
+        config,
+        Arc::new(AtomicBool::new(false)),
+    );
+    let monitor_handle = tokio::spawn(async move {
+        monitor.run().await;
+    });
+
+    let mut stdout = std::io::stdout();
+    while let Some(update) = rx.recv().await {
+        let snapshot = StatusSnapshot::from_tree(&update.agents);
+        if args.watch {
+            writeln!(stdout, "{}", serde_json::to_string(&snapshot)?)?;
+        } else if args.json {
+            writeln!(
+                stdout,
+                "{}",
+                serde_json::to_string_pretty(&snapshot.agents)?
+            )?;
+        } else {
+            print_table(&mut stdout, &snapshot.agents)?;
+        }
+        stdout.flush()?;
+
+        if !args.watch {
+            break;
+        }
+    }
+
+    monitor_handle.abort();
+    Ok(())
+}
+
+fn print_table(out: &mut impl Write, agents: &[AgentReport]) -> Result<()> {
+    for agent in agents {
+        writeln!(
+            out,
+            "{:<20} {:<12} {:<18} {}",
+            agent.target,
+            agent.config_id,
+            agent.status,
+            agent.label.as_deref().unwrap_or("")
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::ApprovalType;
+
+    #[test]
+    fn test_agent_report_json() {
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.status = AgentStatus::AwaitingApproval {
+            approval_type: ApprovalType::ShellCommand,
+            details: "rm -rf target".to_string(),
+        };
+        agent.context_remaining = Some(40);
+
+        let json = serde_json::to_value(AgentReport::from(&agent)).unwrap();
+        assert_eq!(json["target"], "main:0.1");
+        assert_eq!(json["config_id"], "claude");
+        assert_eq!(json["status"], "awaiting_approval");
+        assert_eq!(json["approval_type"], "shell");
+        assert_eq!(json["approval_details"], "rm -rf target");
+        assert_eq!(json["context_remaining"], 40);
+        assert!(json["subagents"].as_array().unwrap().is_empty());
+    }
+}
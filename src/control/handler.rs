@@ -0,0 +1,309 @@
+use crate::agents::{MonitoredAgent, StatusKind};
+use crate::app::config::PolicyAction;
+use crate::app::{AppState, KillMethod};
+use crate::audit::{AuditAction, AuditEntry, AuditSource};
+use crate::cmd::status::AgentReport;
+use crate::tmux::{KeyArg, TmuxBackend};
+
+use super::protocol::{ControlRequest, ControlResponse};
+
+/// Finds the agent addressed by a request (tmux target or agent id, else the selection)
+fn resolve_agent<'a>(
+    state: &'a AppState,
+    target: Option<&str>,
+) -> Result<&'a MonitoredAgent, ControlResponse> {
+    match target {
+        Some(t) => state
+            .agents
+            .root_agents
+            .iter()
+            .find(|a| a.target == t || a.id == t)
+            .ok_or_else(|| ControlResponse::error(format!("No agent with target '{}'", t))),
+        None => state
+            .selected_agent()
+            .ok_or_else(|| ControlResponse::error("No agent selected")),
+    }
+}
+
+/// Executes a control request against the running dashboard
+pub fn handle_request(
+    state: &mut AppState,
+    tmux_client: &dyn TmuxBackend,
+    request: ControlRequest,
+) -> ControlResponse {
+    match request {
+        ControlRequest::List => {
+            let agents: Vec<AgentReport> = state
+                .agents
+                .root_agents
+                .iter()
+                .map(AgentReport::from)
+                .collect();
+            match serde_json::to_value(agents) {
+                Ok(v) => ControlResponse::with_data(v),
+                Err(e) => ControlResponse::error(e.to_string()),
+            }
+        }
+        ControlRequest::Approve { target } => respond_to_approval(state, tmux_client, target, true),
+        ControlRequest::Reject { target } => respond_to_approval(state, tmux_client, target, false),
+        ControlRequest::SendInput { target, text } => {
+            let agent = match resolve_agent(state, target.as_deref()) {
+                Ok(a) => a,
+                Err(r) => return r,
+            };
+            let expanded = state.get_agent_keys(agent).expand_input(&text);
+            let entry = audit_entry(AuditAction::Input, agent).keys(&expanded);
+            audited(
+                state,
+                entry,
+                tmux_client.send_keys_many(&agent.target, &expanded),
+            )
+        }
+        ControlRequest::SendNumber { target, number } => {
+            let agent = match resolve_agent(state, target.as_deref()) {
+                Ok(a) => a,
+                Err(r) => return r,
+            };
+            let expanded = state.get_agent_keys(agent).expand_number(number);
+            let entry = audit_entry(AuditAction::Number, agent).keys(&expanded);
+            audited(
+                state,
+                entry,
+                tmux_client.send_keys_many(&agent.target, &expanded),
+            )
+        }
+        ControlRequest::Focus { target } => {
+            let agent = match resolve_agent(state, target.as_deref()) {
+                Ok(a) => a,
+                Err(r) => return r,
+            };
+            send_result(tmux_client.focus_pane(&agent.target))
+        }
+        ControlRequest::KillApp { target, method } => {
+            let agent = match resolve_agent(state, target.as_deref()) {
+                Ok(a) => a,
+                Err(r) => return r,
+            };
+            let method = method.unwrap_or(KillMethod::Sigterm);
+            let entry = audit_entry(AuditAction::KillApp, agent).detail(format!("{:?}", method));
+            audited(
+                state,
+                entry,
+                tmux_client.kill_application(&agent.target, &method),
+            )
+        }
+        ControlRequest::SetFilter { pattern } => {
+            state.set_filter_pattern(pattern.filter(|p| !p.is_empty()));
+            ControlResponse::ok()
+        }
+    }
+}
+
+/// Sends the agent's approve or reject keys if it is waiting for approval
+fn respond_to_approval(
+    state: &AppState,
+    tmux_client: &dyn TmuxBackend,
+    target: Option<String>,
+    approve: bool,
+) -> ControlResponse {
+    let agent = match resolve_agent(state, target.as_deref()) {
+        Ok(a) => a,
+        Err(r) => return r,
+    };
+    if agent.status.kind() != StatusKind::AwaitingApproval {
+        return ControlResponse::error(format!("Agent {} is not awaiting approval", agent.target));
+    }
+    if approve {
+        if let Some(m) = state.approval_policy.decide(agent) {
+            match m.action {
+                PolicyAction::Never => {
+                    return ControlResponse::error(format!(
+                        "Approval of {} blocked by policy '{}'",
+                        agent.target, m.rule
+                    ))
+                }
+                PolicyAction::Confirm => {
+                    return ControlResponse::error(format!(
+                        "Approval of {} needs confirmation in the dashboard (policy '{}')",
+                        agent.target, m.rule
+                    ))
+                }
+                PolicyAction::Auto => {}
+            }
+        }
+    }
+    let keys = state.get_agent_keys(agent);
+    let keys = KeyArg::names(if approve { &keys.approve } else { &keys.reject });
+    let action = if approve {
+        AuditAction::Approve
+    } else {
+        AuditAction::Reject
+    };
+    audited(
+        state,
+        audit_entry(action, agent).keys(&keys),
+        tmux_client.send_keys_many(&agent.target, &keys),
+    )
+}
+
+fn audit_entry(action: AuditAction, agent: &MonitoredAgent) -> AuditEntry {
+    AuditEntry::new(AuditSource::Ctl, action, Some(&agent.target))
+}
+
+/// Records a sent action in the audit log and builds the response
+fn audited(state: &AppState, entry: AuditEntry, result: anyhow::Result<()>) -> ControlResponse {
+    state.audit(entry.result(&result));
+    send_result(result)
+}
+
+fn send_result(result: anyhow::Result<()>) -> ControlResponse {
+    match result {
+        Ok(()) => ControlResponse::ok(),
+        Err(e) => ControlResponse::error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{AgentStatus, ApprovalType};
+    use crate::audit::{read_entries, AuditLog};
+    use crate::tmux::{FakeTmux, SentKeys};
+
+    fn state_with_agent() -> AppState {
+        let mut state = AppState::default();
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.status = AgentStatus::Idle { label: None };
+        state.agents.root_agents.push(agent);
+        state.update_visible_indices();
+        state
+    }
+
+    #[test]
+    fn test_list_and_filter() {
+        let mut state = state_with_agent();
+        let client = FakeTmux::new();
+
+        let response = handle_request(&mut state, &client, ControlRequest::List);
+        assert!(response.ok);
+        assert_eq!(response.data.unwrap()[0]["target"], "main:0.1");
+
+        let response = handle_request(
+            &mut state,
+            &client,
+            ControlRequest::SetFilter {
+                pattern: Some("claude".to_string()),
+            },
+        );
+        assert!(response.ok);
+        assert_eq!(state.filter_pattern.as_deref(), Some("claude"));
+    }
+
+    #[test]
+    fn test_approve_rejects_idle_and_unknown_targets() {
+        let mut state = state_with_agent();
+        let client = FakeTmux::new();
+
+        let response = handle_request(
+            &mut state,
+            &client,
+            ControlRequest::Approve {
+                target: Some("main:0.1".to_string()),
+            },
+        );
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("not awaiting approval"));
+
+        let response = handle_request(
+            &mut state,
+            &client,
+            ControlRequest::Reject {
+                target: Some("other:1.0".to_string()),
+            },
+        );
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("No agent"));
+    }
+
+    #[test]
+    fn test_approve_sends_agent_keys() {
+        let mut state = state_with_agent();
+        state.agents.root_agents[0].status = AgentStatus::AwaitingApproval {
+            approval_type: ApprovalType::ShellCommand,
+            details: "rm -rf build".to_string(),
+        };
+        let client = FakeTmux::new();
+        client.add_pane("main:0.1", "claude").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let audit_path = dir.path().join("audit.jsonl");
+        state.audit_log = Some(AuditLog::open(audit_path.clone(), 1 << 20, 1).unwrap());
+
+        let response = handle_request(
+            &mut state,
+            &client,
+            ControlRequest::Approve {
+                target: Some("main:0.1".to_string()),
+            },
+        );
+        assert!(response.ok);
+        let expected = state
+            .get_agent_keys(&state.agents.root_agents[0])
+            .approve
+            .clone();
+        assert_eq!(
+            client.take_sent_keys(),
+            vec![SentKeys {
+                target: "main:0.1".to_string(),
+                keys: KeyArg::names(&expected),
+            }]
+        );
+
+        let entries = read_entries(&audit_path, 1).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, AuditSource::Ctl);
+        assert_eq!(entries[0].action, AuditAction::Approve);
+        assert_eq!(entries[0].agent.as_deref(), Some("claude"));
+        assert!(entries[0]
+            .status
+            .as_deref()
+            .unwrap()
+            .starts_with("awaiting_approval"));
+        assert_eq!(entries[0].keys, expected);
+    }
+
+    #[test]
+    fn test_approve_respects_policy() {
+        use crate::app::config::ApprovalPolicyRule;
+        use crate::monitor::ApprovalPolicy;
+
+        let mut state = state_with_agent();
+        state.agents.root_agents[0].status = AgentStatus::AwaitingApproval {
+            approval_type: ApprovalType::ShellCommand,
+            details: "Bash command".to_string(),
+        };
+        state.approval_policy = ApprovalPolicy::new(&[ApprovalPolicyRule {
+            name: Some("manual shell".to_string()),
+            agent: None,
+            approval_type: Some("shell".to_string()),
+            details: None,
+            content: None,
+            action: PolicyAction::Never,
+        }]);
+        let client = FakeTmux::new();
+        client.add_pane("main:0.1", "claude").unwrap();
+
+        let response = handle_request(
+            &mut state,
+            &client,
+            ControlRequest::Approve { target: None },
+        );
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("manual shell"));
+        assert!(client.take_sent_keys().is_empty());
+
+        // Rejecting is always allowed
+        let response = handle_request(&mut state, &client, ControlRequest::Reject { target: None });
+        assert!(response.ok);
+        assert_eq!(client.take_sent_keys().len(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+mod handler;
+mod protocol;
+mod server;
+
+pub use handler::handle_request;
+pub use protocol::{ControlRequest, ControlResponse};
+pub use server::{send_request, spawn_server, ControlMessage};
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::KillMethod;
+
+/// A request sent over the control socket (one JSON object per line)
+///
+/// `target` accepts a tmux target ("main:0.1") or an agent id ("main:0.1-1234").
+/// When omitted, the agent selected in the dashboard is used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ControlRequest {
+    /// List all detected agents
+    List,
+    /// Approve a pending request
+    Approve { target: Option<String> },
+    /// Reject a pending request
+    Reject { target: Option<String> },
+    /// Send text followed by the agent's submit keys
+    SendInput {
+        target: Option<String>,
+        text: String,
+    },
+    /// Send a choice number
+    SendNumber { target: Option<String>, number: u8 },
+    /// Focus the agent's pane in tmux
+    Focus { target: Option<String> },
+    /// Kill the application in the agent's pane (default method: sigterm)
+    KillApp {
+        target: Option<String>,
+        method: Option<KillMethod>,
+    },
+    /// Set or clear (no pattern) the dashboard filter
+    SetFilter { pattern: Option<String> },
+}
+
+/// Response to a control request (one JSON object per line)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            data: None,
+            error: None,
+        }
+    }
+
+    pub fn with_data(data: serde_json::Value) -> Self {
+        Self {
+            ok: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requests() {
+        let req: ControlRequest = serde_json::from_str(r#"{"cmd":"list"}"#).unwrap();
+        assert_eq!(req, ControlRequest::List);
+
+        let req: ControlRequest =
+            serde_json::from_str(r#"{"cmd":"send_input","target":"main:0.1","text":"hi"}"#)
+                .unwrap();
+        assert_eq!(
+            req,
+            ControlRequest::SendInput {
+                target: Some("main:0.1".to_string()),
+                text: "hi".to_string()
+            }
+        );
+
+        let req: ControlRequest =
+            serde_json::from_str(r#"{"cmd":"kill_app","method":"respawn"}"#).unwrap();
+        assert_eq!(
+            req,
+            ControlRequest::KillApp {
+                target: None,
+                method: Some(KillMethod::Respawn)
+            }
+        );
+
+        assert!(serde_json::from_str::<ControlRequest>(r#"{"cmd":"explode"}"#).is_err());
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let json = serde_json::to_string(&ControlResponse::error("nope")).unwrap();
+        assert_eq!(json, r#"{"ok":false,"error":"nope"}"#);
+        let json = serde_json::to_string(&ControlResponse::ok()).unwrap();
+        assert_eq!(json, r#"{"ok":true}"#);
+    }
+}
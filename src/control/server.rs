@@ -0,0 +1,217 @@
+use anyhow::{bail, Context, Result};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::protocol::{ControlRequest, ControlResponse};
+
+/// A request forwarded to the UI loop together with the channel for its reply
+pub type ControlMessage = (ControlRequest, oneshot::Sender<ControlResponse>);
+
+/// Binds the control socket and forwards requests to `tx`.
+///
+/// A stale socket file is replaced; a socket with a live listener is an error.
+/// The socket's directory must not be writable by group or others.
+pub fn spawn_server(path: &Path, tx: mpsc::Sender<ControlMessage>) -> Result<JoinHandle<()>> {
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            bail!(
+                "Control socket {} is already in use by another tmuxx instance",
+                path.display()
+            );
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = bind_private(path)?;
+
+    let path = path.to_path_buf();
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_connection(stream, tx.clone()));
+                }
+                Err(e) => {
+                    warn!("Control socket {} accept failed: {}", path.display(), e);
+                    break;
+                }
+            }
+        }
+    }))
+}
+
+/// Binds `path` so that no other user can connect before it is restricted to `0600`.
+///
+/// The socket is created inside a fresh `0700` directory next to `path`, made
+/// private there and only then renamed into place.
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if !parent.exists() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mode = std::fs::metadata(parent)
+        .with_context(|| format!("Failed to read {}", parent.display()))?
+        .permissions()
+        .mode();
+    if mode & 0o022 != 0 {
+        bail!(
+            "Refusing control socket in {}: directory is writable by group or others (mode {:o})",
+            parent.display(),
+            mode & 0o777
+        );
+    }
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid control socket path {}", path.display()))?;
+    let staging = parent.join(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    let staged = staging.join(file_name);
+    let result = UnixListener::bind(&staged)
+        .with_context(|| format!("Failed to bind control socket {}", path.display()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&staged, path)
+                .with_context(|| format!("Failed to move control socket to {}", path.display()))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+async fn handle_connection(stream: UnixStream, tx: mpsc::Sender<ControlMessage>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        debug!("Control request: {}", line);
+        let response = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                if tx.send((request, reply_tx)).await.is_err() {
+                    ControlResponse::error("tmuxx is shutting down")
+                } else {
+                    reply_rx
+                        .await
+                        .unwrap_or_else(|_| ControlResponse::error("No response from tmuxx"))
+                }
+            }
+            Err(e) => ControlResponse::error(format!("Invalid request: {}", e)),
+        };
+
+        let mut out = match serde_json::to_string(&response) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to serialize control response: {}", e);
+                break;
+            }
+        };
+        out.push('\n');
+        if writer.write_all(out.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Sends one request to a running tmuxx and waits for the response
+pub async fn send_request(path: &Path, request: &ControlRequest) -> Result<ControlResponse> {
+    let stream = UnixStream::connect(path).await.with_context(|| {
+        format!(
+            "Failed to connect to {} (is tmuxx running with control_socket set?)",
+            path.display()
+        )
+    })?;
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let response = lines
+        .next_line()
+        .await?
+        .context("Connection closed without a response")?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip_over_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tmuxx.sock");
+        let (tx, mut rx) = mpsc::channel::<ControlMessage>(4);
+        let server = spawn_server(&path, tx).unwrap();
+
+        // Fake UI loop answering one request
+        tokio::spawn(async move {
+            if let Some((request, reply)) = rx.recv().await {
+                assert_eq!(request, ControlRequest::List);
+                let _ = reply.send(ControlResponse::with_data(serde_json::json!([])));
+            }
+        });
+
+        let response = send_request(&path, &ControlRequest::List).await.unwrap();
+        assert!(response.ok);
+        assert_eq!(response.data, Some(serde_json::json!([])));
+
+        // A second server on the same live socket must refuse to start
+        let (tx2, _rx2) = mpsc::channel::<ControlMessage>(1);
+        assert!(spawn_server(&path, tx2).is_err());
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_socket_is_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("tmuxx.sock");
+        let (tx, _rx) = mpsc::channel::<ControlMessage>(1);
+        let server = spawn_server(&path, tx).unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+        // Only the socket is left behind
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+        server.abort();
+
+        // A directory others can write to is refused
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o777)).unwrap();
+        let (tx, _rx) = mpsc::channel::<ControlMessage>(1);
+        let err = spawn_server(&shared.join("tmuxx.sock"), tx).unwrap_err();
+        assert!(err.to_string().contains("writable by group or others"));
+    }
+}
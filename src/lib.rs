@@ -1,10 +1,14 @@
 pub mod agents;
 pub mod app;
+pub mod audit;
 pub mod cmd;
+pub mod control;
 pub mod monitor;
 pub mod parsers;
 pub mod tmux;
 pub mod ui;
+pub mod util;
+pub mod workflow;
 
 pub use app::{Action, AppState, Config};
-pub use tmux::TmuxClient;
+pub use tmux::{TmuxBackend, TmuxClient};
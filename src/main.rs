@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use tmuxx::app::Config;
+use tmuxx::control::ControlRequest;
 use tmuxx::ui::run_app;
 
 #[derive(Parser)]
@@ -74,6 +75,116 @@ enum Commands {
         #[arg(short, long)]
         debug: bool,
     },
+    /// Print detected agents and their status without starting the TUI
+    Status {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Keep polling and print one JSON line (NDJSON) per poll
+        #[arg(short, long)]
+        watch: bool,
+    },
+    /// Control a running tmuxx dashboard through its control socket
+    Ctl {
+        /// Control socket path (defaults to control_socket from config)
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+    /// Show the audit log of actions sent to panes
+    Audit {
+        /// Only entries newer than this (30m, 2h, 3d, 2024-05-01 or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only targets containing this text (session:window.pane)
+        #[arg(short, long)]
+        target: Option<String>,
+
+        /// Only this agent definition id or agent id
+        #[arg(short, long)]
+        agent: Option<String>,
+
+        /// Only this action (approve, reject, input, number, choice, keys,
+        /// kill_app, kill_session, rename_session, command, launch)
+        #[arg(long)]
+        action: Option<String>,
+
+        /// Only entries by this user
+        #[arg(short, long)]
+        user: Option<String>,
+
+        /// Show only the last N entries
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+
+        /// Print raw JSON lines
+        #[arg(long)]
+        json: bool,
+
+        /// Audit log file (defaults to audit.path from config)
+        #[arg(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+}
+
+/// Agent targets accept a tmux target (session:window.pane) or an agent id.
+/// When omitted, the agent selected in the dashboard is used.
+#[derive(clap::Subcommand)]
+enum CtlCommand {
+    /// List detected agents as JSON
+    List,
+    /// Approve a pending request
+    Approve { target: Option<String> },
+    /// Reject a pending request
+    Reject { target: Option<String> },
+    /// Send text to an agent
+    SendInput {
+        #[arg(short, long)]
+        target: Option<String>,
+        text: String,
+    },
+    /// Send a choice number (0-9) to an agent
+    SendNumber {
+        #[arg(short, long)]
+        target: Option<String>,
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=9))]
+        number: u8,
+    },
+    /// Focus the agent's pane in tmux
+    Focus { target: Option<String> },
+    /// Kill the application in the agent's pane
+    KillApp {
+        /// sigterm, ctrlc_ctrld or respawn
+        #[arg(short, long, default_value = "sigterm")]
+        method: String,
+        target: Option<String>,
+    },
+    /// Set the dashboard filter (clears it when no pattern is given)
+    SetFilter { pattern: Option<String> },
+}
+
+impl CtlCommand {
+    fn into_request(self) -> Result<ControlRequest> {
+        Ok(match self {
+            CtlCommand::List => ControlRequest::List,
+            CtlCommand::Approve { target } => ControlRequest::Approve { target },
+            CtlCommand::Reject { target } => ControlRequest::Reject { target },
+            CtlCommand::SendInput { target, text } => ControlRequest::SendInput { target, text },
+            CtlCommand::SendNumber { target, number } => {
+                ControlRequest::SendNumber { target, number }
+            }
+            CtlCommand::Focus { target } => ControlRequest::Focus { target },
+            CtlCommand::KillApp { method, target } => ControlRequest::KillApp {
+                target,
+                method: Some(tmuxx::cmd::ctl::parse_kill_method(&method)?),
+            },
+            CtlCommand::SetFilter { pattern } => ControlRequest::SetFilter { pattern },
+        })
+    }
 }
 
 #[tokio::main]
@@ -154,6 +265,51 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(Commands::Status { json, watch }) = cli.command {
+        return tmuxx::cmd::status::run_status(
+            config,
+            tmuxx::cmd::status::StatusArgs { json, watch },
+        )
+        .await;
+    }
+
+    if let Some(Commands::Ctl { socket, command }) = cli.command {
+        return tmuxx::cmd::ctl::run_ctl(
+            &config,
+            tmuxx::cmd::ctl::CtlArgs {
+                socket,
+                request: command.into_request()?,
+            },
+        )
+        .await;
+    }
+
+    if let Some(Commands::Audit {
+        since,
+        target,
+        agent,
+        action,
+        user,
+        limit,
+        json,
+        path,
+    }) = cli.command
+    {
+        return tmuxx::cmd::audit::run_audit(
+            &config,
+            tmuxx::cmd::audit::AuditArgs {
+                since,
+                target,
+                agent,
+                action,
+                user,
+                limit,
+                json,
+                path,
+            },
+        );
+    }
+
     // Debug: show loaded config and bindings
     if cli.debug_config {
         println!("=== Loaded Config ===");
@@ -0,0 +1,347 @@
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info, warn};
+
+use crate::agents::{AgentStatus, MonitoredAgent, StatusKind, StatusTransition};
+use crate::app::config::{HookTimeoutAction, HooksConfig};
+use crate::util::shell_escape;
+
+/// Grace period between SIGTERM and SIGKILL for timed out hooks
+const HOOK_KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// Status-change events that can trigger hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookEvent {
+    ApprovalNeeded,
+    Error,
+    Idle,
+    Done,
+    Working,
+    Change,
+}
+
+impl HookEvent {
+    /// Returns the event name as used in config (without the `on_` prefix)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::ApprovalNeeded => "approval_needed",
+            HookEvent::Error => "error",
+            HookEvent::Idle => "idle",
+            HookEvent::Done => "done",
+            HookEvent::Working => "working",
+            HookEvent::Change => "change",
+        }
+    }
+
+    /// Returns the events triggered by a transition
+    pub fn for_transition(transition: &StatusTransition) -> Vec<HookEvent> {
+        let mut events = Vec::new();
+        match transition.to.kind() {
+            StatusKind::AwaitingApproval => events.push(HookEvent::ApprovalNeeded),
+            StatusKind::Error => events.push(HookEvent::Error),
+            StatusKind::Idle => {
+                events.push(HookEvent::Idle);
+                if transition.from.kind() == StatusKind::Processing {
+                    events.push(HookEvent::Done);
+                }
+            }
+            StatusKind::Processing => events.push(HookEvent::Working),
+            StatusKind::Unknown => {}
+        }
+        events.push(HookEvent::Change);
+        events
+    }
+
+    /// Returns the command configured for this event
+    pub fn command<'a>(&self, hooks: &'a HooksConfig) -> Option<&'a str> {
+        let cmd = match self {
+            HookEvent::ApprovalNeeded => &hooks.on_approval_needed,
+            HookEvent::Error => &hooks.on_error,
+            HookEvent::Idle => &hooks.on_idle,
+            HookEvent::Done => &hooks.on_done,
+            HookEvent::Working => &hooks.on_working,
+            HookEvent::Change => &hooks.on_change,
+        };
+        cmd.as_deref().filter(|c| !c.trim().is_empty())
+    }
+}
+
+/// Returns the short approval type of a status ("error"/"attention" for non-approvals)
+pub fn approval_type_str(status: &AgentStatus) -> &str {
+    match status {
+        AgentStatus::AwaitingApproval { approval_type, .. } => approval_type.short_desc(),
+        AgentStatus::Error { .. } => "error",
+        _ => "attention",
+    }
+}
+
+/// Replaces `{name}` placeholders in a single pass over the template, so a
+/// substituted value is never scanned for placeholders again. Unknown
+/// placeholders are left as they are.
+pub fn expand_placeholders(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+    PLACEHOLDER
+        .replace_all(template, |caps: &Captures| {
+            value(&caps[1]).unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Value of an agent placeholder shared by notifications and hooks.
+/// All dynamic values are shell-escaped to prevent injection.
+pub fn agent_placeholder(name: &str, agent: &MonitoredAgent) -> Option<String> {
+    Some(match name {
+        "agent" => shell_escape(&agent.name),
+        "session" => shell_escape(&agent.session),
+        "target" => shell_escape(&agent.target),
+        "path" => shell_escape(&agent.path),
+        "approval_type" => approval_type_str(&agent.status).to_string(), // Internal enum, safe
+        _ => return None,
+    })
+}
+
+/// Expands a hook command template for a transition
+pub fn expand_hook_command(
+    template: &str,
+    event: HookEvent,
+    agent: &MonitoredAgent,
+    transition: &StatusTransition,
+) -> String {
+    expand_placeholders(template, |name| {
+        Some(match name {
+            "event" => event.as_str().to_string(), // Internal enum, safe
+            "from" => transition.from.kind().as_str().to_string(),
+            "to" => transition.to.kind().as_str().to_string(),
+            "status" => shell_escape(&transition.to.short_text()),
+            "rule" => shell_escape(transition.rule.as_deref().unwrap_or("")),
+            "duration" => transition.duration.as_secs().to_string(), // Number, safe
+            _ => return agent_placeholder(name, agent),
+        })
+    })
+}
+
+/// Runs hook commands for status transitions with debouncing and timeouts
+#[derive(Debug, Default)]
+pub struct HookRunner {
+    /// Last run time per (agent id, event)
+    last_run: HashMap<(String, HookEvent), Instant>,
+}
+
+impl HookRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs all hooks triggered by a transition
+    pub fn handle(
+        &mut self,
+        transition: &StatusTransition,
+        agent: &MonitoredAgent,
+        hooks: &HooksConfig,
+        now: Instant,
+    ) {
+        for event in HookEvent::for_transition(transition) {
+            let Some(template) = event.command(hooks) else {
+                continue;
+            };
+            if !self.should_run(&agent.id, event, hooks.debounce(), now) {
+                debug!("Hook on_{} for {} debounced", event.as_str(), agent.target);
+                continue;
+            }
+            let cmd = expand_hook_command(template, event, agent, transition);
+            spawn_hook(
+                cmd,
+                hooks.timeout(),
+                hooks.timeout_action.unwrap_or_default(),
+            );
+        }
+    }
+
+    /// Returns true (and records the run) if the hook is outside its debounce window
+    fn should_run(
+        &mut self,
+        agent_id: &str,
+        event: HookEvent,
+        debounce: Duration,
+        now: Instant,
+    ) -> bool {
+        let key = (agent_id.to_string(), event);
+        if let Some(last) = self.last_run.get(&key) {
+            if now.saturating_duration_since(*last) < debounce {
+                return false;
+            }
+        }
+        self.last_run.insert(key, now);
+        true
+    }
+
+    /// Drops debounce state for agents that disappeared
+    pub fn retain_seen(&mut self, seen: &HashSet<String>) {
+        self.last_run.retain(|(id, _), _| seen.contains(id));
+    }
+}
+
+/// Spawns a hook command in the background and enforces the timeout policy
+fn spawn_hook(cmd: String, timeout: Duration, action: HookTimeoutAction) {
+    debug!("Running hook: {}", cmd);
+    let mut child = match tokio::process::Command::new("bash")
+        .args(["-c", &cmd])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("Failed to spawn hook command: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(Ok(status)) if !status.success() => {
+                info!("Hook exited with {}: {}", status, cmd);
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!("Failed to wait for hook: {}", e),
+            Err(_) => {
+                warn!("Hook timed out after {:?}: {}", timeout, cmd);
+                match action {
+                    HookTimeoutAction::Detach => {}
+                    HookTimeoutAction::Kill => {
+                        let _ = child.kill().await;
+                    }
+                    HookTimeoutAction::Term => {
+                        if let Some(pid) = child.id() {
+                            unsafe {
+                                libc::kill(pid as i32, libc::SIGTERM);
+                            }
+                        }
+                        if tokio::time::timeout(HOOK_KILL_GRACE, child.wait())
+                            .await
+                            .is_err()
+                        {
+                            let _ = child.kill().await;
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::ApprovalType;
+    use chrono::Local;
+
+    fn transition(from: AgentStatus, to: AgentStatus) -> StatusTransition {
+        StatusTransition {
+            agent_id: "main:0.1-42".to_string(),
+            target: "main:0.1".to_string(),
+            from,
+            to,
+            at: Local::now(),
+            duration: Duration::from_secs(75),
+            rule: Some("state_rules[0]".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_events_for_transition() {
+        let working = AgentStatus::Processing {
+            activity: "Thinking".to_string(),
+        };
+        let idle = AgentStatus::Idle { label: None };
+        let approval = AgentStatus::AwaitingApproval {
+            approval_type: ApprovalType::ShellCommand,
+            details: "rm".to_string(),
+        };
+
+        assert_eq!(
+            HookEvent::for_transition(&transition(working.clone(), idle.clone())),
+            vec![HookEvent::Idle, HookEvent::Done, HookEvent::Change]
+        );
+        assert_eq!(
+            HookEvent::for_transition(&transition(AgentStatus::Unknown, idle)),
+            vec![HookEvent::Idle, HookEvent::Change]
+        );
+        assert_eq!(
+            HookEvent::for_transition(&transition(working, approval)),
+            vec![HookEvent::ApprovalNeeded, HookEvent::Change]
+        );
+    }
+
+    #[test]
+    fn test_expand_hook_command_escapes_values() {
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.name = "it's me".to_string();
+        agent.path = "/tmp/x".to_string();
+        agent.status = AgentStatus::Idle { label: None };
+        let t = transition(
+            AgentStatus::Processing {
+                activity: String::new(),
+            },
+            agent.status.clone(),
+        );
+        let cmd = expand_hook_command(
+            "echo {event} {agent} {from}->{to} {duration}",
+            HookEvent::Done,
+            &agent,
+            &t,
+        );
+        assert_eq!(cmd, "echo done 'it'\\''s me' processing->idle 75");
+
+        // Substituted values are not expanded again
+        agent.name = "{path}".to_string();
+        let t = transition(agent.status.clone(), AgentStatus::Unknown);
+        let cmd = expand_hook_command(
+            "notify {agent} {status} {nope}",
+            HookEvent::Change,
+            &agent,
+            &t,
+        );
+        assert_eq!(cmd, "notify '{path}' 'Unknown' {nope}");
+    }
+
+    #[test]
+    fn test_debounce_and_agent_override() {
+        let global = HooksConfig {
+            on_done: Some("echo global".to_string()),
+            on_error: Some("echo error".to_string()),
+            debounce_ms: Some(1000),
+            ..Default::default()
+        };
+        let agent = HooksConfig {
+            on_done: Some("echo agent".to_string()),
+            on_error: Some(String::new()),
+            ..Default::default()
+        };
+        let merged = global.overlay(&agent);
+        assert_eq!(HookEvent::Done.command(&merged), Some("echo agent"));
+        assert_eq!(HookEvent::Error.command(&merged), None);
+        assert_eq!(merged.debounce(), Duration::from_millis(1000));
+
+        let mut runner = HookRunner::new();
+        let now = Instant::now();
+        assert!(runner.should_run("a", HookEvent::Done, merged.debounce(), now));
+        assert!(!runner.should_run(
+            "a",
+            HookEvent::Done,
+            merged.debounce(),
+            now + Duration::from_millis(500)
+        ));
+        assert!(runner.should_run("b", HookEvent::Done, merged.debounce(), now));
+        assert!(runner.should_run(
+            "a",
+            HookEvent::Done,
+            merged.debounce(),
+            now + Duration::from_millis(1500)
+        ));
+    }
+}
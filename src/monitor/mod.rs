@@ -1,5 +1,11 @@
+mod hooks;
+mod policy;
+mod registry;
 mod system_stats;
 mod task;
 
+pub use hooks::{HookEvent, HookRunner};
+pub use policy::{ApprovalPolicy, PolicyDecision, PolicyMatch};
+pub use registry::{AgentRecord, AgentRegistry};
 pub use system_stats::{SystemStats, SystemStatsCollector};
 pub use task::{MonitorTask, MonitorUpdate};
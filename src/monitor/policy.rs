@@ -0,0 +1,257 @@
+use chrono::{DateTime, Local};
+use regex::Regex;
+use tracing::warn;
+
+use crate::agents::{AgentStatus, MonitoredAgent};
+use crate::app::config::{ApprovalPolicyRule, PolicyAction};
+
+/// Number of lines at the bottom of the pane matched by `content` patterns
+pub const POLICY_CONTENT_LINES: usize = 40;
+
+/// Policy verdict for one pending approval
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyMatch {
+    pub action: PolicyAction,
+    /// Name of the deciding rule
+    pub rule: String,
+}
+
+/// An automatic approval decision, kept for auditing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub at: DateTime<Local>,
+    pub agent_id: String,
+    pub target: String,
+    /// Approval type and details at decision time (e.g. "Shell Command: Bash command")
+    pub approval: String,
+    pub rule: String,
+    pub action: PolicyAction,
+    /// Keys sent to the pane
+    pub keys: Vec<String>,
+    /// Set if sending the approval keys failed
+    pub error: Option<String>,
+}
+
+impl PolicyDecision {
+    /// One-line description for the status bar and logs
+    pub fn summary(&self) -> String {
+        match &self.error {
+            None => format!(
+                "Auto-approved {} ({}) by policy '{}'",
+                self.target, self.approval, self.rule
+            ),
+            Some(e) => format!(
+                "Auto-approve of {} by policy '{}' failed: {}",
+                self.target, self.rule, e
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CompiledPolicyRule {
+    name: String,
+    agent: Option<Regex>,
+    approval_type: Option<String>,
+    details: Option<Regex>,
+    content: Option<Regex>,
+    action: PolicyAction,
+}
+
+impl CompiledPolicyRule {
+    /// Compiles a rule. Invalid patterns drop `auto` rules and make the condition
+    /// match everything for `confirm`/`never` rules, so mistakes fail safe.
+    fn compile(index: usize, rule: &ApprovalPolicyRule) -> Option<Self> {
+        let name = rule
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("approval_policies[{}]", index));
+        let mut valid = true;
+        let mut compile = |pattern: &Option<String>| {
+            pattern.as_deref().and_then(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!("Invalid regex in approval policy '{}': {}", name, e);
+                    valid = false;
+                    None
+                }
+            })
+        };
+        let agent = compile(&rule.agent);
+        let details = compile(&rule.details);
+        let content = compile(&rule.content);
+
+        if !valid && rule.action == PolicyAction::Auto {
+            return None;
+        }
+        Some(Self {
+            name,
+            agent,
+            approval_type: rule.approval_type.as_ref().map(|t| t.to_lowercase()),
+            details,
+            content,
+            action: rule.action,
+        })
+    }
+
+    fn matches(&self, agent: &MonitoredAgent, approval_type: &str, details: &str) -> bool {
+        self.agent
+            .as_ref()
+            .is_none_or(|re| re.is_match(&agent.config_id))
+            && self
+                .approval_type
+                .as_deref()
+                .is_none_or(|t| t == approval_type)
+            && self.details.as_ref().is_none_or(|re| re.is_match(details))
+            && self
+                .content
+                .as_ref()
+                .is_none_or(|re| re.is_match(&content_tail(&agent.last_content)))
+    }
+}
+
+/// Returns the last POLICY_CONTENT_LINES lines of a pane
+fn content_tail(content: &str) -> String {
+    let lines: Vec<&str> = content.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(POLICY_CONTENT_LINES)..].join("\n")
+}
+
+/// Compiled `[[approval_policies]]`
+#[derive(Debug)]
+pub struct ApprovalPolicy {
+    rules: Vec<CompiledPolicyRule>,
+}
+
+impl ApprovalPolicy {
+    pub fn new(rules: &[ApprovalPolicyRule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .enumerate()
+                .filter_map(|(i, r)| CompiledPolicyRule::compile(i, r))
+                .collect(),
+        }
+    }
+
+    /// Decides how a pending approval is handled. Returns None if the agent is not
+    /// awaiting approval or no rule matches (manual approval as usual).
+    pub fn decide(&self, agent: &MonitoredAgent) -> Option<PolicyMatch> {
+        let AgentStatus::AwaitingApproval {
+            approval_type,
+            details,
+        } = &agent.status
+        else {
+            return None;
+        };
+        let type_name = approval_type.config_name();
+
+        // The most restrictive matching rule wins; the first one on ties
+        let mut best: Option<&CompiledPolicyRule> = None;
+        for rule in &self.rules {
+            if rule.matches(agent, type_name, details)
+                && best.is_none_or(|b| rule.action > b.action)
+            {
+                best = Some(rule);
+            }
+        }
+        best.map(|r| PolicyMatch {
+            action: r.action,
+            rule: r.name.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::ApprovalType;
+
+    fn rule(action: PolicyAction) -> ApprovalPolicyRule {
+        ApprovalPolicyRule {
+            name: None,
+            agent: None,
+            approval_type: None,
+            details: None,
+            content: None,
+            action,
+        }
+    }
+
+    fn awaiting(approval_type: ApprovalType, content: &str) -> MonitoredAgent {
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.status = AgentStatus::AwaitingApproval {
+            approval_type,
+            details: "menu".to_string(),
+        };
+        agent.last_content = content.to_string();
+        agent
+    }
+
+    #[test]
+    fn test_most_restrictive_rule_wins() {
+        let policy = ApprovalPolicy::new(&[
+            ApprovalPolicyRule {
+                name: Some("safe edits".to_string()),
+                agent: Some("^claude$".to_string()),
+                approval_type: Some("edit".to_string()),
+                ..rule(PolicyAction::Auto)
+            },
+            ApprovalPolicyRule {
+                name: Some("no rm -rf".to_string()),
+                content: Some(r"rm\s+-rf".to_string()),
+                ..rule(PolicyAction::Never)
+            },
+            ApprovalPolicyRule {
+                approval_type: Some("shell".to_string()),
+                ..rule(PolicyAction::Confirm)
+            },
+        ]);
+
+        let edit = awaiting(ApprovalType::FileEdit, "Edit src/main.rs\n❯ 1. Yes");
+        assert_eq!(
+            policy.decide(&edit),
+            Some(PolicyMatch {
+                action: PolicyAction::Auto,
+                rule: "safe edits".to_string(),
+            })
+        );
+
+        let shell = awaiting(ApprovalType::ShellCommand, "Bash command\n\nls -la\n");
+        assert_eq!(
+            policy.decide(&shell).map(|m| (m.action, m.rule)),
+            Some((PolicyAction::Confirm, "approval_policies[2]".to_string()))
+        );
+
+        // A dangerous edit is blocked even though the auto rule matches too
+        let rm = awaiting(ApprovalType::FileEdit, "Bash command\n\nrm -rf build\n");
+        assert_eq!(
+            policy.decide(&rm).map(|m| m.action),
+            Some(PolicyAction::Never)
+        );
+
+        let mut idle = edit.clone();
+        idle.status = AgentStatus::Error {
+            message: "boom".to_string(),
+        };
+        assert_eq!(policy.decide(&idle), None);
+    }
+
+    #[test]
+    fn test_invalid_patterns_fail_safe() {
+        let policy = ApprovalPolicy::new(&[
+            ApprovalPolicyRule {
+                details: Some("(".to_string()),
+                ..rule(PolicyAction::Auto)
+            },
+            ApprovalPolicyRule {
+                content: Some("[".to_string()),
+                ..rule(PolicyAction::Never)
+            },
+        ]);
+        let agent = awaiting(ApprovalType::FileEdit, "anything");
+        assert_eq!(
+            policy.decide(&agent).map(|m| m.action),
+            Some(PolicyAction::Never)
+        );
+    }
+}
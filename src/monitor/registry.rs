@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+
+use crate::agents::{
+    repeat_id, split_repeat_id, AgentStatus, MonitoredAgent, StatusKind, StatusTransition,
+    Subagent, SubagentStatus,
+};
+
+/// Maximum number of transitions kept per agent
+pub const TRANSITION_HISTORY_LIMIT: usize = 50;
+
+/// Maximum number of subagents kept per agent
+pub const SUBAGENT_HISTORY_LIMIT: usize = 50;
+
+/// Persistent per-agent state carried across monitor polls
+#[derive(Debug, Clone)]
+pub struct AgentRecord {
+    /// When the agent was first seen
+    pub first_seen: Instant,
+    /// Status reported by the last poll
+    pub status: AgentStatus,
+    /// When the current status kind was entered
+    pub status_since: Instant,
+    /// Wall-clock time when the current status kind was entered
+    pub status_changed_at: DateTime<Local>,
+    /// Recent transitions (oldest first, bounded by TRANSITION_HISTORY_LIMIT)
+    pub transitions: VecDeque<StatusTransition>,
+    /// Last status with a different kind than the current one
+    pub previous_status: Option<AgentStatus>,
+    /// Accumulated time per status kind (completed stints only)
+    pub status_durations: HashMap<StatusKind, Duration>,
+    /// Subagents seen so far (oldest first, bounded by SUBAGENT_HISTORY_LIMIT)
+    pub subagents: Vec<Subagent>,
+    /// Number of visible starts per subagent id base in the last poll
+    visible_starts: HashMap<String, usize>,
+}
+
+impl AgentRecord {
+    fn new(status: AgentStatus, now: Instant) -> Self {
+        Self {
+            first_seen: now,
+            status,
+            status_since: now,
+            status_changed_at: Local::now(),
+            transitions: VecDeque::new(),
+            previous_status: None,
+            status_durations: HashMap::new(),
+            subagents: Vec::new(),
+            visible_starts: HashMap::new(),
+        }
+    }
+
+    /// Merges the subagents parsed from the latest poll into the history,
+    /// keeping start times and stamping the finish time of each subagent
+    fn merge_subagents(&mut self, parsed: &[Subagent], parent_idle: bool) {
+        let now = Local::now();
+
+        // Identical starts are numbered from the bottom, so each new one pushes
+        // the numbers of the earlier ones up: shift the tracked ids to match
+        let mut visible_starts: HashMap<String, usize> = HashMap::new();
+        for sub in parsed.iter().filter(|s| !s.id.is_empty()) {
+            *visible_starts
+                .entry(split_repeat_id(&sub.id).0.to_string())
+                .or_default() += 1;
+        }
+        for (base, &count) in &visible_starts {
+            let previous = self.visible_starts.get(base).copied().unwrap_or(0);
+            let added = count.saturating_sub(previous);
+            if added == 0 {
+                continue;
+            }
+            for entry in &mut self.subagents {
+                let (entry_base, n) = split_repeat_id(&entry.id);
+                if entry_base == base {
+                    entry.id = repeat_id(base, n + added);
+                }
+            }
+        }
+        self.visible_starts = visible_starts;
+
+        let mut seen = HashSet::new();
+        for sub in parsed {
+            // Updates without id (start line scrolled out) go to the oldest
+            // running subagent of that type that is no longer visible
+            let index = if sub.id.is_empty() {
+                self.subagents.iter().position(|h| {
+                    h.is_running()
+                        && h.subagent_type == sub.subagent_type
+                        && !parsed.iter().any(|p| p.id == h.id)
+                        && !seen.contains(&h.id)
+                })
+            } else {
+                self.subagents.iter().position(|h| h.id == sub.id)
+            };
+
+            match index {
+                Some(i) => {
+                    let entry = &mut self.subagents[i];
+                    seen.insert(entry.id.clone());
+                    // A finished subagent does not start over; only one given
+                    // up on (Unknown) may still report back
+                    let reopened = sub.is_running()
+                        && entry.finished_at.is_some()
+                        && entry.status != SubagentStatus::Unknown;
+                    if entry.status != sub.status && !reopened {
+                        entry.status = sub.status.clone();
+                        entry.finished_at = (!entry.is_running()).then_some(now);
+                    }
+                }
+                None if !sub.id.is_empty() => {
+                    let mut entry = sub.clone();
+                    entry.finished_at = (!entry.is_running()).then_some(now);
+                    seen.insert(entry.id.clone());
+                    self.subagents.push(entry);
+                }
+                None => {}
+            }
+        }
+
+        // A running subagent that vanished while its parent is idle will not
+        // report back
+        if parent_idle {
+            for entry in &mut self.subagents {
+                if entry.is_running() && !seen.contains(&entry.id) {
+                    entry.status = SubagentStatus::Unknown;
+                    entry.finished_at = Some(now);
+                }
+            }
+        }
+
+        while self.subagents.len() > SUBAGENT_HISTORY_LIMIT {
+            let oldest = self
+                .subagents
+                .iter()
+                .position(|s| !s.is_running())
+                .unwrap_or(0);
+            self.subagents.remove(oldest);
+        }
+    }
+}
+
+/// Registry of agents keyed by agent id (target + pid)
+///
+/// A pane whose process is replaced gets a new id and therefore a fresh record.
+#[derive(Debug, Default)]
+pub struct AgentRegistry {
+    records: HashMap<String, AgentRecord>,
+}
+
+impl AgentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the freshly parsed status of an agent and copies the persistent
+    /// timing data back onto it. Returns the transition if the status kind changed.
+    pub fn observe(
+        &mut self,
+        agent: &mut MonitoredAgent,
+        now: Instant,
+    ) -> Option<StatusTransition> {
+        let mut transition = None;
+        let record = self
+            .records
+            .entry(agent.id.clone())
+            .or_insert_with(|| AgentRecord::new(agent.status.clone(), now));
+
+        if record.status.kind() != agent.status.kind() {
+            let elapsed = now.saturating_duration_since(record.status_since);
+            *record
+                .status_durations
+                .entry(record.status.kind())
+                .or_default() += elapsed;
+            record.previous_status = Some(record.status.clone());
+            record.status_since = now;
+            record.status_changed_at = Local::now();
+
+            let t = StatusTransition {
+                agent_id: agent.id.clone(),
+                target: agent.target.clone(),
+                from: record.status.clone(),
+                to: agent.status.clone(),
+                at: record.status_changed_at,
+                duration: elapsed,
+                rule: agent.status_rule.clone(),
+            };
+            record.transitions.push_back(t.clone());
+            while record.transitions.len() > TRANSITION_HISTORY_LIMIT {
+                record.transitions.pop_front();
+            }
+            transition = Some(t);
+        }
+        record.status = agent.status.clone();
+        record.merge_subagents(&agent.subagents, agent.status.kind() == StatusKind::Idle);
+
+        agent.started_at = record.first_seen;
+        agent.status_since = record.status_since;
+        agent.status_changed_at = record.status_changed_at;
+        agent.previous_status = record.previous_status.clone();
+        agent.status_durations = record.status_durations.clone();
+        agent.transitions = record.transitions.clone();
+        agent.subagents = record.subagents.clone();
+
+        transition
+    }
+
+    /// Drops records for agents that were not seen in the latest poll
+    pub fn retain_seen(&mut self, seen: &HashSet<String>) {
+        self.records.retain(|id, _| seen.contains(id));
+    }
+
+    /// Returns the record for an agent id
+    pub fn get(&self, id: &str) -> Option<&AgentRecord> {
+        self.records.get(id)
+    }
+
+    /// Returns the number of tracked agents
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if no agents are tracked
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::SubagentType;
+
+    fn agent(id: &str, status: AgentStatus) -> MonitoredAgent {
+        let mut agent = MonitoredAgent::test_agent("main:0.1", 42);
+        agent.id = id.to_string();
+        agent.status = status;
+        agent
+    }
+
+    #[test]
+    fn test_registry_keeps_first_seen_and_tracks_status_time() {
+        let mut registry = AgentRegistry::new();
+        let t0 = Instant::now();
+        let idle = AgentStatus::Idle { label: None };
+        let working = AgentStatus::Processing {
+            activity: "Thinking".to_string(),
+        };
+
+        let mut a = agent("main:0.1-42", idle.clone());
+        assert!(registry.observe(&mut a, t0).is_none());
+
+        let t1 = t0 + Duration::from_secs(10);
+        let mut a = agent("main:0.1-42", working.clone());
+        a.status_rule = Some("state_rules[1]".to_string());
+        let t = registry.observe(&mut a, t1).expect("transition");
+        assert_eq!(t.from, idle);
+        assert_eq!(t.to, working);
+        assert_eq!(t.rule.as_deref(), Some("state_rules[1]"));
+        assert_eq!(t.duration, Duration::from_secs(10));
+        assert_eq!(a.transitions.len(), 1);
+        assert_eq!(a.started_at, t0);
+        assert_eq!(a.status_since, t1);
+        assert_eq!(a.previous_status, Some(idle));
+        assert_eq!(
+            a.status_durations.get(&StatusKind::Idle),
+            Some(&Duration::from_secs(10))
+        );
+
+        // Same kind with different activity text is not a transition
+        let t2 = t1 + Duration::from_secs(5);
+        let mut a = agent(
+            "main:0.1-42",
+            AgentStatus::Processing {
+                activity: "Reading".to_string(),
+            },
+        );
+        assert!(registry.observe(&mut a, t2).is_none());
+        assert_eq!(a.status_since, t1);
+    }
+
+    #[test]
+    fn test_registry_bounds_transition_history() {
+        let mut registry = AgentRegistry::new();
+        let mut now = Instant::now();
+        for i in 0..(TRANSITION_HISTORY_LIMIT + 10) {
+            let status = if i % 2 == 0 {
+                AgentStatus::Idle { label: None }
+            } else {
+                AgentStatus::Processing {
+                    activity: String::new(),
+                }
+            };
+            let mut a = agent("main:0.1-42", status);
+            registry.observe(&mut a, now);
+            now += Duration::from_secs(1);
+        }
+        let record = registry.get("main:0.1-42").unwrap();
+        assert_eq!(record.transitions.len(), TRANSITION_HISTORY_LIMIT);
+    }
+
+    #[test]
+    fn test_registry_prunes_vanished_agents() {
+        let mut registry = AgentRegistry::new();
+        let now = Instant::now();
+        let mut a = agent("main:0.1-42", AgentStatus::Unknown);
+        let mut b = agent("main:0.2-43", AgentStatus::Unknown);
+        registry.observe(&mut a, now);
+        registry.observe(&mut b, now);
+        assert_eq!(registry.len(), 2);
+
+        let seen: HashSet<String> = ["main:0.1-42".to_string()].into_iter().collect();
+        registry.retain_seen(&seen);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get("main:0.2-43").is_none());
+    }
+
+    #[test]
+    fn test_registry_tracks_subagent_lifecycle() {
+        let mut registry = AgentRegistry::new();
+        let now = Instant::now();
+        let working = AgentStatus::Processing {
+            activity: "Delegating".to_string(),
+        };
+        let explore = || {
+            Subagent::new(
+                "Explore: Find config".to_string(),
+                SubagentType::Explore,
+                "Find config".to_string(),
+            )
+        };
+
+        let mut a = agent("main:0.1-42", working.clone());
+        a.subagents = vec![explore()];
+        registry.observe(&mut a, now);
+        let started_at = a.subagents[0].started_at;
+
+        // Still visible: same entry, same start time
+        let mut a = agent("main:0.1-42", working.clone());
+        a.subagents = vec![explore()];
+        registry.observe(&mut a, now);
+        assert_eq!(a.subagents.len(), 1);
+        assert_eq!(a.subagents[0].started_at, started_at);
+        assert!(a.subagents[0].finished_at.is_none());
+
+        // Start line scrolled out, the failure line is applied by type
+        let mut a = agent("main:0.1-42", working.clone());
+        a.subagents = vec![
+            Subagent::new(String::new(), SubagentType::Explore, String::new())
+                .with_status(SubagentStatus::Failed),
+            Subagent::new(
+                "Plan: Draft".to_string(),
+                SubagentType::Plan,
+                "Draft".to_string(),
+            ),
+        ];
+        registry.observe(&mut a, now);
+        assert_eq!(a.subagents.len(), 2);
+        assert_eq!(a.subagents[0].status, SubagentStatus::Failed);
+        assert!(a.subagents[0].finished_at.is_some());
+        assert!(a.subagents[1].is_running());
+
+        // Parent went idle without the subagent reporting back
+        let mut a = agent("main:0.1-42", AgentStatus::Idle { label: None });
+        registry.observe(&mut a, now);
+        assert_eq!(a.subagents[0].status, SubagentStatus::Failed);
+        assert_eq!(a.subagents[1].status, SubagentStatus::Unknown);
+        assert_eq!(a.active_subagent_count(), 0);
+    }
+
+    #[test]
+    fn test_registry_keeps_duplicate_subagents_apart() {
+        let mut registry = AgentRegistry::new();
+        let now = Instant::now();
+        let working = AgentStatus::Processing {
+            activity: "Delegating".to_string(),
+        };
+        let explore = |n: usize, status: SubagentStatus| {
+            Subagent::new(
+                repeat_id("Explore: Find config", n),
+                SubagentType::Explore,
+                "Find config".to_string(),
+            )
+            .with_status(status)
+        };
+        let mut poll = |subagents: Vec<Subagent>| {
+            let mut a = agent("main:0.1-42", working.clone());
+            a.subagents = subagents;
+            registry.observe(&mut a, now);
+            a.subagents
+        };
+
+        // One start, then an identical one below it renumbers the first
+        poll(vec![explore(1, SubagentStatus::Completed)]);
+        let history = poll(vec![
+            explore(2, SubagentStatus::Completed),
+            explore(1, SubagentStatus::Running),
+        ]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, SubagentStatus::Completed);
+        assert!(history[1].is_running());
+
+        // The first start scrolls out: the second keeps its own entry
+        let history = poll(vec![explore(1, SubagentStatus::Running)]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, SubagentStatus::Completed);
+        assert!(history[0].finished_at.is_some());
+        assert!(history[1].is_running());
+
+        let history = poll(vec![explore(1, SubagentStatus::Completed)]);
+        assert_eq!(history[1].status, SubagentStatus::Completed);
+        assert_eq!(history.iter().filter(|s| s.is_running()).count(), 0);
+    }
+}
@@ -1,27 +1,49 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use parking_lot::Mutex;
 use regex::Regex;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use crate::agents::{AgentStatus, MonitoredAgent};
-use crate::app::config::NotificationMode;
+use crate::agents::{
+    format_duration_long, AgentStatus, GitInfo, MonitoredAgent, StatusKind, StatusTransition,
+    Subagent,
+};
+use crate::app::config::{NotificationMode, PolicyAction};
 use crate::app::{AgentTree, Config};
-use crate::parsers::ParserRegistry;
-use crate::tmux::{refresh_process_cache, TmuxClient};
+use crate::parsers::{AgentMetrics, AgentParser, ParserRegistry};
+use crate::tmux::{refresh_process_cache, ControlModeWatcher, KeyArg, PaneInfo, TmuxBackend};
+use crate::util::shell_escape;
+
+use super::hooks::{agent_placeholder, expand_placeholders, HookRunner};
+use super::policy::{ApprovalPolicy, PolicyDecision};
+use super::registry::AgentRegistry;
+
+/// Minimum delay between an output event and the poll it triggers (coalesces bursts)
+const CONTROL_MODE_EVENT_DELAY: Duration = Duration::from_millis(100);
+
+/// Pane list is re-read at least this often in control mode, even without events
+const CONTROL_MODE_RESYNC_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Update message sent from monitor to UI
 #[derive(Debug, Clone)]
 pub struct MonitorUpdate {
     pub agents: AgentTree,
+    /// Status transitions observed during this poll
+    pub transitions: Vec<StatusTransition>,
+    /// Approvals sent automatically by `[[approval_policies]]` during this poll
+    pub decisions: Vec<PolicyDecision>,
 }
 
 /// Background task that monitors tmux panes for AI agents
 pub struct MonitorTask {
-    tmux_client: Arc<TmuxClient>,
+    tmux_client: Arc<dyn TmuxBackend>,
     parser_registry: Arc<ParserRegistry>,
     tx: mpsc::Sender<MonitorUpdate>,
     poll_interval: Duration,
@@ -40,11 +62,68 @@ pub struct MonitorTask {
     global_notification_sent: bool,
     /// Shared flag - UI sets true on interaction, monitor reads and clears
     user_interacted: Arc<AtomicBool>,
+    /// Persistent agent state (first seen, status timing) keyed by agent id
+    registry: AgentRegistry,
+    /// Runs `[hooks]` commands on status transitions
+    hook_runner: HookRunner,
+    /// Control mode event source (`tmux_control_mode`), started by `run`
+    watcher: Option<ControlModeWatcher>,
+    /// Pane list and contents reused between polls in control mode
+    pane_cache: PaneCache,
+    /// Parse results by agent id, reused while the pane content is unchanged
+    parse_cache: HashMap<String, ParsedPane>,
+    /// Compiled `[[approval_policies]]`
+    approval_policy: ApprovalPolicy,
+    /// Agent id -> start of the approval that was auto-approved (approve once per request)
+    auto_approved: HashMap<String, Instant>,
+    /// Auto-approval decisions not yet sent to the UI
+    pending_decisions: Vec<PolicyDecision>,
+    /// Git state by directory, re-read in the background
+    git_cache: Arc<Mutex<HashMap<String, GitCacheEntry>>>,
+}
+
+/// Git state of one directory
+#[derive(Default)]
+struct GitCacheEntry {
+    info: Option<GitInfo>,
+    read_at: Option<Instant>,
+    /// A background read is in progress
+    reading: bool,
+}
+
+/// Parse results of one pane, valid for as long as its content hash matches
+#[derive(Clone)]
+struct ParsedPane {
+    parser_id: String,
+    content_hash: u64,
+    status: AgentStatus,
+    status_rule: Option<String>,
+    subagents: Vec<Subagent>,
+    metrics: AgentMetrics,
+    /// When the content last changed
+    output_changed_at: Instant,
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Tmux state cached between polls when running in control mode
+#[derive(Default)]
+struct PaneCache {
+    panes: Vec<PaneInfo>,
+    /// Target -> pane id (e.g. "%12") of panes on the default server
+    pane_ids: HashMap<String, String>,
+    /// Pane id -> last captured content
+    contents: HashMap<String, String>,
+    last_listed: Option<Instant>,
 }
 
 impl MonitorTask {
     pub fn new(
-        tmux_client: Arc<TmuxClient>,
+        tmux_client: Arc<dyn TmuxBackend>,
         parser_registry: Arc<ParserRegistry>,
         tx: mpsc::Sender<MonitorUpdate>,
         poll_interval: Duration,
@@ -53,6 +132,7 @@ impl MonitorTask {
     ) -> Self {
         // Get current session once at startup (for ignore_self feature)
         let current_session = tmux_client.get_current_session().ok().flatten();
+        let approval_policy = ApprovalPolicy::new(&config.approval_policies);
 
         Self {
             tmux_client,
@@ -66,15 +146,33 @@ impl MonitorTask {
             notified_agents: HashSet::new(),
             global_notification_sent: false,
             user_interacted,
+            registry: AgentRegistry::new(),
+            hook_runner: HookRunner::new(),
+            watcher: None,
+            pane_cache: PaneCache::default(),
+            parse_cache: HashMap::new(),
+            approval_policy,
+            auto_approved: HashMap::new(),
+            pending_decisions: Vec::new(),
+            git_cache: Arc::default(),
         }
     }
 
     /// Runs the monitoring loop
     pub async fn run(mut self) {
+        if self.config.tmux_control_mode {
+            info!("Using tmux control mode for pane updates");
+            self.watcher = Some(ControlModeWatcher::spawn());
+        }
+
         loop {
             match self.poll_agents().await {
-                Ok(tree) => {
-                    let update = MonitorUpdate { agents: tree };
+                Ok((tree, transitions)) => {
+                    let update = MonitorUpdate {
+                        agents: tree,
+                        transitions,
+                        decisions: std::mem::take(&mut self.pending_decisions),
+                    };
                     if self.tx.send(update).await.is_err() {
                         debug!("Monitor channel closed, stopping");
                         break;
@@ -85,29 +183,209 @@ impl MonitorTask {
                 }
             }
 
-            tokio::time::sleep(self.poll_interval).await;
+            match &self.watcher {
+                // Poll early when a pane produced output, otherwise keep ticking
+                // so time-based state (hysteresis, durations) still advances
+                Some(watcher) => {
+                    tokio::select! {
+                        _ = watcher.changed() => {
+                            tokio::time::sleep(CONTROL_MODE_EVENT_DELAY).await;
+                        }
+                        _ = tokio::time::sleep(self.poll_interval) => {}
+                    }
+                }
+                None => tokio::time::sleep(self.poll_interval).await,
+            }
+        }
+    }
+
+    /// Returns the current pane list.
+    ///
+    /// In control mode the list and pane contents are cached and only refreshed
+    /// for panes that reported output or after layout changes.
+    fn current_panes(&mut self) -> anyhow::Result<Vec<PaneInfo>> {
+        let Some(watcher) = &self.watcher else {
+            // Refresh process cache once per poll cycle (much faster than per-pane)
+            refresh_process_cache();
+            return self.tmux_client.list_panes();
+        };
+
+        let events = watcher.take_events();
+        let cache = &mut self.pane_cache;
+        if !watcher.is_connected() {
+            // No events are arriving, fall back to capturing everything
+            cache.contents.clear();
+        }
+        for pane_id in &events.dirty_panes {
+            cache.contents.remove(pane_id);
+        }
+
+        // Output alone does not re-list: a new command in a pane usually renames its
+        // window (a layout event), anything else is picked up by the resync
+        let resync_due = cache
+            .last_listed
+            .is_none_or(|t| t.elapsed() >= CONTROL_MODE_RESYNC_INTERVAL);
+        if events.layout_changed || resync_due || !watcher.is_connected() {
+            refresh_process_cache();
+            cache.panes = self.tmux_client.list_panes()?;
+            // Control mode only watches the default server; panes on other servers
+            // have no cache entry and are captured on every poll
+            cache.pane_ids = cache
+                .panes
+                .iter()
+                .filter(|p| p.server.is_none() && !p.id.is_empty())
+                .map(|p| (p.target(), p.id.clone()))
+                .collect();
+            let live: HashSet<&String> = cache.pane_ids.values().collect();
+            cache.contents.retain(|id, _| live.contains(id));
+            cache.last_listed = Some(Instant::now());
         }
+
+        Ok(cache.panes.clone())
     }
 
-    async fn poll_agents(&mut self) -> anyhow::Result<AgentTree> {
-        // Refresh process cache once per poll cycle (much faster than per-pane)
-        refresh_process_cache();
+    /// Captures a pane, reusing the cached content in control mode if it had no output
+    fn capture_pane(&mut self, target: &str) -> anyhow::Result<String> {
+        if self.watcher.is_none() {
+            return self.tmux_client.capture_pane(target);
+        }
 
-        let panes = self.tmux_client.list_panes()?;
+        let pane_id = self.pane_cache.pane_ids.get(target).cloned();
+        if let Some(content) = pane_id
+            .as_ref()
+            .and_then(|id| self.pane_cache.contents.get(id))
+        {
+            return Ok(content.clone());
+        }
+
+        let content = self.tmux_client.capture_pane(target)?;
+        if let Some(id) = pane_id {
+            self.pane_cache.contents.insert(id, content.clone());
+        }
+        Ok(content)
+    }
+
+    /// Parses a pane, reusing the previous results if its content did not change
+    fn parse_pane(
+        &mut self,
+        agent_id: &str,
+        parser: &dyn AgentParser,
+        content: &str,
+        now: Instant,
+    ) -> ParsedPane {
+        let hash = content_hash(content);
+        let cached = self.parse_cache.get(agent_id);
+        if let Some(cached) = cached {
+            if cached.content_hash == hash && cached.parser_id == parser.agent_id() {
+                return cached.clone();
+            }
+        }
+
+        let output_changed_at = match cached {
+            Some(c) if c.content_hash == hash => c.output_changed_at,
+            _ => now,
+        };
+        let (status, status_rule) = parser.parse_status_with_rule(content);
+        let parsed = ParsedPane {
+            parser_id: parser.agent_id().to_string(),
+            content_hash: hash,
+            status,
+            status_rule,
+            subagents: parser.parse_subagents(content),
+            metrics: parser.parse_metrics(content),
+            output_changed_at,
+        };
+        self.parse_cache
+            .insert(agent_id.to_string(), parsed.clone());
+        parsed
+    }
+
+    /// Git state of a local pane directory, re-read every `git_refresh_ms`.
+    /// The first read of a directory is waited for; later ones run `git status`
+    /// on a blocking thread, so a poll gets the last state read.
+    fn git_info(&self, pane: &PaneInfo, now: Instant) -> Option<GitInfo> {
+        let refresh = Duration::from_millis(self.config.timing.git_refresh_ms);
+        if refresh.is_zero() || pane.path.is_empty() {
+            return None;
+        }
+        // Directories of panes on remote hosts don't exist here
+        if let Some(label) = &pane.server {
+            let remote = self
+                .config
+                .tmux_servers
+                .iter()
+                .any(|s| &s.label == label && s.host.is_some());
+            if remote {
+                return None;
+            }
+        }
+        let mut cache = self.git_cache.lock();
+        let entry = cache.entry(pane.path.clone()).or_default();
+        if entry.read_at.is_none() && !entry.reading {
+            // Without the lock, so background reads of other paths can finish
+            entry.reading = true;
+            drop(cache);
+            let info = GitInfo::read(Path::new(&pane.path));
+            let mut cache = self.git_cache.lock();
+            let entry = cache.entry(pane.path.clone()).or_default();
+            entry.info = info.clone();
+            entry.read_at = Some(Instant::now());
+            entry.reading = false;
+            return info;
+        }
+        let stale = entry
+            .read_at
+            .is_none_or(|read_at| now.duration_since(read_at) >= refresh);
+        if stale && !entry.reading {
+            entry.reading = true;
+            let git_cache = self.git_cache.clone();
+            let path = pane.path.clone();
+            tokio::task::spawn_blocking(move || {
+                let info = GitInfo::read(Path::new(&path));
+                if let Some(entry) = git_cache.lock().get_mut(&path) {
+                    entry.info = info;
+                    entry.read_at = Some(Instant::now());
+                    entry.reading = false;
+                }
+            });
+        }
+        entry.info.clone()
+    }
+
+    /// Returns the configured stall timeout of an agent type
+    fn stalled_after(&self, agent_id: &str) -> Option<Duration> {
+        self.config
+            .agents
+            .iter()
+            .find(|a| a.id == agent_id)
+            .and_then(|a| a.stalled_after_secs)
+            .map(Duration::from_secs)
+    }
+
+    async fn poll_agents(&mut self) -> anyhow::Result<(AgentTree, Vec<StatusTransition>)> {
+        let panes = self.current_panes()?;
+        let parser_registry = self.parser_registry.clone();
         let mut tree = AgentTree::new();
+        let mut seen_ids = HashSet::new();
+        let mut transitions = Vec::new();
 
         for pane in panes {
-            // Filter out ignored sessions (before any processing)
+            // Filter out ignored sessions (before any processing).
+            // tmuxx itself only runs on the default server, so ignore_self applies there only.
+            let current_session = match pane.server {
+                None => self.current_session.as_deref(),
+                Some(_) => None,
+            };
             if self
                 .config
-                .should_ignore_session(&pane.session, self.current_session.as_deref())
+                .should_ignore_session(&pane.session, current_session)
             {
                 debug!("Ignoring session: {}", pane.session);
                 continue;
             }
 
             // Find suitable parser (possibly checking content)
-            let candidates = self.parser_registry.find_candidates_for_pane(&pane);
+            let candidates = parser_registry.find_candidates_for_pane(&pane);
             if candidates.is_empty() {
                 continue;
             }
@@ -119,7 +397,7 @@ impl MonitorTask {
             for parser in candidates {
                 if parser.requires_content_check() {
                     if captured_content.is_none() {
-                        match self.tmux_client.capture_pane(&target) {
+                        match self.capture_pane(&target) {
                             Ok(c) => captured_content = Some(c),
                             Err(e) => {
                                 error!("Failed to capture pane {}: {}", target, e);
@@ -143,7 +421,7 @@ impl MonitorTask {
                 let content = if let Some(c) = captured_content {
                     c
                 } else {
-                    match self.tmux_client.capture_pane(&target) {
+                    match self.capture_pane(&target) {
                         Ok(c) => c,
                         Err(e) => {
                             error!("Failed to capture pane {}: {}", target, e);
@@ -152,11 +430,14 @@ impl MonitorTask {
                     }
                 };
 
-                // Parse status from content
-                let mut status = parser.parse_status(&content);
+                // Parse status, subagents and context (cached while the content is unchanged)
+                let now = Instant::now();
+                let agent_id = format!("{}-{}", target, pane.pid);
+                let parsed = self.parse_pane(&agent_id, parser, &content, now);
+                let mut status = parsed.status;
+                let mut status_rule = parsed.status_rule;
 
                 // apply hysteresis: if status is now Idle but was recently active, keep as Processing
-                let now = Instant::now();
                 let is_active = matches!(
                     status,
                     AgentStatus::Processing { .. } | AgentStatus::AwaitingApproval { .. }
@@ -174,15 +455,25 @@ impl MonitorTask {
                             status = AgentStatus::Processing {
                                 activity: "Working...".to_string(),
                             };
+                            status_rule = Some("hysteresis".to_string());
                         }
                     }
                 }
 
-                // Parse subagents
-                let subagents = parser.parse_subagents(&content);
-
-                // Parse context remaining
-                let context_remaining = parser.parse_context_remaining(&content);
+                // Processing without any new output for too long is reported as stalled
+                if status.kind() == StatusKind::Processing {
+                    if let Some(limit) = self.stalled_after(parser.agent_id()) {
+                        if now.duration_since(parsed.output_changed_at) >= limit {
+                            status = AgentStatus::Error {
+                                message: format!(
+                                    "Stalled (no output for {})",
+                                    format_duration_long(limit)
+                                ),
+                            };
+                            status_rule = Some("stalled".to_string());
+                        }
+                    }
+                }
 
                 // Calculate process indicators
                 let mut active_indicators = Vec::new();
@@ -202,7 +493,7 @@ impl MonitorTask {
 
                 // Create monitored agent
                 let mut agent = MonitoredAgent::new(
-                    format!("{}-{}", target, pane.pid),
+                    agent_id,
                     parser.agent_id().to_string(),
                     parser.agent_display_name(&pane),
                     parser.agent_color().map(|s| s.to_string()),
@@ -217,24 +508,56 @@ impl MonitorTask {
                     pane.pid,
                     parser.is_ai(),
                 );
+                agent.server = pane.server.clone();
                 agent.status = status;
-                agent.subagents = subagents;
+                agent.status_rule = status_rule;
+                agent.subagents = parsed.subagents;
                 agent.last_content = content;
-                agent.context_remaining = context_remaining;
+                agent.last_output_at = parsed.output_changed_at;
+                agent.context_remaining = parsed.metrics.context_remaining;
+                agent.tokens = parsed.metrics.tokens;
+                agent.cost = parsed.metrics.cost;
                 agent.active_indicators = active_indicators;
+                agent.git = self.git_info(&pane, now);
                 agent.touch(); // Update last_updated
+                if let Some(transition) = self.registry.observe(&mut agent, now) {
+                    debug!(
+                        "Status transition {}: {} (rule: {})",
+                        transition.target,
+                        transition.summary(),
+                        transition.rule.as_deref().unwrap_or("-")
+                    );
+                    self.run_hooks(&transition, &agent, now);
+                    transitions.push(transition);
+                }
+                self.apply_approval_policy(&agent);
+                seen_ids.insert(agent.id.clone());
 
                 tree.root_agents.push(agent);
             }
         }
 
-        // Sort agents by target for consistent ordering
-        tree.root_agents.sort_by(|a, b| a.target.cmp(&b.target));
+        // Forget agents whose pane or process disappeared
+        self.registry.retain_seen(&seen_ids);
+        self.hook_runner.retain_seen(&seen_ids);
+        self.parse_cache.retain(|id, _| seen_ids.contains(id));
+        self.auto_approved.retain(|id, _| seen_ids.contains(id));
+        let paths: HashSet<&str> = tree.root_agents.iter().map(|a| a.path.as_str()).collect();
+        self.git_cache
+            .lock()
+            .retain(|path, _| paths.contains(path.as_str()));
+
+        // Sort agents by server (default first, as in the tree) and target for consistent ordering
+        tree.root_agents.sort_by(|a, b| {
+            a.server
+                .cmp(&b.server)
+                .then_with(|| a.target.cmp(&b.target))
+        });
 
         // Notification logic
         self.handle_notifications(&tree);
 
-        Ok(tree)
+        Ok((tree, transitions))
     }
 
     /// Handle desktop notifications for agents awaiting approval
@@ -319,10 +642,72 @@ impl MonitorTask {
         }
     }
 
-    /// Escape a string for safe use in shell commands (single-quote escaping)
-    fn shell_escape(s: &str) -> String {
-        // Replace single quotes with '\'' (end quote, escaped quote, start quote)
-        format!("'{}'", s.replace('\'', "'\\''"))
+    /// Sends the approve keys for requests an `auto` policy covers, once per request
+    fn apply_approval_policy(&mut self, agent: &MonitoredAgent) {
+        let Some(verdict) = self.approval_policy.decide(agent) else {
+            return;
+        };
+        if verdict.action != PolicyAction::Auto
+            || self.auto_approved.get(&agent.id) == Some(&agent.status_since)
+        {
+            return;
+        }
+        self.auto_approved
+            .insert(agent.id.clone(), agent.status_since);
+
+        let keys = self
+            .config
+            .agents
+            .iter()
+            .find(|a| a.id == agent.config_id)
+            .map(|a| a.keys.approve.clone())
+            .unwrap_or_else(|| crate::app::config::AgentKeys::default().approve);
+        let error = self
+            .tmux_client
+            .send_keys_many(&agent.target, &KeyArg::names(&keys))
+            .err()
+            .map(|e| e.to_string());
+
+        let approval = match &agent.status {
+            AgentStatus::AwaitingApproval {
+                approval_type,
+                details,
+            } => format!("{}: {}", approval_type, details),
+            _ => String::new(),
+        };
+        let decision = PolicyDecision {
+            at: chrono::Local::now(),
+            agent_id: agent.id.clone(),
+            target: agent.target.clone(),
+            approval,
+            rule: verdict.rule,
+            action: verdict.action,
+            keys,
+            error,
+        };
+        match &decision.error {
+            None => info!("{}", decision.summary()),
+            Some(_) => warn!("{}", decision.summary()),
+        }
+        self.pending_decisions.push(decision);
+    }
+
+    /// Run configured hooks for a status transition (agent overrides win over `[hooks]`)
+    fn run_hooks(&mut self, transition: &StatusTransition, agent: &MonitoredAgent, now: Instant) {
+        let agent_hooks = self
+            .config
+            .agents
+            .iter()
+            .find(|a| a.id == agent.config_id)
+            .and_then(|a| a.hooks.as_ref());
+        let hooks = match agent_hooks {
+            Some(overrides) => self.config.hooks.overlay(overrides),
+            None => self.config.hooks.clone(),
+        };
+        if hooks.is_empty() {
+            return;
+        }
+        self.hook_runner.handle(transition, agent, &hooks, now);
     }
 
     /// Send a desktop notification for an agent
@@ -332,25 +717,15 @@ impl MonitorTask {
             None => return,
         };
 
-        let approval_type = match &agent.status {
-            AgentStatus::AwaitingApproval { approval_type, .. } => approval_type.short_desc(),
-            AgentStatus::Error { .. } => "error",
-            _ => "attention",
-        };
-
         // Shell-escape all dynamic placeholder values to prevent injection
-        let cmd = cmd_template
-            .replace("{title}", "tmuxx") // Hardcoded, safe
-            .replace(
-                "{message}",
-                &Self::shell_escape(&format!("{} needs approval", agent.name)),
-            )
-            .replace("{agent}", &Self::shell_escape(&agent.name))
-            .replace("{session}", &Self::shell_escape(&agent.session))
-            .replace("{target}", &Self::shell_escape(&agent.target))
-            .replace("{path}", &Self::shell_escape(&agent.path))
-            .replace("{approval_type}", approval_type) // Internal enum, safe
-            .replace("{count}", &count.to_string()); // Number, safe
+        let cmd = expand_placeholders(cmd_template, |name| {
+            Some(match name {
+                "title" => "tmuxx".to_string(), // Hardcoded, safe
+                "message" => shell_escape(&format!("{} needs approval", agent.name)),
+                "count" => count.to_string(), // Number, safe
+                _ => return agent_placeholder(name, agent),
+            })
+        });
 
         debug!("Sending notification: {}", cmd);
 
@@ -363,3 +738,228 @@ impl MonitorTask {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::{FakeTmux, NewPane};
+
+    fn monitor(fake: &Arc<FakeTmux>, config: Config) -> MonitorTask {
+        let (tx, _rx) = mpsc::channel(4);
+        MonitorTask::new(
+            fake.clone(),
+            Arc::new(ParserRegistry::with_config(&config)),
+            tx,
+            Duration::from_millis(10),
+            config,
+            Arc::new(AtomicBool::new(false)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_detects_agents_from_fixtures() {
+        let fake = Arc::new(FakeTmux::new());
+        fake.add_pane("work:0.0", "claude").unwrap();
+        fake.add_pane("work:0.1", "claude").unwrap();
+        fake.script_fixtures("work:0.0", &["claude/case_approval_proceed.txt"])
+            .unwrap();
+        fake.script_fixtures("work:0.1", &["claude/case_idle_sauteed.txt"])
+            .unwrap();
+
+        let mut task = monitor(&fake, Config::default());
+        let (tree, _) = task.poll_agents().await.unwrap();
+
+        let kinds: Vec<_> = tree
+            .root_agents
+            .iter()
+            .map(|a| (a.target.as_str(), a.config_id.as_str(), a.status.kind()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("work:0.0", "claude", StatusKind::AwaitingApproval),
+                ("work:0.1", "claude", StatusKind::Idle),
+            ]
+        );
+
+        // Panes that disappear are dropped on the next poll
+        fake.remove_pane("work:0.0");
+        let (tree, _) = task.poll_agents().await.unwrap();
+        assert_eq!(tree.root_agents.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_first_poll_reads_git_info() {
+        let tmp = tempfile::tempdir().unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q", "-b", "main"])
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let fake = Arc::new(FakeTmux::new());
+        fake.new_pane(&NewPane {
+            session: "work".to_string(),
+            window: None,
+            cwd: Some(tmp.path().to_string_lossy().into_owned()),
+            env: Vec::new(),
+            command: "claude".to_string(),
+        })
+        .unwrap();
+        let mut task = monitor(&fake, Config::default());
+
+        let (tree, _) = task.poll_agents().await.unwrap();
+        let git = tree.root_agents[0].git.as_ref().unwrap();
+        assert_eq!(git.branch, "main");
+    }
+
+    #[tokio::test]
+    async fn test_hysteresis_keeps_recent_work_processing() {
+        let fake = Arc::new(FakeTmux::new());
+        fake.add_pane("work:0.0", "claude").unwrap();
+        let frames = ["claude/case_working_1.txt", "claude/case_idle_sauteed.txt"];
+
+        let mut config = Config::default();
+        config.timing.hysteresis_ms = 60_000;
+        fake.script_fixtures("work:0.0", &frames).unwrap();
+        let mut task = monitor(&fake, config.clone());
+
+        let (tree, _) = task.poll_agents().await.unwrap();
+        assert_eq!(tree.root_agents[0].status.kind(), StatusKind::Processing);
+        let (tree, transitions) = task.poll_agents().await.unwrap();
+        let agent = &tree.root_agents[0];
+        assert_eq!(agent.status.kind(), StatusKind::Processing);
+        assert_eq!(agent.status_rule.as_deref(), Some("hysteresis"));
+        assert!(transitions.is_empty());
+
+        // Without hysteresis the idle screen is reported right away
+        config.timing.hysteresis_ms = 0;
+        fake.script_fixtures("work:0.0", &frames).unwrap();
+        let mut task = monitor(&fake, config);
+        task.poll_agents().await.unwrap();
+        let (tree, transitions) = task.poll_agents().await.unwrap();
+        assert_eq!(tree.root_agents[0].status.kind(), StatusKind::Idle);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].from.kind(), StatusKind::Processing);
+    }
+
+    #[tokio::test]
+    async fn test_notifies_once_per_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("notifications.log");
+        let fake = Arc::new(FakeTmux::new());
+        fake.add_pane("work:0.0", "claude").unwrap();
+        fake.script_fixtures("work:0.0", &["claude/case_approval_proceed.txt"])
+            .unwrap();
+
+        let config = Config {
+            notification_command: Some(format!("echo {{message}} >> {}", log.display())),
+            notification_delay_ms: 0,
+            notification_mode: NotificationMode::Each,
+            ..Default::default()
+        };
+        let mut task = monitor(&fake, config);
+
+        let read_log = || std::fs::read_to_string(&log).unwrap_or_default();
+        task.poll_agents().await.unwrap();
+        for _ in 0..50 {
+            if !read_log().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(read_log().contains("needs approval"));
+
+        // Still waiting for approval: no second notification
+        task.poll_agents().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(read_log().lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_output_is_reused_and_can_stall() {
+        let fake = Arc::new(FakeTmux::new());
+        fake.add_pane("work:0.0", "claude").unwrap();
+        fake.script_fixtures(
+            "work:0.0",
+            &[
+                "claude/case_working_1.txt",
+                "claude/case_working_1.txt",
+                "claude/case_working_2.txt",
+            ],
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        for agent in config.agents.iter_mut().filter(|a| a.id == "claude") {
+            agent.stalled_after_secs = Some(60);
+        }
+        let mut task = monitor(&fake, config);
+
+        let (tree, _) = task.poll_agents().await.unwrap();
+        let first_output = tree.root_agents[0].last_output_at;
+        let (tree, _) = task.poll_agents().await.unwrap();
+        assert_eq!(tree.root_agents[0].last_output_at, first_output);
+        let (tree, _) = task.poll_agents().await.unwrap();
+        let agent = &tree.root_agents[0];
+        assert!(agent.last_output_at > first_output);
+        assert_eq!(agent.status.kind(), StatusKind::Processing);
+
+        // Pretend the pane has been silent for two minutes
+        let parsed = task.parse_cache.get_mut(&agent.id).unwrap();
+        parsed.output_changed_at -= Duration::from_secs(120);
+        let (tree, transitions) = task.poll_agents().await.unwrap();
+        let agent = &tree.root_agents[0];
+        assert_eq!(agent.status.kind(), StatusKind::Error);
+        assert_eq!(agent.status_rule.as_deref(), Some("stalled"));
+        assert_eq!(transitions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_auto_approves_once_per_request() {
+        use crate::app::config::ApprovalPolicyRule;
+
+        let fake = Arc::new(FakeTmux::new());
+        fake.add_pane("work:0.0", "claude").unwrap();
+        fake.add_pane("work:0.1", "claude").unwrap();
+        fake.script_fixtures("work:0.0", &["claude/case_approval_create.txt"])
+            .unwrap();
+        fake.script_fixtures("work:0.1", &["claude/case_approval_bash-tool-call-1-2.txt"])
+            .unwrap();
+
+        let auto = ApprovalPolicyRule {
+            name: Some("claude menus".to_string()),
+            agent: Some("^claude$".to_string()),
+            approval_type: None,
+            details: None,
+            content: None,
+            action: PolicyAction::Auto,
+        };
+        let manual_yarn = ApprovalPolicyRule {
+            name: Some("no installs".to_string()),
+            content: Some(r"yarn install".to_string()),
+            action: PolicyAction::Never,
+            ..auto.clone()
+        };
+        let config = Config {
+            approval_policies: vec![auto, manual_yarn],
+            ..Default::default()
+        };
+        let mut task = monitor(&fake, config);
+
+        task.poll_agents().await.unwrap();
+        let sent = fake.take_sent_keys();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].target, "work:0.0");
+        let decisions = std::mem::take(&mut task.pending_decisions);
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].rule, "claude menus");
+        assert_eq!(decisions[0].error, None);
+
+        // The same request is not approved twice while it is still on screen
+        task.poll_agents().await.unwrap();
+        assert!(fake.take_sent_keys().is_empty());
+        assert!(task.pending_decisions.is_empty());
+    }
+}
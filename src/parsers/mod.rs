@@ -24,6 +24,16 @@ pub enum MatchStrength {
     Strong = 2, // Command/Ancestor/Process-tree match
 }
 
+/// Usage figures read from the pane (see `metrics_rules`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AgentMetrics {
+    /// Context remaining in percent (0-100)
+    pub context_remaining: Option<u8>,
+    pub tokens: Option<u64>,
+    /// Session cost in dollars
+    pub cost: Option<f64>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct AgentSummary {
     pub current_activity: Option<String>,
@@ -64,16 +74,21 @@ pub trait AgentParser: Send + Sync {
     /// Parses the pane content and returns the agent status
     fn parse_status(&self, content: &str) -> AgentStatus;
 
+    /// Parses the status and also names the rule that produced it (if known)
+    fn parse_status_with_rule(&self, content: &str) -> (AgentStatus, Option<String>) {
+        (self.parse_status(content), None)
+    }
+
     /// Parses subagents from the content (default: empty)
     fn parse_subagents(&self, content: &str) -> Vec<Subagent> {
         let _ = content;
         Vec::new()
     }
 
-    /// Parses context remaining percentage from content (default: None)
-    fn parse_context_remaining(&self, content: &str) -> Option<u8> {
+    /// Parses context, token and cost figures from content (default: none)
+    fn parse_metrics(&self, content: &str) -> AgentMetrics {
         let _ = content;
-        None
+        AgentMetrics::default()
     }
 
     /// Parses summary info from content
@@ -224,6 +239,8 @@ mod tests {
 
         // Test finding parsers with various detection strings
         let claude_pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "main".to_string(),
             window: 0,
             window_name: "code".to_string(),
@@ -239,6 +256,8 @@ mod tests {
         assert!(registry.find_parser_for_pane(&claude_pane).is_some());
 
         let opencode_pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "main".to_string(),
             window: 0,
             window_name: "code".to_string(),
@@ -255,6 +274,8 @@ mod tests {
 
         // Test detection via child processes
         let child_claude_pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "main".to_string(),
             window: 0,
             window_name: "code".to_string(),
@@ -1,9 +1,11 @@
 use regex::Regex;
 use tracing::warn;
 
-use crate::agents::{AgentStatus, AgentType, ApprovalType, Subagent};
-use crate::app::config::{AgentConfig, MatcherConfig};
-use crate::parsers::{safe_tail, AgentParser, AgentSummary, MatchStrength};
+use crate::agents::{
+    repeat_id, AgentStatus, AgentType, ApprovalType, Subagent, SubagentStatus, SubagentType,
+};
+use crate::app::config::{AgentConfig, ChoiceRule, MatcherConfig};
+use crate::parsers::{safe_tail, AgentMetrics, AgentParser, AgentSummary, MatchStrength};
 
 /// Split content on structural separator area (the Claude/Pi prompt sandwich)
 /// This looks from the bottom and identifies the start of the UI chrome.
@@ -57,6 +59,7 @@ pub struct UniversalParser {
     state_rules: Vec<CompiledStateRule>,
     subagent_rules: Option<CompiledSubagentRules>,
     summary_rules: Option<CompiledSummaryRules>,
+    metrics_rules: Option<CompiledMetricsRules>,
     highlight_rules: Vec<CompiledHighlightRule>,
     layout_rules: Option<CompiledLayoutRules>,
 }
@@ -87,6 +90,84 @@ struct CompiledStateRule {
     approval_type: Option<String>,
     last_lines: Option<usize>,
     refinements: Vec<CompiledRefinement>,
+    choices: Option<CompiledChoiceRule>,
+}
+
+struct CompiledChoiceRule {
+    re: Regex,
+    multi_select: Option<Regex>,
+}
+
+impl CompiledChoiceRule {
+    fn compile(rule: &ChoiceRule, agent_name: &str) -> Option<Self> {
+        let re = match Regex::new(&rule.pattern) {
+            Ok(re) if re.capture_names().flatten().any(|n| n == "label") => re,
+            Ok(_) => {
+                warn!(
+                    "Choice pattern '{}' for agent {} has no 'label' group",
+                    rule.pattern, agent_name
+                );
+                return None;
+            }
+            Err(e) => {
+                warn!(
+                    "Invalid choice pattern '{}' for agent {}: {}",
+                    rule.pattern, agent_name, e
+                );
+                return None;
+            }
+        };
+        let multi_select = rule
+            .multi_select
+            .as_ref()
+            .and_then(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!(
+                        "Invalid multi_select pattern '{}' for agent {}: {}",
+                        p, agent_name, e
+                    );
+                    None
+                }
+            });
+        Some(Self { re, multi_select })
+    }
+
+    /// Returns the choice labels of the last menu in `content` and whether it is multi-select
+    fn extract(&self, content: &str) -> Option<(Vec<String>, bool)> {
+        let mut choices = Vec::new();
+        let mut multi_select = false;
+        let mut last_number = 0;
+
+        for line in content.lines() {
+            let Some(caps) = self.re.captures(line) else {
+                continue;
+            };
+            let Some(label) = caps.name("label").map(|m| m.as_str().trim()) else {
+                continue;
+            };
+            if let Some(number) = caps.name("number").and_then(|m| m.as_str().parse().ok()) {
+                if number == 1 {
+                    // A new menu starts; earlier numbered lists are not part of it
+                    choices.clear();
+                    multi_select = false;
+                } else if number != last_number + 1 {
+                    continue;
+                }
+                last_number = number;
+            }
+            choices.push(label.to_string());
+            if self
+                .multi_select
+                .as_ref()
+                .is_some_and(|re| re.is_match(line))
+            {
+                multi_select = true;
+            }
+        }
+
+        (!choices.is_empty()).then_some((choices, multi_select))
+    }
 }
 
 /// Where to apply the refinement pattern
@@ -106,12 +187,14 @@ struct CompiledRefinement {
     kind: Option<crate::app::config::RuleType>,
     approval_type: Option<String>,
     location: MatchLocation,
+    choices: Option<CompiledChoiceRule>,
 }
 
 struct CompiledSubagentRules {
     start: Regex,
-    _running: Regex,
-    _complete: Regex,
+    running: Regex,
+    complete: Regex,
+    failed: Option<Regex>,
 }
 
 struct CompiledSummaryRules {
@@ -121,6 +204,33 @@ struct CompiledSummaryRules {
     tool_use: Option<Regex>,
 }
 
+struct CompiledMetricsRules {
+    context_remaining: Option<Regex>,
+    context_used: Option<Regex>,
+    tokens: Option<Regex>,
+    cost: Option<Regex>,
+}
+
+/// Parses a count like "1,234", "12.5k" or "1.2M"
+fn parse_count(text: &str) -> Option<u64> {
+    let text = text.trim().replace(',', "");
+    let (number, multiplier) = match text.chars().last()? {
+        'k' | 'K' => (&text[..text.len() - 1], 1_000.0),
+        'm' | 'M' => (&text[..text.len() - 1], 1_000_000.0),
+        _ => (text.as_str(), 1.0),
+    };
+    let value: f64 = number.parse().ok()?;
+    Some((value * multiplier).round() as u64)
+}
+
+/// Capture groups of the last match of `re` in `content`
+fn last_groups<'a>(re: &Regex, content: &'a str) -> Vec<&'a str> {
+    re.captures_iter(content)
+        .last()
+        .map(|caps| caps.iter().skip(1).flatten().map(|m| m.as_str()).collect())
+        .unwrap_or_default()
+}
+
 struct CompiledHighlightRule {
     re: Regex,
     color: String,
@@ -211,6 +321,10 @@ impl UniversalParser {
                             kind: r.kind.clone(),
                             approval_type: r.approval_type.clone(),
                             location,
+                            choices: r
+                                .choices
+                                .as_ref()
+                                .and_then(|c| CompiledChoiceRule::compile(c, &config.name)),
                         });
                     }
                     Err(e) => warn!(
@@ -228,19 +342,26 @@ impl UniversalParser {
                 approval_type: rule.approval_type.clone(),
                 last_lines: rule.last_lines,
                 refinements,
+                choices: rule
+                    .choices
+                    .as_ref()
+                    .and_then(|c| CompiledChoiceRule::compile(c, &config.name)),
             });
         }
 
         let subagent_rules = config.subagent_rules.as_ref().and_then(|rules| {
-            if let (Ok(start), Ok(running), Ok(complete)) = (
+            let failed = rules.failed.as_deref().map(Regex::new).transpose();
+            if let (Ok(start), Ok(running), Ok(complete), Ok(failed)) = (
                 Regex::new(&rules.start),
                 Regex::new(&rules.running),
                 Regex::new(&rules.complete),
+                failed,
             ) {
                 Some(CompiledSubagentRules {
                     start,
-                    _running: running,
-                    _complete: complete,
+                    running,
+                    complete,
+                    failed,
                 })
             } else {
                 warn!("Invalid subagent rules for agent {}", config.name);
@@ -264,6 +385,24 @@ impl UniversalParser {
             }
         });
 
+        let metrics_rules = config.metrics_rules.as_ref().map(|rules| {
+            let compile = |pattern: &Option<String>| {
+                pattern.as_ref().and_then(|p| match Regex::new(p) {
+                    Ok(re) => Some(re),
+                    Err(e) => {
+                        warn!("Invalid metrics rule for agent {}: {}", config.name, e);
+                        None
+                    }
+                })
+            };
+            CompiledMetricsRules {
+                context_remaining: compile(&rules.context_remaining),
+                context_used: compile(&rules.context_used),
+                tokens: compile(&rules.tokens),
+                cost: compile(&rules.cost),
+            }
+        });
+
         let highlight_rules = config
             .highlight_rules
             .iter()
@@ -288,6 +427,7 @@ impl UniversalParser {
             state_rules,
             subagent_rules,
             summary_rules,
+            metrics_rules,
             highlight_rules,
             layout_rules,
         }
@@ -351,7 +491,7 @@ impl AgentParser for UniversalParser {
                     }
                     hostname = parts[i];
                     // Strip user@ if present
-                    if let Some(h) = hostname.split('@').last() {
+                    if let Some(h) = hostname.split('@').next_back() {
                         hostname = h;
                     }
                     break;
@@ -417,10 +557,14 @@ impl AgentParser for UniversalParser {
     }
 
     fn parse_status(&self, content: &str) -> AgentStatus {
+        self.parse_status_with_rule(content).0
+    }
+
+    fn parse_status_with_rule(&self, content: &str) -> (AgentStatus, Option<String>) {
         let raw_content = safe_tail(content, self.capture_buffer_size);
         let body_content = self.extract_body(raw_content);
 
-        for rule in &self.state_rules {
+        for (idx, rule) in self.state_rules.iter().enumerate() {
             let search_content = if let Some(n) = rule.last_lines {
                 let lines: Vec<&str> = body_content.lines().collect();
                 if lines.len() > n {
@@ -465,13 +609,15 @@ impl AgentParser for UniversalParser {
             let mut status_str = rule.status.clone();
             let mut status_kind = rule.kind.clone();
             let mut approval_type_override = None;
+            let mut choice_rule = rule.choices.as_ref();
             let mut matched = false;
+            let mut rule_name = format!("state_rules[{}]", idx);
 
             if rule.splitter != Splitter::None || rule.re.is_some() {
                 matched = true;
             }
 
-            for refinement in &rule.refinements {
+            for (r_idx, refinement) in rule.refinements.iter().enumerate() {
                 let target_text = if refinement.group == "prompt" {
                     &prompt_group
                 } else {
@@ -516,6 +662,10 @@ impl AgentParser for UniversalParser {
                     if refinement.approval_type.is_some() {
                         approval_type_override = refinement.approval_type.clone();
                     }
+                    if refinement.choices.is_some() {
+                        choice_rule = refinement.choices.as_ref();
+                    }
+                    rule_name = format!("state_rules[{}].refinements[{}]", idx, r_idx);
                     matched = true;
                     break;
                 }
@@ -527,89 +677,154 @@ impl AgentParser for UniversalParser {
 
             if let Some(kind) = status_kind {
                 use crate::app::config::RuleType;
-                match kind {
-                    RuleType::Idle => {
-                        return AgentStatus::Idle {
-                            label: Some(status_str),
-                        }
-                    }
-                    RuleType::Working => {
-                        return AgentStatus::Processing {
-                            activity: status_str,
-                        }
-                    }
-                    RuleType::Error => {
-                        return AgentStatus::Error {
-                            message: status_str,
-                        }
-                    }
+                let status = match kind {
+                    RuleType::Idle => AgentStatus::Idle {
+                        label: Some(status_str),
+                    },
+                    RuleType::Working => AgentStatus::Processing {
+                        activity: status_str,
+                    },
+                    RuleType::Error => AgentStatus::Error {
+                        message: status_str,
+                    },
                     RuleType::Approval => {
                         let final_approval_type = approval_type_override
                             .as_deref()
                             .or(rule.approval_type.as_deref());
-                        let approval_type = match final_approval_type {
-                            Some("edit") => ApprovalType::FileEdit,
-                            Some("create") => ApprovalType::FileCreate,
-                            Some("delete") => ApprovalType::FileDelete,
-                            Some("shell") => ApprovalType::ShellCommand,
-                            Some("mcp") => ApprovalType::McpTool,
-                            _ => ApprovalType::Other("Action Required".to_string()),
+                        // Menus become questions unless the rule names another type
+                        let choices = match final_approval_type {
+                            None | Some("question") => {
+                                choice_rule.and_then(|c| c.extract(&search_content))
+                            }
+                            _ => None,
+                        };
+                        let approval_type = match (choices, final_approval_type) {
+                            (Some((choices, multi_select)), _) => ApprovalType::UserQuestion {
+                                choices,
+                                multi_select,
+                            },
+                            (None, final_approval_type) => match final_approval_type {
+                                Some("question") => ApprovalType::UserQuestion {
+                                    choices: Vec::new(),
+                                    multi_select: false,
+                                },
+                                Some("edit") => ApprovalType::FileEdit,
+                                Some("create") => ApprovalType::FileCreate,
+                                Some("delete") => ApprovalType::FileDelete,
+                                Some("shell") => ApprovalType::ShellCommand,
+                                Some("mcp") => ApprovalType::McpTool,
+                                _ => ApprovalType::Other("Action Required".to_string()),
+                            },
                         };
-                        return AgentStatus::AwaitingApproval {
+                        AgentStatus::AwaitingApproval {
                             approval_type,
                             details: status_str,
-                        };
+                        }
                     }
-                }
+                };
+                return (status, Some(rule_name));
             }
         }
 
         if body_content.trim().is_empty() {
-            AgentStatus::Idle { label: None }
+            (AgentStatus::Idle { label: None }, None)
         } else {
             if let Some(kind) = &self.config.default_type {
                 use crate::app::config::RuleType;
                 let label = self.config.default_status.clone();
-                match kind {
-                    RuleType::Idle => return AgentStatus::Idle { label },
-                    RuleType::Working => {
-                        return AgentStatus::Processing {
-                            activity: label.unwrap_or_else(|| "Processing".to_string()),
-                        }
-                    }
-                    RuleType::Error => {
-                        return AgentStatus::Error {
-                            message: label.unwrap_or_else(|| "Error".to_string()),
-                        }
-                    }
-                    RuleType::Approval => {
-                        return AgentStatus::AwaitingApproval {
-                            approval_type: ApprovalType::Other("Action Required".to_string()),
-                            details: label.unwrap_or_else(|| "Action Required".to_string()),
-                        }
-                    }
-                }
-            }
-            AgentStatus::Processing {
-                activity: "Processing".to_string(),
+                let status = match kind {
+                    RuleType::Idle => AgentStatus::Idle { label },
+                    RuleType::Working => AgentStatus::Processing {
+                        activity: label.unwrap_or_else(|| "Processing".to_string()),
+                    },
+                    RuleType::Error => AgentStatus::Error {
+                        message: label.unwrap_or_else(|| "Error".to_string()),
+                    },
+                    RuleType::Approval => AgentStatus::AwaitingApproval {
+                        approval_type: ApprovalType::Other("Action Required".to_string()),
+                        details: label.unwrap_or_else(|| "Action Required".to_string()),
+                    },
+                };
+                return (status, Some("default_type".to_string()));
             }
+            (
+                AgentStatus::Processing {
+                    activity: "Processing".to_string(),
+                },
+                None,
+            )
         }
     }
 
     fn parse_subagents(&self, content: &str) -> Vec<Subagent> {
-        let mut subagents = Vec::new();
-        if let Some(rules) = &self.subagent_rules {
-            for caps in rules.start.captures_iter(content) {
-                let name = caps.get(1).map(|m| m.as_str()).unwrap_or("unknown");
-                let desc = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-                subagents.push(Subagent::new(
-                    name.to_string(),
-                    crate::agents::SubagentType::parse(name),
-                    desc.to_string(),
-                ));
+        let Some(rules) = &self.subagent_rules else {
+            return Vec::new();
+        };
+
+        let starts: Vec<_> = rules
+            .start
+            .captures_iter(content)
+            .map(|caps| {
+                let pos = caps.get(0).map_or(0, |m| m.start());
+                let name = caps.get(1).map_or("unknown", |m| m.as_str());
+                let desc = caps.get(2).map_or("", |m| m.as_str());
+                (pos, name, desc)
+            })
+            .collect();
+
+        // Started subagents with their position; identical starts are numbered
+        // from the bottom so the ids survive the oldest ones scrolling out
+        let mut subagents: Vec<(usize, Subagent)> = Vec::with_capacity(starts.len());
+        for (i, &(pos, name, desc)) in starts.iter().enumerate() {
+            let newer = starts[i + 1..]
+                .iter()
+                .filter(|(_, n, d)| *n == name && *d == desc)
+                .count();
+            let id = repeat_id(&format!("{}: {}", name, desc), newer + 1);
+            subagents.push((
+                pos,
+                Subagent::new(id, SubagentType::parse(name), desc.to_string()),
+            ));
+        }
+
+        // Lifecycle lines in screen order, each applied to the oldest unfinished
+        // subagent of its type started above it
+        let mut events = Vec::new();
+        let lifecycle = [
+            (Some(&rules.running), SubagentStatus::Running),
+            (Some(&rules.complete), SubagentStatus::Completed),
+            (rules.failed.as_ref(), SubagentStatus::Failed),
+        ];
+        for (re, status) in lifecycle {
+            for caps in re.into_iter().flat_map(|re| re.captures_iter(content)) {
+                let Some(name) = caps.get(1) else { continue };
+                let detail = caps.get(2).map_or("", |m| m.as_str().trim());
+                events.push((name.start(), name.as_str(), detail, status.clone()));
             }
         }
+        events.sort_by_key(|(pos, ..)| *pos);
+
+        let mut orphans = Vec::new();
+        for (pos, name, detail, status) in events {
+            let kind = SubagentType::parse(name);
+            let started = subagents
+                .iter_mut()
+                .find(|(start, s)| *start < pos && s.is_running() && s.subagent_type == kind);
+            match started {
+                Some((_, subagent)) => subagent.status = status,
+                // The start line scrolled out: an id-less update for the monitor
+                // to apply to the subagent it is already tracking
+                None => orphans.push(
+                    Subagent::new(String::new(), kind, detail.to_string()).with_status(status),
+                ),
+            }
+        }
+
         subagents
+            .into_iter()
+            .map(|(_, s)| s)
+            .chain(orphans)
+            .collect()
     }
 
     fn parse_summary(&self, content: &str) -> AgentSummary {
@@ -655,6 +870,37 @@ impl AgentParser for UniversalParser {
         summary
     }
 
+    fn parse_metrics(&self, content: &str) -> AgentMetrics {
+        let mut metrics = AgentMetrics::default();
+        let Some(rules) = &self.metrics_rules else {
+            return metrics;
+        };
+        let percent = |re: &Option<Regex>| {
+            let re = re.as_ref()?;
+            let value: f64 = last_groups(re, content).first()?.parse().ok()?;
+            Some(value.clamp(0.0, 100.0))
+        };
+
+        metrics.context_remaining = percent(&rules.context_remaining)
+            .or_else(|| percent(&rules.context_used).map(|used| 100.0 - used))
+            .map(|p| p.round() as u8);
+        if let Some(re) = &rules.tokens {
+            let counts: Vec<u64> = last_groups(re, content)
+                .into_iter()
+                .filter_map(parse_count)
+                .collect();
+            if !counts.is_empty() {
+                metrics.tokens = Some(counts.iter().sum());
+            }
+        }
+        if let Some(re) = &rules.cost {
+            metrics.cost = last_groups(re, content)
+                .first()
+                .and_then(|c| c.replace(',', "").parse().ok());
+        }
+        metrics
+    }
+
     fn explain_status(&self, content: &str) -> Option<String> {
         let raw_content = safe_tail(content, self.capture_buffer_size);
         let body_content = self.extract_body(raw_content);
@@ -806,8 +1052,9 @@ impl AgentParser for UniversalParser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::config::{AgentConfig, AgentKeys};
-    use crate::tmux::PaneInfo;
+    use crate::app::config::{AgentConfig, AgentKeys, AttachMode};
+    use crate::app::Config;
+    use crate::tmux::{FakeTmux, PaneInfo};
 
     #[test]
     fn test_agent_display_name() {
@@ -828,12 +1075,18 @@ mod tests {
             layout: None,
             process_indicators: Vec::new(),
             summary_rules: None,
+            metrics_rules: None,
             highlight_rules: Vec::new(),
             keys: AgentKeys::default(),
+            hooks: None,
+            stalled_after_secs: None,
+            attach: AttachMode::default(),
         };
 
         let parser = UniversalParser::new(config, 1024);
         let pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "test".to_string(),
             window: 0,
             window_name: "test".to_string(),
@@ -850,6 +1103,8 @@ mod tests {
         assert_eq!(parser.agent_display_name(&pane), "SSH: cislo5");
 
         let pane2 = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "test".to_string(),
             window: 0,
             window_name: "test".to_string(),
@@ -864,4 +1119,152 @@ mod tests {
         };
         assert_eq!(parser.agent_display_name(&pane2), "SSH: s8");
     }
+
+    #[test]
+    fn test_parse_status_with_rule_names_matched_rule() {
+        let config: AgentConfig = toml::from_str(
+            r#"
+id = "test"
+name = "Test"
+
+[[state_rules]]
+status = "Approve?"
+type = "approval"
+pattern = "Do you want to proceed"
+
+[[state_rules]]
+status = "Ready"
+type = "idle"
+pattern = "^> $"
+"#,
+        )
+        .unwrap();
+        let parser = UniversalParser::new(config, 1024);
+
+        let (status, rule) = parser.parse_status_with_rule("output\nDo you want to proceed?\n");
+        assert!(matches!(status, AgentStatus::AwaitingApproval { .. }));
+        assert_eq!(rule.as_deref(), Some("state_rules[0]"));
+
+        let (status, rule) = parser.parse_status_with_rule("");
+        assert_eq!(status, AgentStatus::Idle { label: None });
+        assert_eq!(rule, None);
+    }
+
+    #[test]
+    fn test_choice_extraction_from_claude_menus() {
+        let config = crate::app::Config::default();
+        let claude = config.agents.iter().find(|a| a.id == "claude").unwrap();
+        let parser = UniversalParser::new(claude.clone(), config.capture_buffer_size);
+        let fixture = |name: &str| crate::tmux::FakeTmux::fixture(name).unwrap();
+
+        let status = parser.parse_status(&fixture("claude/case_approval_create.txt"));
+        let AgentStatus::AwaitingApproval { approval_type, .. } = status else {
+            panic!("expected approval, got {:?}", status);
+        };
+        assert_eq!(
+            approval_type,
+            ApprovalType::UserQuestion {
+                choices: vec![
+                    "Yes".to_string(),
+                    "Yes, allow all edits in bin/ during this session (shift+Tab)".to_string(),
+                    "No".to_string(),
+                ],
+                multi_select: false,
+            }
+        );
+
+        // Checkbox menu: earlier numbered lists in the output are ignored
+        let status = parser.parse_status(&fixture("claude/case_approval_plan_chat.txt"));
+        let AgentStatus::AwaitingApproval {
+            approval_type:
+                ApprovalType::UserQuestion {
+                    choices,
+                    multi_select,
+                },
+            ..
+        } = status
+        else {
+            panic!("expected question, got {:?}", status);
+        };
+        assert!(multi_select);
+        assert_eq!(choices.len(), 5);
+        assert_eq!(choices[0], "AI can't parse help");
+        assert_eq!(choices[4], "Type something");
+    }
+
+    #[test]
+    fn test_metrics_rules_read_last_match() {
+        let config = Config::default();
+        let pi = config.agents.iter().find(|a| a.id == "pi").unwrap().clone();
+        let parser = UniversalParser::new(pi, 4096);
+        let content = FakeTmux::fixture("pi/case_approval_conftest_offer.txt").unwrap();
+
+        let metrics = parser.parse_metrics(&content);
+        assert_eq!(metrics.context_remaining, Some(95)); // 5.1% used
+        assert_eq!(metrics.tokens, Some(284_000)); // 271k in + 13k out
+        assert_eq!(metrics.cost, Some(0.559));
+
+        let config: AgentConfig = toml::from_str(
+            r#"
+id = "test"
+name = "Test"
+
+[metrics_rules]
+context_remaining = '(\d+)% left'
+tokens = 'tokens: ([\d,.]+[kM]?)'
+"#,
+        )
+        .unwrap();
+        let parser = UniversalParser::new(config, 1024);
+        let metrics = parser.parse_metrics("80% left tokens: 1,200\n40% left tokens: 1.5M\n");
+        assert_eq!(metrics.context_remaining, Some(40));
+        assert_eq!(metrics.tokens, Some(1_500_000));
+        assert_eq!(metrics.cost, None);
+        assert_eq!(parser.parse_metrics("nothing"), AgentMetrics::default());
+    }
+
+    #[test]
+    fn test_subagent_lifecycle_rules() {
+        let config: AgentConfig = toml::from_str(
+            r#"
+id = "test"
+name = "Test"
+
+[subagent_rules]
+start = 'Task\(subagent_type="(\w+)", description="([^"]+)"\)'
+running = '(?m)^▶ (\w+): (.*)$'
+complete = '✓ (\w+) completed'
+failed = '✗ (\w+) failed'
+"#,
+        )
+        .unwrap();
+        let parser = UniversalParser::new(config, 1024);
+
+        let subagents = parser.parse_subagents(
+            "● Task(subagent_type=\"Explore\", description=\"Find config\")\n\
+             ● Task(subagent_type=\"Explore\", description=\"Find config\")\n\
+             ▶ Explore: reading src\n\
+             ✓ Explore completed\n",
+        );
+        let ids: Vec<_> = subagents.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["Explore: Find config#2", "Explore: Find config"]);
+        assert_eq!(subagents[0].status, SubagentStatus::Completed);
+        assert_eq!(subagents[1].status, SubagentStatus::Running);
+
+        // The first start scrolled out: the second keeps its id
+        let subagents = parser.parse_subagents(
+            "● Task(subagent_type=\"Explore\", description=\"Find config\")\n\
+             ▶ Explore: reading src\n",
+        );
+        assert_eq!(subagents.len(), 1);
+        assert_eq!(subagents[0].id, "Explore: Find config");
+        assert!(subagents[0].is_running());
+
+        // Start line scrolled out: an update without id
+        let subagents = parser.parse_subagents("✗ Explore failed\n");
+        assert_eq!(subagents.len(), 1);
+        assert!(subagents[0].id.is_empty());
+        assert_eq!(subagents[0].subagent_type, SubagentType::Explore);
+        assert_eq!(subagents[0].status, SubagentStatus::Failed);
+    }
 }
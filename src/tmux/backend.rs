@@ -0,0 +1,77 @@
+use anyhow::Result;
+
+use super::keys::KeyArg;
+use super::pane::PaneInfo;
+use crate::app::KillMethod;
+
+/// A pane to start with `TmuxBackend::new_pane`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewPane {
+    /// Session of the new window (may be server-qualified), created if missing
+    pub session: String,
+    /// Name of the new window
+    pub window: Option<String>,
+    /// Working directory of the new pane
+    pub cwd: Option<String>,
+    /// Extra environment variables
+    pub env: Vec<(String, String)>,
+    /// Shell command run in the pane
+    pub command: String,
+}
+
+/// Operations tmuxx performs on tmux.
+///
+/// `TmuxClient` implements this by running `tmux` commands; `FakeTmux` replays
+/// scripted pane contents so the monitor and action handlers can be tested
+/// without a tmux server.
+pub trait TmuxBackend: Send + Sync {
+    /// Returns true if tmux is reachable
+    fn is_available(&self) -> bool;
+
+    /// Lists all monitored panes
+    fn list_panes(&self) -> Result<Vec<PaneInfo>>;
+
+    /// Returns the session tmuxx runs in, if any
+    fn get_current_session(&self) -> Result<Option<String>>;
+
+    /// Captures the content of a pane
+    fn capture_pane(&self, target: &str) -> Result<String>;
+
+    /// Captures the visible screen of a pane with its colour escape sequences
+    fn capture_styled(&self, target: &str) -> Result<String>;
+
+    /// Returns the paste buffers of the default server (copy mode selections), newest first
+    fn paste_buffers(&self) -> Result<Vec<String>>;
+
+    /// Sends keys (tmux key names or literal text) to a pane
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()>;
+
+    /// Sends a key list to a pane (key names in one command, text typed or pasted)
+    fn send_keys_many(&self, target: &str, keys: &[KeyArg]) -> Result<()>;
+
+    /// Selects a pane in its window
+    fn select_pane(&self, target: &str) -> Result<()>;
+
+    /// Selects the window containing the target
+    fn select_window(&self, target: &str) -> Result<()>;
+
+    /// Switches the tmux client to the pane (across sessions if needed)
+    fn focus_pane(&self, target: &str) -> Result<()>;
+
+    /// Stops the application in a pane (SIGTERM, Ctrl-C/Ctrl-D or respawn)
+    fn kill_application(&self, target: &str, method: &KillMethod) -> Result<()>;
+
+    /// Renames a session
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()>;
+
+    /// Kills a session
+    fn kill_session(&self, target_session: &str) -> Result<()>;
+
+    /// Starts a command in a new window (in the background) and returns the new pane's target
+    fn new_pane(&self, pane: &NewPane) -> Result<String>;
+
+    /// Strips a server qualifier from a target or session name
+    fn unqualified<'a>(&self, target: &'a str) -> &'a str {
+        target
+    }
+}
@@ -1,23 +1,43 @@
 use anyhow::{Context, Result};
-use std::process::Command;
-
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::debug;
+
+use super::backend::{NewPane, TmuxBackend};
+use super::keys::KeyArg;
 use super::pane::PaneInfo;
+use super::server::TmuxServer;
 use crate::app::{Config, KillMethod};
 
 /// Client for interacting with tmux
+///
+/// Targets of panes on additional servers (`[[tmux_servers]]`) are qualified
+/// with the server label ("label/session:0.1"); all methods accept both forms.
 pub struct TmuxClient {
     /// Number of lines to capture from pane
     capture_lines: u32,
     /// Whether to show detached tmux sessions
     show_detached_sessions: bool,
+    /// Monitored servers, the default server first
+    servers: Vec<TmuxServer>,
 }
 
 impl TmuxClient {
     /// Creates a new TmuxClient from a Config
     pub fn from_config(config: &Config) -> Self {
+        let mut servers = vec![TmuxServer::default_server()];
+        servers.extend(
+            config
+                .tmux_servers
+                .iter()
+                .filter_map(TmuxServer::from_config),
+        );
         Self {
             capture_lines: config.capture_lines,
             show_detached_sessions: config.show_detached_sessions,
+            servers,
         }
     }
 
@@ -26,6 +46,7 @@ impl TmuxClient {
         Self {
             capture_lines: 100,
             show_detached_sessions: true,
+            servers: vec![TmuxServer::default_server()],
         }
     }
 
@@ -34,28 +55,33 @@ impl TmuxClient {
         Self {
             capture_lines,
             show_detached_sessions: true,
+            servers: vec![TmuxServer::default_server()],
         }
     }
 
-    /// Check if tmux is available and running
-    pub fn is_available(&self) -> bool {
-        Command::new("tmux")
-            .arg("list-sessions")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    /// Returns the monitored servers (the default server first)
+    pub fn servers(&self) -> &[TmuxServer] {
+        &self.servers
+    }
+
+    /// Finds the server a (possibly qualified) target belongs to and the raw tmux target
+    fn resolve<'a>(&self, target: &'a str) -> (&TmuxServer, &'a str) {
+        self.servers[1..]
+            .iter()
+            .find_map(|server| server.strip(target).map(|raw| (server, raw)))
+            .unwrap_or((&self.servers[0], target))
     }
 
-    /// Lists all panes across all attached sessions
-    pub fn list_panes(&self) -> Result<Vec<PaneInfo>> {
+    /// Lists the panes of one server, tagged with its label
+    fn list_server_panes(&self, server: &TmuxServer) -> Result<Vec<PaneInfo>> {
         // Use tab separator to handle spaces in titles/paths
         // Include session_attached to filter out detached sessions
-        let output = Command::new("tmux")
-            .args([
+        let output = server
+            .command([
                 "list-panes",
                 "-a",
                 "-F",
-                "#{session_attached}\t#{session_name}:#{window_index}.#{pane_index}\t#{window_name}\t#{pane_current_command}\t#{pane_pid}\t#{pane_title}\t#{pane_current_path}",
+                "#{session_attached}\t#{session_name}:#{window_index}.#{pane_index}\t#{window_name}\t#{pane_current_command}\t#{pane_pid}\t#{pane_title}\t#{pane_current_path}\t#{pane_id}",
             ])
             .output()
             .context("Failed to execute tmux list-panes")?;
@@ -65,6 +91,13 @@ impl TmuxClient {
             anyhow::bail!("tmux list-panes failed: {}", stderr);
         }
 
+        // Control mode clients (ours included) count as attached, so look at real clients
+        let attached_sessions = if self.show_detached_sessions {
+            None
+        } else {
+            Some(self.sessions_with_terminal_clients(server)?)
+        };
+
         let stdout = String::from_utf8_lossy(&output.stdout);
         let panes: Vec<PaneInfo> = stdout
             .lines()
@@ -72,27 +105,161 @@ impl TmuxClient {
                 let (attached_str, rest) = line.split_once('\t')?;
 
                 // Filter based on config setting
-                if !self.show_detached_sessions {
-                    // Skip detached sessions (attached_str == "0")
-                    if attached_str != "1" {
+                if let Some(attached) = &attached_sessions {
+                    // Skip detached sessions (no clients, or only control mode clients)
+                    let session = rest.split(':').next().unwrap_or_default();
+                    if attached_str == "0" || !attached.contains(session) {
                         return None;
                     }
                 }
 
                 // Parse pane info
-                PaneInfo::parse(rest)
+                let mut pane = PaneInfo::parse(rest)?;
+                pane.server = server.label.clone();
+                if server.is_remote() {
+                    // The process tree lives on the remote host; local pids would be unrelated
+                    pane.cmdline.clear();
+                    pane.child_commands.clear();
+                    pane.ancestor_commands.clear();
+                }
+                Some(pane)
             })
             .collect();
 
         Ok(panes)
     }
 
+    /// Returns the names of sessions that have at least one non-control-mode client
+    fn sessions_with_terminal_clients(&self, server: &TmuxServer) -> Result<HashSet<String>> {
+        let output = server
+            .command([
+                "list-clients",
+                "-F",
+                "#{client_control_mode}\t#{session_name}",
+            ])
+            .output()
+            .context("Failed to execute tmux list-clients")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(control, _)| *control != "1")
+            .map(|(_, session)| session.to_string())
+            .collect())
+    }
+
+    /// Check if running inside tmux
+    fn is_inside_tmux() -> bool {
+        std::env::var("TMUX").is_ok()
+    }
+}
+
+/// Runs one `send-keys` command (nothing without keys)
+fn run_send_keys(server: &TmuxServer, raw_target: &str, keys: &[&str], target: &str) -> Result<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let output = server
+        .command(["send-keys", "-t", raw_target].iter().chain(keys))
+        .output()
+        .context("Failed to execute tmux send-keys")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("tmux send-keys failed for {}: {}", target, stderr);
+    }
+    Ok(())
+}
+
+/// Pastes text into a pane through a temporary buffer; `-p` wraps it in
+/// bracketed paste sequences when the application asked for them
+fn paste_text(server: &TmuxServer, raw_target: &str, text: &str) -> Result<()> {
+    static BUFFER_SEQ: AtomicU64 = AtomicU64::new(0);
+    let buffer = format!(
+        "tmuxx-{}-{}",
+        std::process::id(),
+        BUFFER_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let mut child = server
+        .command(["load-buffer", "-b", &buffer, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute tmux load-buffer")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("tmux load-buffer failed: {}", stderr);
+    }
+
+    let output = server
+        .command(["paste-buffer", "-p", "-d", "-b", &buffer, "-t", raw_target])
+        .output()
+        .context("Failed to execute tmux paste-buffer")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("tmux paste-buffer failed: {}", stderr);
+    }
+    Ok(())
+}
+
+impl TmuxBackend for TmuxClient {
+    /// Strips the server label from a qualified target or session name
+    fn unqualified<'a>(&self, target: &'a str) -> &'a str {
+        self.resolve(target).1
+    }
+
+    /// Check if tmux is available and running (on any monitored server)
+    fn is_available(&self) -> bool {
+        self.servers.iter().any(|server| {
+            server
+                .command(["list-sessions"])
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Lists all panes across all attached sessions of all monitored servers
+    ///
+    /// Unreachable additional servers are skipped; fails only if no server answers.
+    fn list_panes(&self) -> Result<Vec<PaneInfo>> {
+        let mut panes = Vec::new();
+        let mut last_error = None;
+        let mut any_ok = false;
+
+        for server in &self.servers {
+            match self.list_server_panes(server) {
+                Ok(server_panes) => {
+                    any_ok = true;
+                    panes.extend(server_panes);
+                }
+                Err(e) => {
+                    debug!("Skipping tmux server {:?}: {}", server.label, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if !any_ok => Err(e),
+            _ => Ok(panes),
+        }
+    }
+
     /// Captures the content of a specific pane
-    pub fn capture_pane(&self, target: &str) -> Result<String> {
+    fn capture_pane(&self, target: &str) -> Result<String> {
         let start_line = format!("-{}", self.capture_lines);
+        let (server, raw_target) = self.resolve(target);
 
-        let output = Command::new("tmux")
-            .args(["capture-pane", "-p", "-t", target, "-S", &start_line])
+        let output = server
+            .command(["capture-pane", "-p", "-t", raw_target, "-S", &start_line])
             .output()
             .context("Failed to execute tmux capture-pane")?;
 
@@ -104,30 +271,55 @@ impl TmuxClient {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    /// Sends keys to a specific pane
-    pub fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
-        let output = Command::new("tmux")
-            .args(["send-keys", "-t", target, keys])
+    /// Captures the visible screen of a pane with escape sequences for colours and attributes
+    fn capture_styled(&self, target: &str) -> Result<String> {
+        let (server, raw_target) = self.resolve(target);
+        let output = server
+            .command(["capture-pane", "-p", "-e", "-t", raw_target])
             .output()
-            .context("Failed to execute tmux send-keys")?;
+            .context("Failed to execute tmux capture-pane")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("tmux send-keys failed for {}: {}", target, stderr);
+            anyhow::bail!("tmux capture-pane failed for {}: {}", target, stderr);
         }
 
-        Ok(())
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    /// Sends multiple keys to a specific pane in one tmux command
-    pub fn send_keys_many(&self, target: &str, keys: &[&str]) -> Result<()> {
-        let mut cmd = Command::new("tmux");
-        cmd.arg("send-keys").arg("-t").arg(target);
-        for key in keys {
-            cmd.arg(key);
+    /// Returns the default server's paste buffers (listed newest first by tmux)
+    fn paste_buffers(&self) -> Result<Vec<String>> {
+        let server = &self.servers[0];
+        let output = server
+            .command(["list-buffers", "-F", "#{buffer_name}"])
+            .output()
+            .context("Failed to execute tmux list-buffers")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("tmux list-buffers failed: {}", stderr);
         }
 
-        let output = cmd.output().context("Failed to execute tmux send-keys")?;
+        let mut buffers = Vec::new();
+        for name in String::from_utf8_lossy(&output.stdout).lines() {
+            let output = server
+                .command(["show-buffer", "-b", name])
+                .output()
+                .context("Failed to execute tmux show-buffer")?;
+            if output.status.success() {
+                buffers.push(String::from_utf8_lossy(&output.stdout).to_string());
+            }
+        }
+        Ok(buffers)
+    }
+
+    /// Sends keys to a specific pane
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        let (server, raw_target) = self.resolve(target);
+        let output = server
+            .command(["send-keys", "-t", raw_target, keys])
+            .output()
+            .context("Failed to execute tmux send-keys")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -137,10 +329,35 @@ impl TmuxClient {
         Ok(())
     }
 
+    /// Sends multiple keys to a specific pane, key names in one tmux command and
+    /// text typed literally or pasted in between
+    fn send_keys_many(&self, target: &str, keys: &[KeyArg]) -> Result<()> {
+        let (server, raw_target) = self.resolve(target);
+        let mut names = Vec::new();
+        for key in keys {
+            match key {
+                KeyArg::Key(key) => names.push(key.as_str()),
+                KeyArg::Literal(text) => {
+                    run_send_keys(server, raw_target, &names, target)?;
+                    names.clear();
+                    run_send_keys(server, raw_target, &["-l", "--", text], target)?;
+                }
+                KeyArg::Paste(text) => {
+                    run_send_keys(server, raw_target, &names, target)?;
+                    names.clear();
+                    paste_text(server, raw_target, text)
+                        .with_context(|| format!("Failed to paste into {}", target))?;
+                }
+            }
+        }
+        run_send_keys(server, raw_target, &names, target)
+    }
+
     /// Selects (focuses) a specific pane
-    pub fn select_pane(&self, target: &str) -> Result<()> {
-        let output = Command::new("tmux")
-            .args(["select-pane", "-t", target])
+    fn select_pane(&self, target: &str) -> Result<()> {
+        let (server, raw_target) = self.resolve(target);
+        let output = server
+            .command(["select-pane", "-t", raw_target])
             .output()
             .context("Failed to execute tmux select-pane")?;
 
@@ -153,7 +370,8 @@ impl TmuxClient {
     }
 
     /// Selects a specific window
-    pub fn select_window(&self, target: &str) -> Result<()> {
+    fn select_window(&self, target: &str) -> Result<()> {
+        let (server, target) = self.resolve(target);
         // Extract session:window from full target
         let window_target = if let Some(pos) = target.rfind('.') {
             &target[..pos]
@@ -161,8 +379,8 @@ impl TmuxClient {
             target
         };
 
-        let output = Command::new("tmux")
-            .args(["select-window", "-t", window_target])
+        let output = server
+            .command(["select-window", "-t", window_target])
             .output()
             .context("Failed to execute tmux select-window")?;
 
@@ -179,12 +397,26 @@ impl TmuxClient {
     }
 
     /// Kill application in target pane
-    pub fn kill_application(&self, target: &str, method: &KillMethod) -> Result<()> {
+    fn kill_application(&self, target: &str, method: &KillMethod) -> Result<()> {
+        let (server, raw_target) = self.resolve(target);
         match method {
+            KillMethod::Sigterm if server.is_remote() => {
+                // The pane process is on the remote host, so let its tmux server send the signal
+                let output = server
+                    .command(["run-shell", "-t", raw_target, "kill -TERM #{pane_pid}"])
+                    .output()
+                    .context("Failed to execute tmux run-shell")?;
+
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    anyhow::bail!("Failed to send SIGTERM to {}: {}", target, stderr.trim());
+                }
+                Ok(())
+            }
             KillMethod::Sigterm => {
                 // Get PID of process in pane
-                let output = Command::new("tmux")
-                    .args(["display-message", "-t", target, "-p", "#{pane_pid}"])
+                let output = server
+                    .command(["display-message", "-t", raw_target, "-p", "#{pane_pid}"])
                     .output()
                     .context("Failed to get pane PID")?;
 
@@ -218,8 +450,8 @@ impl TmuxClient {
             }
             KillMethod::Respawn => {
                 // Respawn pane with -k (kill) flag
-                let output = Command::new("tmux")
-                    .args(["respawn-pane", "-k", "-t", target])
+                let output = server
+                    .command(["respawn-pane", "-k", "-t", raw_target])
                     .output()
                     .context("Failed to execute tmux respawn-pane")?;
 
@@ -232,19 +464,14 @@ impl TmuxClient {
         }
     }
 
-    /// Check if running inside tmux
-    fn is_inside_tmux() -> bool {
-        std::env::var("TMUX").is_ok()
-    }
-
     /// Get current tmux session name (if inside tmux)
-    pub fn get_current_session(&self) -> Result<Option<String>> {
+    fn get_current_session(&self) -> Result<Option<String>> {
         if !Self::is_inside_tmux() {
             return Ok(None);
         }
 
-        let output = Command::new("tmux")
-            .args(["display-message", "-p", "#S"])
+        let output = self.servers[0]
+            .command(["display-message", "-p", "#S"])
             .output()
             .context("Failed to get current tmux session")?;
 
@@ -261,7 +488,17 @@ impl TmuxClient {
     /// Supports cross-session focus when running inside tmux.
     /// If target is in a different session, uses switch-client to change sessions.
     /// If running outside tmux, returns an error.
-    pub fn focus_pane(&self, target: &str) -> Result<()> {
+    fn focus_pane(&self, target: &str) -> Result<()> {
+        let (server, raw_target) = self.resolve(target);
+        if let Some(label) = &server.label {
+            anyhow::bail!(
+                "Cannot focus {}: it is on tmux server '{}', not the one tmuxx runs in",
+                target,
+                label
+            );
+        }
+        let target = raw_target;
+
         // Extract session from target (e.g., "main:0.1" -> "main")
         let target_session = target
             .split(':')
@@ -287,8 +524,8 @@ impl TmuxClient {
             self.select_pane(target)?;
         } else {
             // Different session: use switch-client
-            let output = Command::new("tmux")
-                .args(["switch-client", "-t", target])
+            let output = server
+                .command(["switch-client", "-t", target])
                 .output()
                 .context("Failed to execute tmux switch-client")?;
 
@@ -301,10 +538,11 @@ impl TmuxClient {
         Ok(())
     }
 
-    /// Renames a tmux session
-    pub fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
-        let output = Command::new("tmux")
-            .args(["rename-session", "-t", old_name, new_name])
+    /// Renames a tmux session (`old_name` may be server-qualified)
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let (server, old_name) = self.resolve(old_name);
+        let output = server
+            .command(["rename-session", "-t", old_name, new_name])
             .output()
             .context("Failed to execute tmux rename-session")?;
 
@@ -315,10 +553,11 @@ impl TmuxClient {
         Ok(())
     }
 
-    /// Kills a tmux session
-    pub fn kill_session(&self, target_session: &str) -> Result<()> {
-        let output = Command::new("tmux")
-            .args(["kill-session", "-t", target_session])
+    /// Kills a tmux session (may be server-qualified)
+    fn kill_session(&self, target_session: &str) -> Result<()> {
+        let (server, target_session) = self.resolve(target_session);
+        let output = server
+            .command(["kill-session", "-t", target_session])
             .output()
             .context("Failed to execute tmux kill-session")?;
 
@@ -328,6 +567,54 @@ impl TmuxClient {
         }
         Ok(())
     }
+
+    /// Creates a detached window, or a detached session if the session doesn't exist
+    fn new_pane(&self, pane: &NewPane) -> Result<String> {
+        let (server, session) = self.resolve(&pane.session);
+        let exists = server
+            .command(["has-session", "-t", &format!("={}", session)])
+            .output()
+            .context("Failed to execute tmux has-session")?
+            .status
+            .success();
+
+        let mut args: Vec<String> = if exists {
+            vec!["new-window".into(), "-t".into(), format!("={}:", session)]
+        } else {
+            vec!["new-session".into(), "-s".into(), session.to_string()]
+        };
+        args.extend(
+            [
+                "-d",
+                "-P",
+                "-F",
+                "#{session_name}:#{window_index}.#{pane_index}",
+            ]
+            .map(String::from),
+        );
+        if let Some(window) = &pane.window {
+            args.extend(["-n".to_string(), window.clone()]);
+        }
+        if let Some(cwd) = &pane.cwd {
+            args.extend(["-c".to_string(), cwd.clone()]);
+        }
+        for (key, value) in &pane.env {
+            args.extend(["-e".to_string(), format!("{}={}", key, value)]);
+        }
+        args.push(pane.command.clone());
+
+        let output = server
+            .command(&args)
+            .output()
+            .with_context(|| format!("Failed to execute tmux {}", args[0]))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("tmux {} failed: {}", args[0], stderr.trim());
+        }
+        let target = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(server.qualify(&target))
+    }
 }
 
 impl Default for TmuxClient {
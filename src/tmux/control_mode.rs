@@ -0,0 +1,292 @@
+use parking_lot::Mutex;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Notify;
+use tokio::task::{AbortHandle, JoinHandle, JoinSet};
+use tracing::{debug, warn};
+
+/// How often the session list is re-read even without `%sessions-changed`
+const SESSION_RESYNC_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Changes reported by control mode clients since the last `take_events`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ControlModeEvents {
+    /// Pane ids (e.g. "%12") that produced output
+    pub dirty_panes: HashSet<String>,
+    /// Windows, panes, sessions or clients were added, removed or rearranged
+    pub layout_changed: bool,
+}
+
+/// A control mode notification relevant to the monitor
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Notification {
+    /// Pane produced output or changed mode
+    Output(String),
+    /// Window/pane/session structure changed
+    LayoutChanged,
+    /// A session was created or destroyed
+    SessionsChanged,
+    /// The control client is exiting
+    Exit,
+}
+
+/// Parses one line of control mode output
+fn parse_notification(line: &[u8]) -> Option<Notification> {
+    if line.first() != Some(&b'%') {
+        return None;
+    }
+    // Only the notification name and first argument are needed; output payloads may not be UTF-8
+    let mut parts = line.splitn(3, |&b| b == b' ');
+    let name = std::str::from_utf8(parts.next()?).ok()?;
+    let first_arg = parts
+        .next()
+        .and_then(|a| std::str::from_utf8(a).ok())
+        .map(|a| a.to_string());
+
+    match name {
+        "%output" | "%extended-output" | "%pane-mode-changed" => {
+            first_arg.map(Notification::Output)
+        }
+        "%window-add"
+        | "%window-close"
+        | "%window-renamed"
+        | "%window-pane-changed"
+        | "%unlinked-window-add"
+        | "%unlinked-window-close"
+        | "%unlinked-window-renamed"
+        | "%layout-change"
+        | "%session-changed"
+        | "%session-renamed"
+        | "%session-window-changed"
+        | "%client-session-changed"
+        | "%client-detached" => Some(Notification::LayoutChanged),
+        "%sessions-changed" => Some(Notification::SessionsChanged),
+        "%exit" => Some(Notification::Exit),
+        _ => None,
+    }
+}
+
+struct Shared {
+    events: Mutex<ControlModeEvents>,
+    /// Wakes the monitor when something changed
+    changed: Notify,
+    /// Wakes the supervisor to re-read the session list
+    resync: Notify,
+    /// Number of control clients currently reading events
+    connected: AtomicUsize,
+}
+
+/// Watches all tmux sessions through control mode (`tmux -C`) clients.
+///
+/// One read-only client is attached per session, since tmux only reports
+/// `%output` for panes of the client's own session.
+pub struct ControlModeWatcher {
+    shared: Arc<Shared>,
+    supervisor: JoinHandle<()>,
+}
+
+impl ControlModeWatcher {
+    /// Starts the watcher (must be called inside a tokio runtime)
+    pub fn spawn() -> Self {
+        let shared = Arc::new(Shared {
+            events: Mutex::new(ControlModeEvents {
+                layout_changed: true,
+                ..Default::default()
+            }),
+            changed: Notify::new(),
+            resync: Notify::new(),
+            connected: AtomicUsize::new(0),
+        });
+        let supervisor = tokio::spawn(supervise(shared.clone()));
+        Self { shared, supervisor }
+    }
+
+    /// Returns and clears the changes collected since the last call
+    pub fn take_events(&self) -> ControlModeEvents {
+        std::mem::take(&mut *self.shared.events.lock())
+    }
+
+    /// Waits until a control client reports a change
+    pub async fn changed(&self) {
+        self.shared.changed.notified().await;
+    }
+
+    /// Returns true if at least one control client is attached.
+    /// Without clients no output is reported, so callers should re-capture everything.
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Relaxed) > 0
+    }
+}
+
+impl Drop for ControlModeWatcher {
+    fn drop(&mut self) {
+        // Aborting drops the client tasks, which kills their tmux processes
+        self.supervisor.abort();
+    }
+}
+
+/// Keeps one control client per session, following session creation and removal
+async fn supervise(shared: Arc<Shared>) {
+    // The JoinSet owns the client tasks, so aborting the supervisor aborts them too
+    let mut tasks = JoinSet::new();
+    let mut clients: HashMap<String, AbortHandle> = HashMap::new();
+
+    loop {
+        while tasks.try_join_next().is_some() {}
+
+        match list_session_ids().await {
+            Ok(ids) => {
+                clients.retain(|id, handle| {
+                    let keep = ids.contains(id) && !handle.is_finished();
+                    if !keep {
+                        handle.abort();
+                    }
+                    keep
+                });
+                for id in ids {
+                    if let Entry::Vacant(entry) = clients.entry(id) {
+                        debug!("Attaching control mode client to session {}", entry.key());
+                        let id = entry.key().clone();
+                        entry.insert(tasks.spawn(run_client(id, shared.clone())));
+                    }
+                }
+            }
+            Err(e) => debug!("Control mode: failed to list sessions: {}", e),
+        }
+
+        tokio::select! {
+            _ = shared.resync.notified() => {}
+            _ = tokio::time::sleep(SESSION_RESYNC_INTERVAL) => {}
+        }
+    }
+}
+
+async fn list_session_ids() -> anyhow::Result<HashSet<String>> {
+    let output = Command::new("tmux")
+        .args(["list-sessions", "-F", "#{session_id}"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// Runs one control mode client and records its notifications
+async fn run_client(session_id: String, shared: Arc<Shared>) {
+    let child = Command::new("tmux")
+        .args([
+            "-C",
+            "attach-session",
+            "-t",
+            &session_id,
+            "-f",
+            "read-only,ignore-size",
+        ])
+        // Control mode exits when stdin closes, so keep it open
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to start tmux control mode client: {}", e);
+            return;
+        }
+    };
+    let Some(stdout) = child.stdout.take() else {
+        return;
+    };
+
+    let _connected = ConnectedGuard::new(&shared);
+    let mut lines = BufReader::new(stdout).split(b'\n');
+    while let Ok(Some(line)) = lines.next_segment().await {
+        let Some(notification) = parse_notification(&line) else {
+            continue;
+        };
+        match notification {
+            Notification::Output(pane_id) => {
+                shared.events.lock().dirty_panes.insert(pane_id);
+            }
+            Notification::LayoutChanged => shared.events.lock().layout_changed = true,
+            Notification::SessionsChanged => {
+                shared.events.lock().layout_changed = true;
+                shared.resync.notify_one();
+            }
+            Notification::Exit => break,
+        }
+        shared.changed.notify_one();
+    }
+
+    // The supervisor re-attaches on its next session resync if the session still exists
+    debug!("Control mode client for session {} exited", session_id);
+    let _ = child.kill().await;
+    shared.events.lock().layout_changed = true;
+    shared.changed.notify_one();
+}
+
+/// Counts a control client as connected for as long as it is alive (also when aborted)
+struct ConnectedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> ConnectedGuard<'a> {
+    fn new(shared: &'a Shared) -> Self {
+        shared.connected.fetch_add(1, Ordering::Relaxed);
+        Self(&shared.connected)
+    }
+}
+
+impl Drop for ConnectedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notification() {
+        assert_eq!(
+            parse_notification(b"%output %12 hello\\015\\012"),
+            Some(Notification::Output("%12".to_string()))
+        );
+        assert_eq!(
+            parse_notification(b"%extended-output %3 120 : data"),
+            Some(Notification::Output("%3".to_string()))
+        );
+        assert_eq!(
+            parse_notification(b"%pane-mode-changed %7"),
+            Some(Notification::Output("%7".to_string()))
+        );
+        // Non-UTF-8 payloads must not prevent reading the pane id
+        assert_eq!(
+            parse_notification(b"%output %1 \xff\xfe"),
+            Some(Notification::Output("%1".to_string()))
+        );
+        assert_eq!(
+            parse_notification(b"%window-add @4"),
+            Some(Notification::LayoutChanged)
+        );
+        assert_eq!(
+            parse_notification(b"%sessions-changed"),
+            Some(Notification::SessionsChanged)
+        );
+        assert_eq!(parse_notification(b"%exit"), Some(Notification::Exit));
+        assert_eq!(parse_notification(b"%begin 1700000000 1 0"), None);
+        assert_eq!(parse_notification(b"plain output"), None);
+    }
+}
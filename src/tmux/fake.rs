@@ -0,0 +1,363 @@
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use super::backend::{NewPane, TmuxBackend};
+use super::keys::KeyArg;
+use super::pane::PaneInfo;
+use crate::app::KillMethod;
+
+/// Keys sent to a pane through `FakeTmux`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentKeys {
+    pub target: String,
+    pub keys: Vec<KeyArg>,
+}
+
+/// A tmux action recorded by `FakeTmux` (everything except captures and key sends)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeAction {
+    SelectPane(String),
+    SelectWindow(String),
+    Focus(String),
+    KillApplication(String, KillMethod),
+    RenameSession(String, String),
+    KillSession(String),
+    /// A pane started with `new_pane`, with its target
+    NewPane(String, NewPane),
+}
+
+struct FakePane {
+    info: PaneInfo,
+    /// Scripted contents still to be shown, one per capture
+    frames: VecDeque<String>,
+    /// Content returned once the script is exhausted
+    content: String,
+}
+
+#[derive(Default)]
+struct FakeState {
+    panes: Vec<FakePane>,
+    sent: Vec<SentKeys>,
+    actions: Vec<FakeAction>,
+    current_session: Option<String>,
+    buffers: Vec<String>,
+    next_pid: u32,
+}
+
+impl FakeState {
+    fn pane_mut(&mut self, target: &str) -> Result<&mut FakePane> {
+        self.panes
+            .iter_mut()
+            .find(|p| p.info.target() == target)
+            .ok_or_else(|| anyhow::anyhow!("can't find pane: {}", target))
+    }
+
+    fn has_session(&self, session: &str) -> bool {
+        self.panes.iter().any(|p| p.info.session == session)
+    }
+}
+
+/// In-memory tmux backend for tests.
+///
+/// Each `capture_pane` returns the next scripted frame of the pane (the last
+/// one repeats), so one monitor poll advances every captured pane by one step.
+/// Sent keys and other actions are recorded instead of executed.
+#[derive(Default)]
+pub struct FakeTmux {
+    state: Mutex<FakeState>,
+}
+
+impl FakeTmux {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directory with the regression fixtures (`tests/fixtures`)
+    pub fn fixtures_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+    }
+
+    /// Reads a fixture, relative to `tests/fixtures` (e.g. "claude/case_working_1.txt")
+    pub fn fixture(name: &str) -> Result<String> {
+        let path = Self::fixtures_dir().join(name);
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))
+    }
+
+    /// Adds a pane ("session:window.pane") running `command` with empty content
+    pub fn add_pane(&self, target: &str, command: &str) -> Result<()> {
+        let (session, rest) = target
+            .split_once(':')
+            .with_context(|| format!("Invalid target: {}", target))?;
+        let (window, pane) = rest
+            .split_once('.')
+            .with_context(|| format!("Invalid target: {}", target))?;
+
+        let mut state = self.state.lock();
+        state.next_pid += 1;
+        let info = PaneInfo {
+            server: None,
+            session: session.to_string(),
+            window: window.parse()?,
+            window_name: command.to_string(),
+            pane: pane.parse()?,
+            command: command.to_string(),
+            title: String::new(),
+            path: format!("/work/{}", session),
+            pid: 1000 + state.next_pid,
+            id: format!("%{}", state.next_pid),
+            cmdline: command.to_string(),
+            child_commands: Vec::new(),
+            ancestor_commands: Vec::new(),
+        };
+        state.panes.push(FakePane {
+            info,
+            frames: VecDeque::new(),
+            content: String::new(),
+        });
+        Ok(())
+    }
+
+    /// Removes a pane, as if its process exited
+    pub fn remove_pane(&self, target: &str) {
+        self.state
+            .lock()
+            .panes
+            .retain(|p| p.info.target() != target);
+    }
+
+    /// Queues contents the pane shows on its next captures
+    pub fn script<I, S>(&self, target: &str, frames: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut state = self.state.lock();
+        let pane = state.pane_mut(target)?;
+        pane.frames.extend(frames.into_iter().map(Into::into));
+        Ok(())
+    }
+
+    /// Queues fixture files (see `fixture`) as the pane's next contents
+    pub fn script_fixtures(&self, target: &str, names: &[&str]) -> Result<()> {
+        let frames = names
+            .iter()
+            .map(|name| Self::fixture(name))
+            .collect::<Result<Vec<_>>>()?;
+        self.script(target, frames)
+    }
+
+    /// Adds a paste buffer (the newest one)
+    pub fn add_buffer(&self, text: &str) {
+        self.state.lock().buffers.insert(0, text.to_string());
+    }
+
+    /// Sets the session reported by `get_current_session`
+    pub fn set_current_session(&self, session: Option<&str>) {
+        self.state.lock().current_session = session.map(|s| s.to_string());
+    }
+
+    /// Returns and clears the keys sent so far
+    pub fn take_sent_keys(&self) -> Vec<SentKeys> {
+        std::mem::take(&mut self.state.lock().sent)
+    }
+
+    /// Returns and clears the recorded actions
+    pub fn take_actions(&self) -> Vec<FakeAction> {
+        std::mem::take(&mut self.state.lock().actions)
+    }
+
+    fn record_keys(&self, target: &str, keys: &[KeyArg]) -> Result<()> {
+        let mut state = self.state.lock();
+        state.pane_mut(target)?;
+        state.sent.push(SentKeys {
+            target: target.to_string(),
+            keys: keys.to_vec(),
+        });
+        Ok(())
+    }
+
+    fn record_pane_action(&self, target: &str, action: FakeAction) -> Result<()> {
+        let mut state = self.state.lock();
+        state.pane_mut(target)?;
+        state.actions.push(action);
+        Ok(())
+    }
+}
+
+impl TmuxBackend for FakeTmux {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn list_panes(&self) -> Result<Vec<PaneInfo>> {
+        Ok(self
+            .state
+            .lock()
+            .panes
+            .iter()
+            .map(|p| p.info.clone())
+            .collect())
+    }
+
+    fn get_current_session(&self) -> Result<Option<String>> {
+        Ok(self.state.lock().current_session.clone())
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        let mut state = self.state.lock();
+        let pane = state.pane_mut(target)?;
+        if let Some(frame) = pane.frames.pop_front() {
+            pane.content = frame;
+        }
+        Ok(pane.content.clone())
+    }
+
+    fn capture_styled(&self, target: &str) -> Result<String> {
+        Ok(self.state.lock().pane_mut(target)?.content.clone())
+    }
+
+    fn paste_buffers(&self) -> Result<Vec<String>> {
+        Ok(self.state.lock().buffers.clone())
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        self.record_keys(target, &[KeyArg::key(keys)])
+    }
+
+    fn send_keys_many(&self, target: &str, keys: &[KeyArg]) -> Result<()> {
+        self.record_keys(target, keys)
+    }
+
+    fn select_pane(&self, target: &str) -> Result<()> {
+        self.record_pane_action(target, FakeAction::SelectPane(target.to_string()))
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        self.record_pane_action(target, FakeAction::SelectWindow(target.to_string()))
+    }
+
+    fn focus_pane(&self, target: &str) -> Result<()> {
+        self.record_pane_action(target, FakeAction::Focus(target.to_string()))
+    }
+
+    fn kill_application(&self, target: &str, method: &KillMethod) -> Result<()> {
+        self.record_pane_action(
+            target,
+            FakeAction::KillApplication(target.to_string(), method.clone()),
+        )
+    }
+
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if !state.has_session(old_name) {
+            anyhow::bail!("can't find session: {}", old_name);
+        }
+        if state.has_session(new_name) {
+            anyhow::bail!("duplicate session: {}", new_name);
+        }
+        for pane in state.panes.iter_mut() {
+            if pane.info.session == old_name {
+                pane.info.session = new_name.to_string();
+            }
+        }
+        state.actions.push(FakeAction::RenameSession(
+            old_name.to_string(),
+            new_name.to_string(),
+        ));
+        Ok(())
+    }
+
+    fn kill_session(&self, target_session: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if !state.has_session(target_session) {
+            anyhow::bail!("can't find session: {}", target_session);
+        }
+        state.panes.retain(|p| p.info.session != target_session);
+        state
+            .actions
+            .push(FakeAction::KillSession(target_session.to_string()));
+        Ok(())
+    }
+
+    fn new_pane(&self, pane: &NewPane) -> Result<String> {
+        let mut state = self.state.lock();
+        let window = state
+            .panes
+            .iter()
+            .filter(|p| p.info.session == pane.session)
+            .map(|p| p.info.window + 1)
+            .max()
+            .unwrap_or(0);
+        let command = pane
+            .command
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        state.next_pid += 1;
+        let info = PaneInfo {
+            server: None,
+            session: pane.session.clone(),
+            window,
+            window_name: pane.window.clone().unwrap_or_else(|| command.clone()),
+            pane: 0,
+            command,
+            title: String::new(),
+            path: pane
+                .cwd
+                .clone()
+                .unwrap_or_else(|| format!("/work/{}", pane.session)),
+            pid: 1000 + state.next_pid,
+            id: format!("%{}", state.next_pid),
+            cmdline: pane.command.clone(),
+            child_commands: Vec::new(),
+            ancestor_commands: Vec::new(),
+        };
+        let target = info.target();
+        state.panes.push(FakePane {
+            info,
+            frames: VecDeque::new(),
+            content: String::new(),
+        });
+        state
+            .actions
+            .push(FakeAction::NewPane(target.clone(), pane.clone()));
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_replays_frames() {
+        let fake = FakeTmux::new();
+        fake.add_pane("main:0.1", "claude").unwrap();
+        fake.script("main:0.1", ["first", "second"]).unwrap();
+
+        assert_eq!(fake.capture_pane("main:0.1").unwrap(), "first");
+        assert_eq!(fake.capture_pane("main:0.1").unwrap(), "second");
+        // The last frame sticks once the script is exhausted
+        assert_eq!(fake.capture_pane("main:0.1").unwrap(), "second");
+        assert!(fake.capture_pane("main:0.2").is_err());
+
+        fake.send_keys_many("main:0.1", &KeyArg::names(&["y", "Enter"]))
+            .unwrap();
+        assert_eq!(
+            fake.take_sent_keys(),
+            vec![SentKeys {
+                target: "main:0.1".to_string(),
+                keys: KeyArg::names(&["y", "Enter"]),
+            }]
+        );
+
+        fake.rename_session("main", "work").unwrap();
+        assert_eq!(fake.list_panes().unwrap()[0].target(), "work:0.1");
+        fake.kill_session("work").unwrap();
+        assert!(fake.list_panes().unwrap().is_empty());
+    }
+}
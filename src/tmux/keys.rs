@@ -0,0 +1,45 @@
+/// One entry of a key list sent to a pane with `TmuxBackend::send_keys_many`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyArg {
+    /// tmux key name(s) such as "Enter" or "C-c"
+    Key(String),
+    /// Text typed character by character (`send-keys -l`)
+    Literal(String),
+    /// Text pasted through a tmux buffer (`paste-buffer -p`)
+    Paste(String),
+}
+
+impl KeyArg {
+    /// tmux key name
+    pub fn key(name: impl Into<String>) -> Self {
+        KeyArg::Key(name.into())
+    }
+
+    /// Text typed literally
+    pub fn literal(text: impl Into<String>) -> Self {
+        KeyArg::Literal(text.into())
+    }
+
+    /// Text pasted with bracketed paste
+    pub fn paste(text: impl Into<String>) -> Self {
+        KeyArg::Paste(text.into())
+    }
+
+    /// Key list of plain tmux key names
+    pub fn names<S: AsRef<str>>(names: &[S]) -> Vec<KeyArg> {
+        names.iter().map(|k| KeyArg::key(k.as_ref())).collect()
+    }
+
+    /// The key name or text
+    pub fn text(&self) -> &str {
+        match self {
+            KeyArg::Key(s) | KeyArg::Literal(s) | KeyArg::Paste(s) => s,
+        }
+    }
+}
+
+impl AsRef<str> for KeyArg {
+    fn as_ref(&self) -> &str {
+        self.text()
+    }
+}
@@ -1,5 +1,17 @@
+mod backend;
 mod client;
+mod control_mode;
+#[cfg(any(test, feature = "test-util"))]
+mod fake;
+mod keys;
 mod pane;
+mod server;
 
+pub use backend::{NewPane, TmuxBackend};
 pub use client::TmuxClient;
+pub use control_mode::{ControlModeEvents, ControlModeWatcher};
+#[cfg(any(test, feature = "test-util"))]
+pub use fake::{FakeAction, FakeTmux, SentKeys};
+pub use keys::KeyArg;
 pub use pane::{refresh_process_cache, PaneInfo};
+pub use server::{TmuxServer, SERVER_SEPARATOR};
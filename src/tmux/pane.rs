@@ -5,6 +5,8 @@ use std::process::Command;
 use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 
+use super::server::SERVER_SEPARATOR;
+
 #[derive(Clone, Debug)]
 struct ProcessInfo {
     command: String,
@@ -145,6 +147,8 @@ pub fn refresh_process_cache() {
 /// Represents a tmux pane with its identifying information
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PaneInfo {
+    /// Label of the tmux server the pane lives on (None for the default server)
+    pub server: Option<String>,
     /// Session name
     pub session: String,
     /// Window index
@@ -161,6 +165,8 @@ pub struct PaneInfo {
     pub path: String,
     /// Process ID of the pane
     pub pid: u32,
+    /// tmux pane id (e.g. "%12"), unique on its server
+    pub id: String,
     /// Full command line of the process
     pub cmdline: String,
     /// Child process commands (for detecting running agents)
@@ -170,13 +176,20 @@ pub struct PaneInfo {
 }
 
 impl PaneInfo {
-    /// Returns the tmux target string (e.g., "session:0.1")
+    /// Returns the tmux target string (e.g., "session:0.1", or "label/session:0.1"
+    /// for panes on an additional server)
     pub fn target(&self) -> String {
-        format!("{}:{}.{}", self.session, self.window, self.pane)
+        match &self.server {
+            Some(label) => format!(
+                "{}{}{}:{}.{}",
+                label, SERVER_SEPARATOR, self.session, self.window, self.pane
+            ),
+            None => format!("{}:{}.{}", self.session, self.window, self.pane),
+        }
     }
 
     /// Parses a pane info from tmux list-panes output
-    /// Expected format: "session:window.pane\twindow_name\tcommand\tpid\ttitle\tpath"
+    /// Expected format: "session:window.pane\twindow_name\tcommand\tpid\ttitle\tpath\tpane_id"
     pub fn parse(line: &str) -> Option<Self> {
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() < 6 {
@@ -189,6 +202,7 @@ impl PaneInfo {
         let pid: u32 = parts[3].parse().ok()?;
         let title = parts[4].to_string();
         let path = parts[5].to_string();
+        let id = parts.get(6).unwrap_or(&"").to_string();
 
         // Parse target "session:window.pane"
         let (session, rest) = target.split_once(':')?;
@@ -205,6 +219,7 @@ impl PaneInfo {
         let ancestor_commands = cache.get_ancestor_commands(pid, 3); // Look up 3 levels
 
         Some(Self {
+            server: None,
             session: session.to_string(),
             window,
             window_name,
@@ -213,6 +228,7 @@ impl PaneInfo {
             title,
             path,
             pid,
+            id,
             cmdline,
             child_commands,
             ancestor_commands,
@@ -252,6 +268,8 @@ mod tests {
     #[test]
     fn test_target() {
         let pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "dev".to_string(),
             window: 2,
             window_name: "editor".to_string(),
@@ -265,6 +283,12 @@ mod tests {
             ancestor_commands: Vec::new(),
         };
         assert_eq!(pane.target(), "dev:2.3");
+
+        let remote = PaneInfo {
+            server: Some("proj".to_string()),
+            ..pane
+        };
+        assert_eq!(remote.target(), "proj/dev:2.3");
     }
 
     #[test]
@@ -276,6 +300,8 @@ mod tests {
     #[test]
     fn test_detection_strings() {
         let pane = PaneInfo {
+            server: None,
+            id: String::new(),
             session: "main".to_string(),
             window: 0,
             window_name: "code".to_string(),
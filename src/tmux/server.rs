@@ -0,0 +1,240 @@
+use std::ffi::OsStr;
+use std::process::Command;
+
+use tracing::warn;
+
+use crate::app::config::TmuxServerConfig;
+use crate::util::shell_escape;
+
+/// Separator between a server label and the tmux target ("label/session:0.1")
+pub const SERVER_SEPARATOR: char = '/';
+
+/// Default template for running tmux on remote hosts.
+/// The shared ControlMaster connection keeps per-command overhead low.
+pub const DEFAULT_REMOTE_COMMAND: &str = "ssh -o BatchMode=yes -o ConnectTimeout=5 \
+     -o ControlMaster=auto -o ControlPath=~/.ssh/tmuxx-%C -o ControlPersist=300 {host} {cmd}";
+
+/// A remote host reached through a command template
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RemoteHost {
+    host: String,
+    /// Template with {host} and {cmd} placeholders
+    template: String,
+}
+
+/// A tmux server the client talks to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxServer {
+    /// Label of an additional server, None for the default server
+    pub label: Option<String>,
+    /// Arguments selecting the socket (`-L NAME` / `-S PATH`)
+    socket_args: Vec<String>,
+    /// Set for servers on other hosts
+    remote: Option<RemoteHost>,
+}
+
+impl TmuxServer {
+    /// The server plain `tmux` talks to (respects `$TMUX`)
+    pub fn default_server() -> Self {
+        Self {
+            label: None,
+            socket_args: Vec::new(),
+            remote: None,
+        }
+    }
+
+    /// Builds a server from config, or None (with a warning) if the entry is invalid
+    pub fn from_config(config: &TmuxServerConfig) -> Option<Self> {
+        let label = config.label.trim();
+        if label.is_empty() || label.contains(SERVER_SEPARATOR) || label.contains(':') {
+            warn!(
+                "Ignoring tmux server with invalid label '{}' (must be non-empty, without '/' or ':')",
+                config.label
+            );
+            return None;
+        }
+
+        let remote = config
+            .host
+            .as_deref()
+            .filter(|h| !h.trim().is_empty())
+            .map(|host| RemoteHost {
+                host: host.trim().to_string(),
+                template: config
+                    .remote_command
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_REMOTE_COMMAND.to_string()),
+            });
+
+        let socket_args = match (&config.socket_path, &config.socket_name) {
+            (Some(path), _) => vec!["-S".to_string(), path.clone()],
+            (None, Some(name)) => vec!["-L".to_string(), name.clone()],
+            // A remote host without socket uses its default server
+            (None, None) if remote.is_some() => Vec::new(),
+            (None, None) => {
+                warn!(
+                    "Ignoring tmux server '{}' without socket_name, socket_path or host",
+                    label
+                );
+                return None;
+            }
+        };
+
+        Some(Self {
+            label: Some(label.to_string()),
+            socket_args,
+            remote,
+        })
+    }
+
+    /// Returns true if the server runs on another host (pids are not local)
+    pub fn is_remote(&self) -> bool {
+        self.remote.is_some()
+    }
+
+    /// Returns a `tmux` command with the given arguments bound to this server.
+    ///
+    /// For remote servers the whole tmux command line is shell-escaped and passed
+    /// as `{cmd}` to the remote command template, which runs via `bash -c`.
+    pub fn command<I, S>(&self, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        match &self.remote {
+            None => {
+                let mut cmd = Command::new("tmux");
+                cmd.args(&self.socket_args).args(args);
+                cmd
+            }
+            Some(remote) => {
+                let remote_cmdline = std::iter::once("tmux".to_string())
+                    .chain(self.socket_args.iter().cloned())
+                    .chain(
+                        args.into_iter()
+                            .map(|a| a.as_ref().to_string_lossy().into_owned()),
+                    )
+                    .map(|a| shell_escape(&a))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let expanded = remote
+                    .template
+                    .replace("{host}", &shell_escape(&remote.host))
+                    .replace("{cmd}", &shell_escape(&remote_cmdline));
+                let mut cmd = Command::new("bash");
+                cmd.args(["-c", &expanded]);
+                cmd
+            }
+        }
+    }
+
+    /// Qualifies a raw tmux target (or session name) with this server's label
+    pub fn qualify(&self, target: &str) -> String {
+        match &self.label {
+            Some(label) => format!("{}{}{}", label, SERVER_SEPARATOR, target),
+            None => target.to_string(),
+        }
+    }
+
+    /// Strips this server's label from a qualified target, if it belongs to this server
+    pub fn strip<'a>(&self, target: &'a str) -> Option<&'a str> {
+        match &self.label {
+            Some(label) => target
+                .strip_prefix(label.as_str())?
+                .strip_prefix(SERVER_SEPARATOR),
+            None => Some(target),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_config(label: &str) -> TmuxServerConfig {
+        TmuxServerConfig {
+            label: label.to_string(),
+            socket_name: None,
+            socket_path: None,
+            host: None,
+            remote_command: None,
+        }
+    }
+
+    #[test]
+    fn test_qualify_and_strip() {
+        let server = TmuxServer::from_config(&TmuxServerConfig {
+            socket_name: Some("proj-sock".to_string()),
+            ..server_config("proj")
+        })
+        .unwrap();
+        assert_eq!(server.socket_args, vec!["-L", "proj-sock"]);
+        assert_eq!(server.qualify("main:0.1"), "proj/main:0.1");
+        assert_eq!(server.strip("proj/main:0.1"), Some("main:0.1"));
+        assert_eq!(server.strip("main:0.1"), None);
+        assert_eq!(server.strip("project/main:0.1"), None);
+
+        let default = TmuxServer::default_server();
+        assert_eq!(default.qualify("main:0.1"), "main:0.1");
+        assert_eq!(default.strip("main:0.1"), Some("main:0.1"));
+    }
+
+    #[test]
+    fn test_invalid_servers_are_rejected() {
+        assert!(TmuxServer::from_config(&server_config("x")).is_none());
+
+        let bad_label = TmuxServerConfig {
+            socket_name: Some("s".to_string()),
+            ..server_config("a/b")
+        };
+        assert!(TmuxServer::from_config(&bad_label).is_none());
+
+        let path_wins = TmuxServer::from_config(&TmuxServerConfig {
+            socket_name: Some("ignored".to_string()),
+            socket_path: Some("/tmp/ci.sock".to_string()),
+            ..server_config("ci")
+        })
+        .unwrap();
+        assert_eq!(path_wins.socket_args, vec!["-S", "/tmp/ci.sock"]);
+    }
+
+    #[test]
+    fn test_remote_command_preserves_arguments() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for ssh: a local shell running a fake `tmux` that prints its arguments
+        let dir = tempfile::tempdir().unwrap();
+        let fake_tmux = dir.path().join("tmux");
+        std::fs::write(
+            &fake_tmux,
+            "#!/bin/sh\nfor a in \"$@\"; do echo \"[$a]\"; done\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake_tmux, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let server = TmuxServer::from_config(&TmuxServerConfig {
+            host: Some("build-1".to_string()),
+            socket_name: Some("agents".to_string()),
+            remote_command: Some("test {host} = build-1 && bash -c {cmd}".to_string()),
+            ..server_config("build")
+        })
+        .unwrap();
+        assert!(server.is_remote());
+
+        let path = format!(
+            "{}:{}",
+            dir.path().display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let output = server
+            .command(["send-keys", "-t", "main:0.1", "it's $HOME; ls", "Enter"])
+            .env("PATH", path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "[-L]\n[agents]\n[send-keys]\n[-t]\n[main:0.1]\n[it's $HOME; ls]\n[Enter]\n"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Converts text with ANSI escape sequences (as from `capture-pane -e`) into
+/// styled lines. SGR colours and attributes are kept; other sequences are dropped.
+pub fn ansi_to_lines(text: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut style = Style::default();
+    for raw in text.lines() {
+        let mut spans = Vec::new();
+        let mut current = String::new();
+        let mut chars = raw.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                current.push(c);
+                continue;
+            }
+            match chars.next() {
+                // CSI: parameters up to the final byte
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut fin = None;
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            fin = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+                    if fin == Some('m') {
+                        if !current.is_empty() {
+                            spans.push(Span::styled(std::mem::take(&mut current), style));
+                        }
+                        style = apply_sgr(style, &params);
+                    }
+                }
+                // OSC (e.g. hyperlinks): up to BEL or ST
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !current.is_empty() {
+            spans.push(Span::styled(current, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Applies an SGR parameter list ("1;38;5;208") to a style
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let codes: Vec<u16> = params
+        .split([';', ':'])
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse().unwrap_or(0))
+        .collect();
+    if codes.is_empty() {
+        return Style::default();
+    }
+
+    let mut codes = codes.into_iter();
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 | 6 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            21 | 22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(Color::Indexed((code - 30) as u8)),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => Style { fg: None, ..style },
+            40..=47 => style.bg(Color::Indexed((code - 40) as u8)),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => Style { bg: None, ..style },
+            90..=97 => style.fg(Color::Indexed((code - 90 + 8) as u8)),
+            100..=107 => style.bg(Color::Indexed((code - 100 + 8) as u8)),
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Reads the rest of a 38/48 colour: "5;n" (256 colours) or "2;r;g;b"
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()? as u8)),
+        2 => {
+            let (r, g, b) = (codes.next()?, codes.next()?, codes.next()?);
+            Some(Color::Rgb(r as u8, g as u8, b as u8))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_to_lines() {
+        let lines = ansi_to_lines(
+            "\x1b[1;31mError\x1b[0m: plain\n\x1b[38;5;208mo\x1b[48;2;1;2;3mrgb\x1b[39m\x1b]8;;http://x\x1b\\link\x1b[K",
+        );
+        assert_eq!(lines.len(), 2);
+
+        let spans = &lines[0].spans;
+        assert_eq!(spans[0].content, "Error");
+        assert_eq!(
+            spans[0].style,
+            Style::default()
+                .fg(Color::Indexed(1))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(spans[1].content, ": plain");
+        assert_eq!(spans[1].style, Style::default());
+
+        let spans = &lines[1].spans;
+        assert_eq!(spans[0].style.fg, Some(Color::Indexed(208)));
+        assert_eq!(spans[1].content, "rgb");
+        assert_eq!(spans[1].style.bg, Some(Color::Rgb(1, 2, 3)));
+        // Style carries over lines; OSC and erase sequences are dropped
+        assert_eq!(spans[2].content, "link");
+        assert_eq!(spans[2].style.fg, None);
+        assert_eq!(spans[2].style.bg, Some(Color::Rgb(1, 2, 3)));
+    }
+}
@@ -16,16 +16,21 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
 use tui_textarea::Input;
 
+use crate::app::history::InputHistory;
 use crate::app::key_binding::CommandConfig;
-use crate::app::{Action, AppState, Config, KeyAction, NavAction};
+use crate::app::{template, Action, AppState, Config, KeyAction, NavAction};
+use crate::audit::{AuditAction, AuditEntry, AuditLog, AuditSource};
+use crate::control::ControlMessage;
 use crate::monitor::{MonitorTask, SystemStatsCollector};
 use crate::parsers::ParserRegistry;
-use crate::tmux::TmuxClient;
+use crate::tmux::{KeyArg, TmuxBackend, TmuxClient};
+use crate::workflow;
 
 use super::components::{
     AgentTreeWidget, FooterWidget, HeaderWidget, InputWidget, MenuTreeWidget, ModalTextareaWidget,
     PanePreviewWidget, PopupInputWidget, SubagentLogWidget,
 };
+use super::mirror;
 use super::Layout;
 
 // Layout constants
@@ -49,6 +54,12 @@ pub async fn run_app(config: Config) -> Result<()> {
     let tmux_client = Arc::new(TmuxClient::from_config(&config));
     let parser_registry = Arc::new(ParserRegistry::with_config(&config));
 
+    match AuditLog::from_config(&config.audit, config.audit_log_path()) {
+        Ok(log) => state.audit_log = log,
+        Err(e) => state.set_error(format!("Audit log disabled: {:#}", e)),
+    }
+    state.input_history = InputHistory::new(config.history_dir(), config.history.max_entries);
+
     // Check if tmux is available
     if !tmux_client.is_available() {
         state.set_error("tmux is not running".to_string());
@@ -74,6 +85,20 @@ pub async fn run_app(config: Config) -> Result<()> {
         monitor.run().await;
     });
 
+    // Start control socket server if configured
+    let (control_tx, mut control_rx) = mpsc::channel::<ControlMessage>(16);
+    let control_path = config.control_socket_path();
+    let control_handle = match &control_path {
+        Some(path) => match crate::control::spawn_server(path, control_tx) {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                state.set_error(format!("Control socket disabled: {:#}", e));
+                None
+            }
+        },
+        None => None,
+    };
+
     // Create system stats collector
     let mut system_stats = SystemStatsCollector::new();
 
@@ -82,7 +107,8 @@ pub async fn run_app(config: Config) -> Result<()> {
         &mut terminal,
         &mut state,
         &mut rx,
-        &tmux_client,
+        &mut control_rx,
+        tmux_client.as_ref(),
         &mut system_stats,
         &user_interacted,
     )
@@ -90,6 +116,12 @@ pub async fn run_app(config: Config) -> Result<()> {
 
     // Cleanup
     monitor_handle.abort();
+    if let Some(handle) = control_handle {
+        handle.abort();
+        if let Some(path) = &control_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
@@ -105,13 +137,34 @@ async fn run_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
     rx: &mut mpsc::Receiver<crate::monitor::MonitorUpdate>,
-    tmux_client: &TmuxClient,
+    control_rx: &mut mpsc::Receiver<ControlMessage>,
+    tmux_client: &dyn TmuxBackend,
     system_stats: &mut SystemStatsCollector,
     user_interacted: &Arc<AtomicBool>,
 ) -> Result<()> {
     let mut needs_redraw = true;
 
     loop {
+        // Send staggered broadcast input that is due
+        if flush_broadcast(state, tmux_client) {
+            needs_redraw = true;
+        }
+
+        // Run the next steps of workflows in progress
+        if workflow::advance_workflows(state, tmux_client) {
+            needs_redraw = true;
+        }
+
+        // Send initial prompts to launched agents that became idle
+        if workflow::advance_launches(state, tmux_client) {
+            needs_redraw = true;
+        }
+
+        // Re-capture the zoomed pane
+        if mirror::refresh_mirror(state, tmux_client) {
+            needs_redraw = true;
+        }
+
         // Advance animation tick
         let old_tick = state.tick;
         state.tick();
@@ -143,6 +196,9 @@ async fn run_loop(
                 HeaderWidget::render(frame, main_chunks[0], state);
 
                 // Calculate input height based on config
+                // The zoomed pane mirror takes the summary's space
+                let show_summary = state.show_summary_detail && state.mirror.is_none();
+
                 let input_height = if state.config.hide_bottom_input {
                     0 // No input widget shown
                 } else {
@@ -164,7 +220,7 @@ async fn run_loop(
                             ratatui::layout::Constraint::Length(input_height + INPUT_BORDER_HEIGHT),
                         ])
                         .split(preview);
-                    if state.show_summary_detail {
+                    if show_summary {
                         PanePreviewWidget::render_summary(frame, preview_chunks[0], state);
                     }
 
@@ -185,10 +241,10 @@ async fn run_loop(
                         let (left, summary, preview) = Layout::content_layout_no_input(
                             main_chunks[1],
                             &state.sidebar_width,
-                            state.show_summary_detail,
+                            show_summary,
                         );
                         AgentTreeWidget::render(frame, left, state);
-                        if state.show_summary_detail {
+                        if show_summary {
                             PanePreviewWidget::render_summary(frame, summary, state);
                         }
                         if state.selected_agent().is_some() {
@@ -201,10 +257,10 @@ async fn run_loop(
                                 main_chunks[1],
                                 &state.sidebar_width,
                                 input_height,
-                                state.show_summary_detail,
+                                show_summary,
                             );
                         AgentTreeWidget::render(frame, left, state);
-                        if state.show_summary_detail {
+                        if show_summary {
                             PanePreviewWidget::render_summary(frame, summary, state);
                         }
                         if state.selected_agent().is_some() {
@@ -241,13 +297,42 @@ async fn run_loop(
 
                 // Prompts Menu (before help)
                 if state.show_prompts {
+                    let title = match state.broadcast_label() {
+                        Some(label) => format!("Prompts Menu → {}", label),
+                        None => "Prompts Menu".to_string(),
+                    };
                     MenuTreeWidget::render(
                         frame,
                         size,
                         &mut state.prompts_tree,
                         &state.config.prompts,
                         &state.styles,
-                        "Prompts Menu",
+                        &title,
+                    );
+                }
+
+                // History search (over the input field it was opened from)
+                if let Some(search) = &mut state.history_search {
+                    MenuTreeWidget::render(
+                        frame,
+                        size,
+                        &mut search.tree,
+                        &search.menu,
+                        &state.styles,
+                        "History",
+                    );
+                }
+
+                // File / pane picker (over the editor it was opened from)
+                if let Some(picker) = &mut state.attach_picker {
+                    let title = picker.title();
+                    MenuTreeWidget::render(
+                        frame,
+                        size,
+                        &mut picker.tree,
+                        &picker.menu,
+                        &state.styles,
+                        title,
                     );
                 }
 
@@ -270,6 +355,9 @@ async fn run_loop(
                 state.agents = update.agents;
                 // Sync selection based on agent IDs
                 state.sync_selection();
+                state.sync_choice_picker();
+                state.record_policy_decisions(update.decisions);
+                state.deliver_queued_prompts(&update.transitions);
 
                 // Update cached visibility projection after agent list changes
                 state.update_visible_indices();
@@ -279,6 +367,13 @@ async fn run_loop(
                 needs_redraw = true;
             }
 
+            // Handle control socket requests
+            Some((request, reply)) = control_rx.recv() => {
+                let response = crate::control::handle_request(state, tmux_client, request);
+                let _ = reply.send(response);
+                needs_redraw = true;
+            }
+
             // Handle keyboard and mouse events
             _ = tokio::time::sleep(timeout) => {
                 // Process all pending events to avoid input lag
@@ -343,6 +438,9 @@ async fn run_loop(
 
                     // Handle paste events
                     if let Event::Paste(data) = &event {
+                        if mirror::passthrough_paste(state, tmux_client, data) {
+                            continue;
+                        }
                         if let Some(modal) = &mut state.modal_textarea {
                             if !modal.readonly {
                                 for line in data.lines() {
@@ -362,9 +460,69 @@ async fn run_loop(
 
                     // Handle keyboard events
                     if let Event::Key(key) = event {
+                        // Passthrough sends every key to the zoomed pane
+                        if mirror::passthrough_key(state, tmux_client, &key) {
+                            continue;
+                        }
+
+                        // History search (opened from an input field) takes the keys first
+                        if let Some(search) = &mut state.history_search {
+                            use crate::ui::components::menu_tree::get_current_items_count;
+
+                            match key.code {
+                                KeyCode::Esc => {
+                                    state.history_search = None;
+                                }
+                                KeyCode::Enter => {
+                                    state.pick_history_search();
+                                }
+                                KeyCode::Down => {
+                                    let count = get_current_items_count(&search.menu, &search.tree);
+                                    search.tree.key_down(count);
+                                }
+                                KeyCode::Up => {
+                                    let count = get_current_items_count(&search.menu, &search.tree);
+                                    search.tree.key_up(count);
+                                }
+                                KeyCode::Backspace => {
+                                    search.tree.filter.pop();
+                                    search.tree.list_state.select(Some(0));
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    search.tree.filter.push(c);
+                                    search.tree.list_state.select(Some(0));
+                                }
+                                _ => {}
+                            }
+                        } else if let Some(picker) = &mut state.attach_picker {
+                            use crate::ui::components::menu_tree::get_current_items_count;
 
-                        // Special handling for modal textarea (both editable and readonly)
-                        if state.modal_textarea.is_some() {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    state.attach_picker = None;
+                                }
+                                KeyCode::Enter => {
+                                    state.pick_attachment(tmux_client);
+                                }
+                                KeyCode::Down => {
+                                    let count = get_current_items_count(&picker.menu, &picker.tree);
+                                    picker.tree.key_down(count);
+                                }
+                                KeyCode::Up => {
+                                    let count = get_current_items_count(&picker.menu, &picker.tree);
+                                    picker.tree.key_up(count);
+                                }
+                                KeyCode::Backspace => {
+                                    picker.tree.filter.pop();
+                                    picker.tree.list_state.select(Some(0));
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    picker.tree.filter.push(c);
+                                    picker.tree.list_state.select(Some(0));
+                                }
+                                _ => {}
+                            }
+                        } else if state.modal_textarea.is_some() {
                             // Check for special keys first
                             let action = map_key_to_action(key.code, key.modifiers, state, &state.config);
                             state.log_action(&action);
@@ -373,17 +531,24 @@ async fn run_loop(
                                 Action::ModalTextareaSubmit => {
                                     if let Some(modal) = state.modal_textarea.take() {
                                         let text = modal.get_text();
-                                        // Send text to selected agent
-                                        if let Some(agent) = state.agents.get_agent(state.selected_index) {
-                                            if let Err(e) = tmux_client.send_keys(&agent.target, &text) {
-                                                state.set_error(format!("Failed to send input: {}", e));
-                                            }
+                                        if let Some(agent_id) = state.queue_editor.take() {
+                                            state.save_prompt_queue(&agent_id, &text);
+                                        } else {
+                                            // Send text to the selected agents
+                                            state.broadcast_text(text);
                                         }
+                                        flush_broadcast(state, tmux_client);
                                     }
                                 }
                                 Action::HideModalTextarea => {
                                     state.modal_textarea = None;
+                                    state.queue_editor = None;
                                 }
+                                Action::HistoryPrev => state.recall_history(true),
+                                Action::HistoryNext => state.recall_history(false),
+                                Action::ShowHistorySearch => state.show_history_search(),
+                                Action::ShowFilePicker => state.show_file_picker(),
+                                Action::ShowPanePicker => state.show_pane_picker(tmux_client),
                                 Action::HideHelp => {
                                     state.show_help = false;
                                     state.modal_textarea = None;
@@ -477,11 +642,9 @@ async fn run_loop(
                                     let count = get_current_items_count(&state.config.menu, &state.menu_tree);
                                     for _ in 0..10 { state.menu_tree.key_up(count); }
                                 }
-                                 KeyCode::Backspace => {
-                                     if !state.menu_tree.filter.is_empty() {
-                                         state.menu_tree.filter.pop();
-                                         state.menu_tree.list_state.select(Some(0));
-                                     }
+                                 KeyCode::Backspace if !state.menu_tree.filter.is_empty() => {
+                                     state.menu_tree.filter.pop();
+                                     state.menu_tree.list_state.select(Some(0));
                                  }
 
                                  KeyCode::Enter => {
@@ -497,7 +660,7 @@ async fn run_loop(
 
                                               // Get context from selected agent before any state mutations
                                               let (target, path, expanded) = if let Some(agent) = state.selected_agent() {
-                                                  (Some(agent.target.clone()), agent.path.clone(), expand_command_variables(&execute_command.command, agent))
+                                                  (Some(agent.target.clone()), agent.path.clone(), template::expand_shell_variables(&execute_command.command, agent))
                                               } else {
                                                   (None, String::new(), execute_command.command.clone())
                                               };
@@ -510,6 +673,7 @@ async fn run_loop(
                                                   active_in_tmux: execute_command.active_in_tmux,
                                               };
                                               state.log_action(&action);
+                                              state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Command, target.as_deref()).detail(expanded.clone()));
 
                                               if execute_command.active_in_tmux {
                                                   if let Some(t) = target {
@@ -628,7 +792,6 @@ async fn run_loop(
                             }
                         } else if state.show_prompts {
                             use crate::ui::components::menu_tree::{find_flat_menu_item_by_index, get_current_items_count};
-                            use crate::ui::components::ModalTextareaState;
 
                             match key.code {
                                 KeyCode::Esc => {
@@ -705,11 +868,9 @@ async fn run_loop(
                                     let count = get_current_items_count(&state.config.prompts, &state.prompts_tree);
                                     for _ in 0..10 { state.prompts_tree.key_up(count); }
                                 }
-                                 KeyCode::Backspace => {
-                                     if !state.prompts_tree.filter.is_empty() {
-                                         state.prompts_tree.filter.pop();
-                                         state.prompts_tree.list_state.select(Some(0));
-                                     }
+                                 KeyCode::Backspace if !state.prompts_tree.filter.is_empty() => {
+                                     state.prompts_tree.filter.pop();
+                                     state.prompts_tree.list_state.select(Some(0));
                                  }
 
                                  KeyCode::Enter => {
@@ -724,31 +885,13 @@ async fn run_loop(
                                          if let Some(text) = text_opt {
                                                 state.toggle_prompts();
 
-                                                if key.modifiers.contains(KeyModifiers::ALT) {
-                                                    // Open in ModalTextarea
-                                                    state.modal_textarea = Some(ModalTextareaState::new(
-                                                        format!("Edit Prompt: {}", name),
-                                                        "Verify/Edit before sending".to_string(),
-                                                        text.clone(),
-                                                        false, // multiline
-                                                        false, // editable
-                                                        &state.styles,
-                                                    ));
+                                                // Alt+Enter opens the prompt in the editor instead of sending it
+                                                let edit = key.modifiers.contains(KeyModifiers::ALT);
+                                                let labels = template::ask_labels(&text);
+                                                if labels.is_empty() {
+                                                    send_prompt(state, tmux_client, name, &text, &[], edit);
                                                 } else {
-                                                    // Send directly
-                                                    if let Some(agent) = state.selected_agent() {
-                                                        if let Err(e) = tmux_client.send_keys_many(
-                                                            &agent.target,
-                                                            &[&text, "Enter"],
-                                                        ) {
-                                                            state.set_error(format!(
-                                                                "Failed to send text: {}",
-                                                                e
-                                                            ));
-                                                        } else {
-                                                            state.set_status(format!("Sent: {}", name));
-                                                        }
-                                                    }
+                                                    state.ask_prompt_parameter(name, text, labels, Vec::new(), edit);
                                                 }
                                          } else if is_submenu {
                                              state.prompts_tree.toggle_expansion(path);
@@ -798,22 +941,7 @@ async fn run_loop(
                                 }
                                 Action::Approve => {
                                     let indices = state.get_operation_indices();
-                                    for idx in indices {
-                                        if let Some(agent) = state.agents.get_agent(idx) {
-                                            if agent.status.needs_attention() {
-                                                let target = agent.target.clone();
-                                                let keys = state.get_agent_keys(agent);
-                                                let key_refs: Vec<&str> =
-                                                    keys.approve.iter().map(|s| s.as_str()).collect();
-                                                if let Err(e) =
-                                                    tmux_client.send_keys_many(&target, &key_refs)
-                                                {
-                                                    state.set_error(format!("Failed to approve: {}", e));
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
+                                    approve_with_policy(state, tmux_client, &indices);
                                 }
                                 Action::Reject => {
                                     let indices = state.get_operation_indices();
@@ -821,12 +949,10 @@ async fn run_loop(
                                         if let Some(agent) = state.agents.get_agent(idx) {
                                             if agent.status.needs_attention() {
                                                 let target = agent.target.clone();
-                                                let keys = state.get_agent_keys(agent);
-                                                let key_refs: Vec<&str> =
-                                                    keys.reject.iter().map(|s| s.as_str()).collect();
-                                                if let Err(e) =
-                                                    tmux_client.send_keys_many(&target, &key_refs)
-                                                {
+                                                let keys = KeyArg::names(&state.get_agent_keys(agent).reject);
+                                                let result = tmux_client.send_keys_many(&target, &keys);
+                                                state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Reject, Some(&target)).keys(&keys).result(&result));
+                                                if let Err(e) = result {
                                                     state.set_error(format!("Failed to reject: {}", e));
                                                     break;
                                                 }
@@ -835,22 +961,9 @@ async fn run_loop(
                                     }
                                 }
                                 Action::ApproveAll => {
-                                    for agent in &state.agents.root_agents {
-                                        if agent.status.needs_attention() {
-                                            let keys = state.get_agent_keys(agent);
-                                            let key_refs: Vec<&str> =
-                                                keys.approve.iter().map(|s| s.as_str()).collect();
-                                            if let Err(e) =
-                                                tmux_client.send_keys_many(&agent.target, &key_refs)
-                                            {
-                                                state.set_error(format!(
-                                                    "Failed to approve {}: {}",
-                                                    agent.target, e
-                                                ));
-                                                break;
-                                            }
-                                        }
-                                    }
+                                    let indices: Vec<usize> =
+                                        (0..state.agents.root_agents.len()).collect();
+                                    approve_with_policy(state, tmux_client, &indices);
                                 }
                                 Action::FocusPane => {
                                     if let Some(agent) = state.selected_agent() {
@@ -919,35 +1032,94 @@ async fn run_loop(
                                 }
                                 Action::SendInput => {
                                     let input = state.take_input();
-                                    if let Some(agent) = state.selected_agent() {
-                                        let target = agent.target.clone();
-                                        let res = if input.is_empty() {
-                                            tmux_client.send_keys(&target, "Enter")
+                                    state.record_history(&input);
+                                    state.broadcast_input(None, |_| input.clone(), |keys, text| {
+                                        if text.is_empty() {
+                                            KeyArg::names(&["Enter"])
                                         } else {
-                                            let keys = state.get_agent_keys(agent);
-                                            let expanded = keys.expand_input(&input);
-                                            let key_refs: Vec<&str> =
-                                                expanded.iter().map(|s| s.as_str()).collect();
-                                            tmux_client.send_keys_many(&target, &key_refs)
-                                        };
-                                        if let Err(e) = res {
-                                            state.set_error(format!("Failed to send input: {}", e));
+                                            keys.expand_input(text)
                                         }
-                                    }
+                                    });
+                                    flush_broadcast(state, tmux_client);
                                 }
                                 Action::SendNumber(num) => {
                                     if let Some(agent) = state.selected_agent() {
                                         let target = agent.target.clone();
                                         let keys = state.get_agent_keys(agent);
                                         let expanded = keys.expand_number(num);
-                                        let key_refs: Vec<&str> =
-                                            expanded.iter().map(|s| s.as_str()).collect();
-                                        if let Err(e) = tmux_client.send_keys_many(&target, &key_refs)
-                                        {
+                                        let result = tmux_client.send_keys_many(&target, &expanded);
+                                        state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Number, Some(&target)).keys(&expanded).result(&result));
+                                        if let Err(e) = result {
                                             state.set_error(format!("Failed to send number: {}", e));
                                         }
                                     }
                                 }
+                                Action::ShowChoicePicker => {
+                                    if !state.open_choice_picker() {
+                                        state.set_error(
+                                            "Selected agent is not asking a question with known choices"
+                                                .to_string(),
+                                        );
+                                    }
+                                }
+                                Action::HideChoicePicker => {
+                                    state.choice_picker = None;
+                                }
+                                Action::ChoiceMove(delta) => {
+                                    if let Some(picker) = &mut state.choice_picker {
+                                        picker.move_cursor(delta);
+                                    }
+                                }
+                                Action::ChoiceToggle => {
+                                    if let Some(picker) = &mut state.choice_picker {
+                                        picker.toggle();
+                                    }
+                                }
+                                Action::ChoicePick(index) => {
+                                    if let Some(picker) = &mut state.choice_picker {
+                                        picker.pick(index);
+                                    }
+                                }
+                                Action::ChoiceSubmit => {
+                                    if let Some(picker) = state.choice_picker.take() {
+                                        let picked = picker.picked_numbers();
+                                        let expanded = state
+                                            .agents
+                                            .root_agents
+                                            .iter()
+                                            .find(|a| a.id == picker.agent_id)
+                                            .map(|a| {
+                                                state
+                                                    .get_agent_keys(a)
+                                                    .expand_choices(&picked, picker.multi_select)
+                                            });
+                                        match expanded {
+                                            Some(expanded) => {
+                                                let labels: Vec<&str> = picked
+                                                    .iter()
+                                                    .filter_map(|n| picker.choices.get(*n as usize - 1))
+                                                    .map(|s| s.as_str())
+                                                    .collect();
+                                                let result = tmux_client.send_keys_many(&picker.target, &expanded);
+                                                state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Choice, Some(&picker.target)).keys(&expanded).detail(labels.join(", ")).result(&result));
+                                                match result {
+                                                    Ok(_) => {
+                                                        state.set_status(format!(
+                                                            "✓ Answered: {}",
+                                                            labels.join(", ")
+                                                        ));
+                                                    }
+                                                    Err(e) => {
+                                                        state.set_error(format!("Failed to answer: {}", e));
+                                                    }
+                                                }
+                                            }
+                                            None => {
+                                                state.set_error("Agent is no longer available".to_string());
+                                            }
+                                        }
+                                    }
+                                }
                                 Action::SidebarWider => {
                                     state.sidebar_width.wider();
                                 }
@@ -970,7 +1142,9 @@ async fn run_loop(
                                     for idx in indices {
                                         if let Some(agent) = state.agents.get_agent(idx) {
                                             let target = agent.target.clone();
-                                            if let Err(e) = tmux_client.send_keys(&target, &keys) {
+                                            let result = tmux_client.send_keys(&target, &keys);
+                                            state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Keys, Some(&target)).keys(&[&keys]).result(&result));
+                                            if let Err(e) = result {
                                                 state.set_error(format!("Failed to send keys: {}", e));
                                                 break;
                                             } else {
@@ -985,7 +1159,9 @@ async fn run_loop(
                                     for idx in indices {
                                         if let Some(agent) = state.agents.get_agent(idx) {
                                             let target = agent.target.clone();
-                                            if let Err(e) = tmux_client.kill_application(&target, &method) {
+                                            let result = tmux_client.kill_application(&target, &method);
+                                            state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::KillApp, Some(&target)).detail(format!("{:?}", method)).result(&result));
+                                            if let Err(e) = result {
                                                 state.set_error(format!("Failed to kill app: {}", e));
                                                 break;
                                             }
@@ -1000,7 +1176,7 @@ async fn run_loop(
                                             prompt: format!("Are you sure you want to kill session '{}'? (y/n)", agent.session),
                                             initial: String::new(), // Empty buffer expects y/n
                                             popup_type: crate::app::PopupType::KillConfirmation {
-                                                session: agent.session.clone(),
+                                                session: agent.qualified_session(),
                                             },
                                         };
                                         // We need to trigger the action immediately to show popup
@@ -1012,7 +1188,7 @@ async fn run_loop(
                                             buffer: String::new(),
                                             cursor: 0,
                                             popup_type: crate::app::PopupType::KillConfirmation {
-                                                session: agent.session.clone(),
+                                                session: agent.qualified_session(),
                                             },
                                         });
                                     }
@@ -1025,12 +1201,14 @@ async fn run_loop(
                                     active_in_tmux,
                                 } => {
                                     let (target, path, expanded) = if let Some(agent) = state.selected_agent() {
-                                        (Some(agent.target.clone()), agent.path.clone(), expand_command_variables(&command, agent))
+                                        (Some(agent.target.clone()), agent.path.clone(), template::expand_shell_variables(&command, agent))
                                     } else {
                                         (None, String::new(), String::new())
                                     };
 
                                     if let Some(target) = target {
+                                        state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::Command, Some(&target)).detail(expanded.clone()));
+
                                         // Ensure the agent's window is active in tmux if requested
                                         if active_in_tmux {
                                             if let Err(e) = tmux_client.select_window(&target) {
@@ -1236,6 +1414,12 @@ async fn run_loop(
                                     use crate::app::PopupInputState;
                                     // Set cursor at end of buffer for rename dialog (easier to edit)
                                     let cursor = initial.len();
+                                    let title = match state.broadcast_label() {
+                                        Some(label) if popup_type == crate::app::PopupType::GeneralInput => {
+                                            format!("{} → {}", title, label)
+                                        }
+                                        _ => title,
+                                    };
                                     state.popup_input = Some(PopupInputState {
                                         title,
                                         prompt,
@@ -1251,6 +1435,10 @@ async fn run_loop(
                                     single_line,
                                 } => {
                                     use crate::ui::components::ModalTextareaState;
+                                    let title = match state.broadcast_label() {
+                                        Some(label) => format!("{} → {}", title, label),
+                                        None => title,
+                                    };
                                     state.modal_textarea = Some(ModalTextareaState::new(
                                         title,
                                         prompt,
@@ -1300,15 +1488,18 @@ async fn run_loop(
                                                     state.set_filter_pattern(Some(popup.buffer));
                                                 }
                                             }
+                                            PopupType::PromptParameter { name, text, mut labels, mut values, edit } => {
+                                                values.push((labels.remove(0), popup.buffer));
+                                                if labels.is_empty() {
+                                                    send_prompt(state, tmux_client, name, &text, &values, edit);
+                                                } else {
+                                                    state.ask_prompt_parameter(name, text, labels, values, edit);
+                                                }
+                                            }
                                             PopupType::GeneralInput => {
                                                 // Send to selected agent
-                                                let text = popup.buffer;
-                                                if let Some(agent) = state.selected_agent() {
-                                                    if let Err(e) = tmux_client.send_keys(&agent.target, &text)
-                                                    {
-                                                        state.set_error(format!("Failed to send input: {}", e));
-                                                    }
-                                                }
+                                                state.broadcast_text(popup.buffer);
+                                                flush_broadcast(state, tmux_client);
                                             }
                                             PopupType::RenameSession { session } => {
                                                 let new_name = popup.buffer.trim();
@@ -1316,8 +1507,10 @@ async fn run_loop(
                                                     state.set_error("Session name cannot be empty".to_string());
                                                 } else if new_name.contains('.') || new_name.contains(':') {
                                                     state.set_error("Session name cannot contain '.' or ':'".to_string());
-                                                } else if new_name != session {
-                                                    if let Err(e) = tmux_client.rename_session(&session, new_name) {
+                                                } else if new_name != tmux_client.unqualified(&session) {
+                                                    let result = tmux_client.rename_session(&session, new_name);
+                                                    state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::RenameSession, Some(&session)).detail(new_name).result(&result));
+                                                    if let Err(e) = result {
                                                         state.set_error(format!("Failed to rename session: {}", e));
                                                     }
                                                 }
@@ -1325,7 +1518,9 @@ async fn run_loop(
                                             }
                                             PopupType::KillConfirmation { session } => {
                                                 if popup.buffer.trim().eq_ignore_ascii_case("y") {
-                                                    if let Err(e) = tmux_client.kill_session(&session) {
+                                                    let result = tmux_client.kill_session(&session);
+                                                    state.audit(AuditEntry::new(AuditSource::Ui, AuditAction::KillSession, Some(&session)).result(&result));
+                                                    if let Err(e) = result {
                                                         state.set_error(format!("Failed to kill session: {}", e));
                                                     } else {
                                                         state.set_status(format!("Killed session: {}", session));
@@ -1333,6 +1528,23 @@ async fn run_loop(
                                                     }
                                                 }
                                             }
+                                            PopupType::ApproveConfirmation { agent_ids } => {
+                                                if popup.buffer.trim().eq_ignore_ascii_case("y") {
+                                                    // Approve those still waiting; the screen may have moved on
+                                                    let indices: Vec<usize> = state
+                                                        .agents
+                                                        .root_agents
+                                                        .iter()
+                                                        .enumerate()
+                                                        .filter(|(_, a)| {
+                                                            agent_ids.contains(&a.id)
+                                                                && a.status.kind() == crate::agents::StatusKind::AwaitingApproval
+                                                        })
+                                                        .map(|(i, _)| i)
+                                                        .collect();
+                                                    send_approvals(state, tmux_client, &indices);
+                                                }
+                                            }
                                             PopupType::CaptureStatus { content } => {
                                                 let status_str = popup.buffer.trim().to_lowercase();
                                                 if status_str.is_empty() {
@@ -1462,6 +1674,38 @@ async fn run_loop(
                                         }
                                     }
                                 }
+                                Action::HistoryPrev => state.recall_history(true),
+                                Action::HistoryNext => state.recall_history(false),
+                                Action::ShowHistorySearch => state.show_history_search(),
+                                // Only mapped in the editor
+                                Action::ShowFilePicker | Action::ShowPanePicker => {}
+                                Action::ResendLast => {
+                                    state.resend_last();
+                                    flush_broadcast(state, tmux_client);
+                                }
+                                Action::EditQueue => state.edit_prompt_queue(),
+                                Action::ToggleZoom => state.toggle_zoom(),
+                                Action::StartPassthrough => state.start_passthrough(),
+                                Action::RunWorkflow(name) => {
+                                    match workflow::start_workflow(state, &name) {
+                                        Ok(count) => state.set_status(format!(
+                                            "Started workflow {} on {} agent(s)",
+                                            name, count
+                                        )),
+                                        Err(e) => state.set_error(format!("{:#}", e)),
+                                    }
+                                    workflow::advance_workflows(state, tmux_client);
+                                }
+                                Action::CancelWorkflows => workflow::cancel_workflows(state),
+                                Action::Launch(name) => {
+                                    match workflow::launch(state, tmux_client, &name) {
+                                        Ok(target) => state.set_status(format!(
+                                            "Launching {} in {}",
+                                            name, target
+                                        )),
+                                        Err(e) => state.set_error(format!("{:#}", e)),
+                                    }
+                                }
                                 Action::NextTheme => {
                                     state.next_theme();
                                 }
@@ -1481,6 +1725,166 @@ async fn run_loop(
     Ok(())
 }
 
+/// Sends a prompt from the Prompts Menu to the selected agents, with its variables
+/// expanded per agent and `{{ask:...}}` placeholders filled, or opens it in the editor
+fn send_prompt(
+    state: &mut AppState,
+    tmux_client: &dyn TmuxBackend,
+    name: String,
+    text: &str,
+    values: &[(String, String)],
+    edit: bool,
+) {
+    if edit {
+        // The editor shows the prompt as expanded for the cursor agent
+        let filled = match state.selected_agent() {
+            Some(agent) => template::fill_asks(&template::expand_variables(text, agent), values),
+            None => template::fill_asks(text, values),
+        };
+        let title = match state.broadcast_label() {
+            Some(label) => format!("Edit Prompt: {} → {}", name, label),
+            None => format!("Edit Prompt: {}", name),
+        };
+        state.modal_textarea = Some(crate::ui::components::ModalTextareaState::new(
+            title,
+            "Verify/Edit before sending".to_string(),
+            filled,
+            false, // multiline
+            false, // editable
+            &state.styles,
+        ));
+    } else {
+        state.broadcast_input(
+            Some(name),
+            |agent| template::fill_asks(&template::expand_variables(text, agent), values),
+            |keys, text| {
+                let body = keys.deliver_text(text);
+                body.into_iter().chain([KeyArg::key("Enter")]).collect()
+            },
+        );
+        flush_broadcast(state, tmux_client);
+    }
+}
+
+/// Sends the due keys of the broadcast in progress. Returns true if anything was sent.
+fn flush_broadcast(state: &mut AppState, tmux_client: &dyn TmuxBackend) -> bool {
+    let due = state.take_due_sends(std::time::Instant::now());
+    for send in &due {
+        let result = tmux_client.send_keys_many(&send.target, &send.keys);
+        state.finish_send(send, result);
+    }
+    !due.is_empty()
+}
+
+/// Sends the approve keys to the given agents (by index), stopping at the first failure
+fn send_approvals(state: &mut AppState, tmux_client: &dyn TmuxBackend, indices: &[usize]) {
+    for &idx in indices {
+        let Some(agent) = state.agents.get_agent(idx) else {
+            continue;
+        };
+        let target = agent.target.clone();
+        let keys = KeyArg::names(&state.get_agent_keys(agent).approve);
+        let result = tmux_client.send_keys_many(&target, &keys);
+        state.audit(
+            AuditEntry::new(AuditSource::Ui, AuditAction::Approve, Some(&target))
+                .keys(&keys)
+                .result(&result),
+        );
+        if let Err(e) = result {
+            state.set_error(format!("Failed to approve {}: {}", target, e));
+            break;
+        }
+    }
+}
+
+/// Approves agents as allowed by `[[approval_policies]]`: sends plain approvals,
+/// asks before approving `confirm` ones and reports the `never` ones
+fn approve_with_policy(state: &mut AppState, tmux_client: &dyn TmuxBackend, indices: &[usize]) {
+    let plan = state.plan_approvals(indices);
+    send_approvals(state, tmux_client, &plan.approve);
+
+    let target_of = |state: &AppState, idx: usize| {
+        state
+            .agents
+            .get_agent(idx)
+            .map(|a| a.target.clone())
+            .unwrap_or_default()
+    };
+    if let Some((idx, rule)) = plan.blocked.first() {
+        let more = match plan.blocked.len() {
+            1 => String::new(),
+            n => format!(" (+{} more)", n - 1),
+        };
+        state.set_error(format!(
+            "Approval of {} blocked by policy '{}'{}",
+            target_of(state, *idx),
+            rule,
+            more
+        ));
+    }
+    if let Some((idx, rule)) = plan.confirm.first() {
+        let prompt = match plan.confirm.len() {
+            1 => format!(
+                "Approve {} (policy '{}')? Type 'y' to confirm.",
+                target_of(state, *idx),
+                rule
+            ),
+            n => format!(
+                "Approve {} requests that need confirmation? Type 'y' to confirm.",
+                n
+            ),
+        };
+        let agent_ids = plan
+            .confirm
+            .iter()
+            .filter_map(|(idx, _)| state.agents.get_agent(*idx).map(|a| a.id.clone()))
+            .collect();
+        state.popup_input = Some(crate::app::PopupInputState {
+            title: "Confirm Approval".to_string(),
+            prompt,
+            buffer: String::new(),
+            cursor: 0,
+            popup_type: crate::app::PopupType::ApproveConfirmation { agent_ids },
+        });
+    }
+}
+
+/// Key in the notation used by `[key_bindings]` ("C-x", "M-x", "Enter", "F1")
+pub(super) fn key_string(code: KeyCode, modifiers: KeyModifiers) -> String {
+    match code {
+        KeyCode::Char(c) => {
+            let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+            let alt = modifiers.contains(KeyModifiers::ALT);
+            let shift = modifiers.contains(KeyModifiers::SHIFT);
+            let is_uppercase = c.is_ascii_uppercase();
+            let base_lowercase = c.to_ascii_lowercase();
+
+            match (ctrl, alt, shift, is_uppercase) {
+                (true, _, _, _) => format!("C-{}", base_lowercase),
+                (false, true, true, _) => format!("M-S-{}", base_lowercase),
+                (false, true, false, true) => format!("M-{}", base_lowercase),
+                (false, true, false, false) => format!("M-{}", base_lowercase),
+                (false, false, true, _) => c.to_uppercase().to_string(),
+                (false, false, false, true) => c.to_string(),
+                (false, false, false, false) => c.to_string(),
+            }
+        }
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        _ => String::new(),
+    }
+}
+
 fn map_key_to_action(
     code: KeyCode,
     modifiers: KeyModifiers,
@@ -1509,13 +1913,33 @@ fn map_key_to_action(
             KeyCode::Esc => Action::HideModalTextarea,
             KeyCode::Enter if modal.is_single_line => Action::ModalTextareaSubmit,
             KeyCode::Enter if modifiers.contains(KeyModifiers::ALT) => Action::ModalTextareaSubmit,
+            // History: Up/Down past the first/last line, Ctrl-S to search
+            // (Ctrl-R stays redo in the editor)
+            KeyCode::Up if !modal.readonly && modal.cursor_on_first_line() => Action::HistoryPrev,
+            KeyCode::Down if !modal.readonly && modal.cursor_on_last_line() => Action::HistoryNext,
+            KeyCode::Char('s') if !modal.readonly && modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::ShowHistorySearch
+            }
+            // Attach a file / insert a paste buffer or another pane's screen
+            KeyCode::Char('o') if !modal.readonly && modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::ShowFilePicker
+            }
+            KeyCode::Char('g') if !modal.readonly && modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::ShowPanePicker
+            }
             _ => Action::None, // All other keys handled directly in event loop
         };
     }
 
     // If popup is shown, intercept all keys
-    if state.popup_input.is_some() {
+    if let Some(popup) = &state.popup_input {
+        let general_input = popup.popup_type == crate::app::PopupType::GeneralInput;
         return match code {
+            KeyCode::Up if general_input => Action::HistoryPrev,
+            KeyCode::Down if general_input => Action::HistoryNext,
+            KeyCode::Char('r') if general_input && modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::ShowHistorySearch
+            }
             KeyCode::Enter => Action::PopupInputSubmit,
             KeyCode::Esc => Action::HidePopupInput,
             KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
@@ -1535,6 +1959,19 @@ fn map_key_to_action(
         };
     }
 
+    // If the choice picker is shown, it takes the keys
+    if let Some(picker) = &state.choice_picker {
+        return match code {
+            KeyCode::Esc => Action::HideChoicePicker,
+            KeyCode::Up | KeyCode::Char('k') => Action::ChoiceMove(-1),
+            KeyCode::Down | KeyCode::Char('j') => Action::ChoiceMove(1),
+            KeyCode::Char(' ') if picker.multi_select => Action::ChoiceToggle,
+            KeyCode::Char(c @ '1'..='9') => Action::ChoicePick(c as usize - '1' as usize),
+            KeyCode::Enter => Action::ChoiceSubmit,
+            _ => Action::None,
+        };
+    }
+
     // If input panel is focused, handle input-specific keys
     if state.is_input_focused() {
         return match code {
@@ -1550,44 +1987,19 @@ fn map_key_to_action(
             KeyCode::Right => Action::CursorRight,
             KeyCode::Home => Action::CursorHome,
             KeyCode::End => Action::CursorEnd,
+            // History recall and search
+            KeyCode::Up => Action::HistoryPrev,
+            KeyCode::Down => Action::HistoryNext,
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::ShowHistorySearch
+            }
             KeyCode::Char(c) => Action::InputChar(c),
             _ => Action::None,
         };
     }
 
     // Sidebar focused - check popup trigger key first
-    let key_str = match code {
-        KeyCode::Char(c) => {
-            let ctrl = modifiers.contains(KeyModifiers::CONTROL);
-            let alt = modifiers.contains(KeyModifiers::ALT);
-            let shift = modifiers.contains(KeyModifiers::SHIFT);
-            let is_uppercase = c.is_ascii_uppercase();
-            let base_lowercase = c.to_ascii_lowercase();
-
-            match (ctrl, alt, shift, is_uppercase) {
-                (true, _, _, _) => format!("C-{}", base_lowercase),
-                (false, true, true, _) => format!("M-S-{}", base_lowercase),
-                (false, true, false, true) => format!("M-{}", base_lowercase),
-                (false, true, false, false) => format!("M-{}", base_lowercase),
-                (false, false, true, _) => c.to_uppercase().to_string(),
-                (false, false, false, true) => c.to_string(),
-                (false, false, false, false) => c.to_string(),
-            }
-        }
-        KeyCode::Enter => "Enter".to_string(),
-        KeyCode::Tab => "Tab".to_string(),
-        KeyCode::BackTab => "BackTab".to_string(),
-        KeyCode::Esc => "Esc".to_string(),
-        KeyCode::Backspace => "Backspace".to_string(),
-        KeyCode::Delete => "Delete".to_string(),
-        KeyCode::Insert => "Insert".to_string(),
-        KeyCode::Home => "Home".to_string(),
-        KeyCode::End => "End".to_string(),
-        KeyCode::PageUp => "PageUp".to_string(),
-        KeyCode::PageDown => "PageDown".to_string(),
-        KeyCode::F(n) => format!("F{}", n),
-        _ => String::new(),
-    };
+    let key_str = key_string(code, modifiers);
 
     if !key_str.is_empty() {
         // Check popup trigger key (only for unmodified keys)
@@ -1611,6 +2023,7 @@ fn map_key_to_action(
                 KeyAction::Reject => Action::Reject,
                 KeyAction::ApproveAll => Action::ApproveAll,
                 KeyAction::SendNumber(n) => Action::SendNumber(*n),
+                KeyAction::PickChoice => Action::ShowChoicePicker,
                 KeyAction::SendKeys(keys) => Action::SendKeys(keys.clone()),
                 KeyAction::KillApp { method } => Action::KillApp {
                     method: method.clone(),
@@ -1623,7 +2036,7 @@ fn map_key_to_action(
                             prompt: "New session name:".to_string(),
                             initial: agent.session.clone(),
                             popup_type: crate::app::PopupType::RenameSession {
-                                session: agent.session.clone(),
+                                session: agent.qualified_session(),
                             },
                         }
                     } else {
@@ -1653,6 +2066,13 @@ fn map_key_to_action(
                 KeyAction::ToggleFilterSelected => Action::ToggleFilterSelected,
                 KeyAction::ReloadConfig => Action::ReloadConfig,
                 KeyAction::NextTheme => Action::NextTheme,
+                KeyAction::ResendLast => Action::ResendLast,
+                KeyAction::EditQueue => Action::EditQueue,
+                KeyAction::RunWorkflow(name) => Action::RunWorkflow(name.clone()),
+                KeyAction::CancelWorkflows => Action::CancelWorkflows,
+                KeyAction::Launch(name) => Action::Launch(name.clone()),
+                KeyAction::ToggleZoom => Action::ToggleZoom,
+                KeyAction::Passthrough => Action::StartPassthrough,
             };
         }
     }
@@ -1712,6 +2132,8 @@ fn map_key_to_action(
                 Action::ClearSelection
             } else if state.show_subagent_log {
                 Action::ToggleSubagentLog
+            } else if state.mirror.is_some() {
+                Action::ToggleZoom
             } else {
                 Action::None
             }
@@ -1721,52 +2143,6 @@ fn map_key_to_action(
     }
 }
 
-/// Expand variables in a command template using agent context
-///
-/// Supported variables:
-/// - `${SESSION_NAME}` - Agent's tmux session name
-/// - `${SESSION_DIR}` - Agent's working directory path
-/// - `${WINDOW_INDEX}` - Agent's tmux window index
-/// - `${WINDOW_NAME}` - Agent's tmux window name
-/// - `${PANE_INDEX}` - Agent's tmux pane index
-/// - `${PANE_TARGET}` - Agent's tmux target (session:window.pane)
-/// - `${ENV:VAR}` - Environment variable value
-fn expand_command_variables(template: &str, agent: &crate::agents::MonitoredAgent) -> String {
-    use regex::Regex;
-
-    let mut result = template.to_string();
-
-    // Replace ${SESSION_NAME}
-    result = result.replace("${SESSION_NAME}", &agent.session);
-
-    // Replace ${SESSION_DIR}
-    result = result.replace("${SESSION_DIR}", &agent.path);
-
-    // Replace ${WINDOW_INDEX}
-    result = result.replace("${WINDOW_INDEX}", &agent.window.to_string());
-
-    // Replace ${WINDOW_NAME}
-    result = result.replace("${WINDOW_NAME}", &agent.window_name);
-
-    // Replace ${PANE_INDEX}
-    result = result.replace("${PANE_INDEX}", &agent.pane.to_string());
-
-    // Replace ${PANE_TARGET}
-    result = result.replace("${PANE_TARGET}", &agent.target);
-
-    // Replace ${ENV:VAR} using regex
-    if let Ok(re) = Regex::new(r"\$\{ENV:([^}]+)\}") {
-        result = re
-            .replace_all(&result, |caps: &regex::Captures| {
-                let var_name = &caps[1];
-                std::env::var(var_name).unwrap_or_default()
-            })
-            .to_string();
-    }
-
-    result
-}
-
 /// Helper to get stdio for logging (debug mode) or null
 fn get_log_stdio(debug_mode: bool) -> std::process::Stdio {
     if debug_mode {
@@ -8,7 +8,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::agents::{AgentStatus, ApprovalType, MonitoredAgent, SubagentStatus};
+use crate::agents::{format_tokens, AgentStatus, ApprovalType, MonitoredAgent};
 use crate::app::AppState;
 use crate::ui::Styles;
 
@@ -27,17 +27,25 @@ type WindowsMap<'a> = BTreeMap<WindowKey<'a>, WindowAgents<'a>>;
 /// Type alias for sessions map
 type SessionsMap<'a> = BTreeMap<&'a str, WindowsMap<'a>>;
 
-/// Represents the hierarchical structure: Session -> Window -> Agents
+/// Type alias for servers map (None = default server, sorted first)
+type ServersMap<'a> = BTreeMap<Option<&'a str>, SessionsMap<'a>>;
+
+/// Label used in server headers for the default tmux server
+const DEFAULT_SERVER_LABEL: &str = "default";
+
+/// Represents the hierarchical structure: Server -> Session -> Window -> Agents
 struct SessionWindowTree<'a> {
-    sessions: SessionsMap<'a>,
+    servers: ServersMap<'a>,
 }
 
 impl<'a> SessionWindowTree<'a> {
     fn new(agents: &[(usize, &'a MonitoredAgent)]) -> Self {
-        let mut sessions: SessionsMap<'a> = BTreeMap::new();
+        let mut servers: ServersMap<'a> = BTreeMap::new();
 
         for (original_idx, agent) in agents.iter() {
-            sessions
+            servers
+                .entry(agent.server.as_deref())
+                .or_default()
                 .entry(&agent.session)
                 .or_default()
                 .entry((agent.window, &agent.window_name))
@@ -45,7 +53,12 @@ impl<'a> SessionWindowTree<'a> {
                 .push((*original_idx, *agent));
         }
 
-        Self { sessions }
+        Self { servers }
+    }
+
+    /// Server headers are only shown when agents from additional servers are present
+    fn show_server_headers(&self) -> bool {
+        self.servers.keys().any(|server| server.is_some())
     }
 }
 
@@ -163,73 +176,97 @@ impl AgentTreeWidget {
         let mut color_cache: HashMap<String, Color> = HashMap::new();
         let _selection_mode = state.config.selection_mode.as_str();
 
-        for (session, windows) in tree.sessions.iter() {
-            // Render Session Header (once per session)
-            let header_str = if header_template.is_empty() {
-                format!("▼ {}", session)
-            } else {
-                header_template.replace("{session}", session)
-            };
+        let server_header_template = &state.config.pane_tree.server_header_template;
+        let show_server_headers = tree.show_server_headers();
 
-            // Text style (FG only)
-            let text_style = Style::default().fg(header_fg).add_modifier(Modifier::BOLD);
-
-            // Item style (BG applies to full width)
-            let mut item_style = Style::default();
-            if let Some(bg) = header_bg {
-                item_style = item_style.bg(bg);
+        for (server, sessions) in tree.servers.iter() {
+            if show_server_headers {
+                let header_str = server_header_template
+                    .replace("{server}", server.unwrap_or(DEFAULT_SERVER_LABEL));
+                let mut item_style = Style::default();
+                if let Some(bg) = header_bg {
+                    item_style = item_style.bg(bg);
+                }
+                items.push(
+                    ListItem::new(Line::from(vec![Span::styled(
+                        header_str,
+                        Style::default()
+                            .fg(header_fg)
+                            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    )]))
+                    .style(item_style),
+                );
             }
 
-            items.push(
-                ListItem::new(Line::from(vec![Span::styled(header_str, text_style)]))
-                    .style(item_style),
-            );
-
-            for ((window_num, window_name), window_agents) in windows.iter() {
-                for (original_idx, agent) in window_agents.iter() {
-                    let is_cursor = *original_idx == state.selected_index;
-                    let is_selected = state.is_multi_selected(*original_idx);
-
-                    let mut ctx = AgentRenderCtx {
-                        state,
-                        session,
-                        window_id: *window_num,
-                        window_name,
-                        available_width,
-                        is_cursor,
-                        is_selected,
-                        color_cache: &mut color_cache,
-                    };
-
-                    // Render agent using pre-parsed template
-                    let rendered_lines = render_parsed_template(&parsed_template, agent, &mut ctx);
-
-                    // Create ONE ListItem for the whole agent (fixes cropping)
-                    let mut item = ListItem::new(rendered_lines);
-
-                    // Apply style to the whole item
-                    let mut item_style = Style::default();
-
-                    // 1. Base background from agent config
-                    if let Some(bg_color) = &agent.background_color {
-                        if let Some(c) = Styles::parse_color(bg_color) {
-                            item_style = item_style.bg(c);
-                        }
-                    }
+            for (session, windows) in sessions.iter() {
+                // Render Session Header (once per session)
+                let header_str = if header_template.is_empty() {
+                    format!("▼ {}", session)
+                } else {
+                    header_template.replace("{session}", session)
+                };
+
+                // Text style (FG only)
+                let text_style = Style::default().fg(header_fg).add_modifier(Modifier::BOLD);
 
-                    // 2. Apply selection background if configured
-                    if is_cursor {
-                        if let Some(bg_color) = selected_bg {
-                            item_style = item_style.bg(bg_color);
+                // Item style (BG applies to full width)
+                let mut item_style = Style::default();
+                if let Some(bg) = header_bg {
+                    item_style = item_style.bg(bg);
+                }
+
+                items.push(
+                    ListItem::new(Line::from(vec![Span::styled(header_str, text_style)]))
+                        .style(item_style),
+                );
+
+                for ((window_num, window_name), window_agents) in windows.iter() {
+                    for (original_idx, agent) in window_agents.iter() {
+                        let is_cursor = *original_idx == state.selected_index;
+                        let is_selected = state.is_multi_selected(*original_idx);
+
+                        let mut ctx = AgentRenderCtx {
+                            state,
+                            session,
+                            window_id: *window_num,
+                            window_name,
+                            available_width,
+                            is_cursor,
+                            is_selected,
+                            color_cache: &mut color_cache,
+                        };
+
+                        // Render agent using pre-parsed template
+                        let rendered_lines =
+                            render_parsed_template(&parsed_template, agent, &mut ctx);
+
+                        // Create ONE ListItem for the whole agent (fixes cropping)
+                        let mut item = ListItem::new(rendered_lines);
+
+                        // Apply style to the whole item
+                        let mut item_style = Style::default();
+
+                        // 1. Base background from agent config
+                        if let Some(bg_color) = &agent.background_color {
+                            if let Some(c) = Styles::parse_color(bg_color) {
+                                item_style = item_style.bg(c);
+                            }
                         }
-                    } else if is_selected {
-                        if let Some(bg_color) = multi_select_bg {
-                            item_style = item_style.bg(bg_color);
+
+                        // 2. Apply selection background if configured
+                        if is_cursor {
+                            if let Some(bg_color) = selected_bg {
+                                item_style = item_style.bg(bg_color);
+                            }
+                        } else if is_selected {
+                            if let Some(bg_color) = multi_select_bg {
+                                item_style = item_style.bg(bg_color);
+                            }
                         }
-                    }
 
-                    item = item.style(item_style);
-                    items.push(item);
+                        item = item.style(item_style);
+                        items.push(item);
+                    }
                 }
             }
         }
@@ -242,17 +279,22 @@ impl AgentTreeWidget {
         let mut found = false;
 
         // Re-traverse to find visual index
-        'outer: for (_session, windows) in tree.sessions.iter() {
-            visual_index += 1; // Header
-
-            for ((_window_num, _window_name), window_agents) in windows.iter() {
-                for (original_idx, _agent) in window_agents.iter() {
-                    if *original_idx == state.selected_index {
-                        found = true;
-                        break 'outer;
-                    }
+        'outer: for sessions in tree.servers.values() {
+            if show_server_headers {
+                visual_index += 1; // Server header
+            }
+            for windows in sessions.values() {
+                visual_index += 1; // Header
+
+                for window_agents in windows.values() {
+                    for (original_idx, _agent) in window_agents.iter() {
+                        if *original_idx == state.selected_index {
+                            found = true;
+                            break 'outer;
+                        }
 
-                    visual_index += 1; // Each agent is now 1 item
+                        visual_index += 1; // Each agent is now 1 item
+                    }
                 }
             }
         }
@@ -284,35 +326,47 @@ impl AgentTreeWidget {
         // Re-traverse to find agent at row
         let mut visual_index = 0;
 
-        for (session, windows) in tree.sessions.iter() {
-            // Header takes 1 line
-            if visual_index == row {
-                // Clicked on session header - maybe in future this can collapse select session etc.
-                return None;
+        let show_server_headers = tree.show_server_headers();
+
+        for sessions in tree.servers.values() {
+            if show_server_headers {
+                if visual_index == row {
+                    return None;
+                }
+                visual_index += 1;
             }
-            visual_index += 1;
-
-            for ((window_num, window_name), window_agents) in windows.iter() {
-                for (original_idx, agent) in window_agents.iter() {
-                    // Calculate height by rendering (fast enough for click handling)
-                    let mut ctx = AgentRenderCtx {
-                        state,
-                        session,
-                        window_id: *window_num,
-                        window_name,
-                        available_width: width,
-                        is_cursor: false, // height same regardless
-                        is_selected: false,
-                        color_cache: &mut color_cache,
-                    };
-                    let height = render_parsed_template(&parsed_template, agent, &mut ctx).len();
-
-                    // Check if row matches this agent item block
-                    if row >= visual_index && row < visual_index + height {
-                        return Some(*original_idx);
-                    }
 
-                    visual_index += height;
+            for (session, windows) in sessions.iter() {
+                // Header takes 1 line
+                if visual_index == row {
+                    // Clicked on session header - maybe in future this can collapse select session etc.
+                    return None;
+                }
+                visual_index += 1;
+
+                for ((window_num, window_name), window_agents) in windows.iter() {
+                    for (original_idx, agent) in window_agents.iter() {
+                        // Calculate height by rendering (fast enough for click handling)
+                        let mut ctx = AgentRenderCtx {
+                            state,
+                            session,
+                            window_id: *window_num,
+                            window_name,
+                            available_width: width,
+                            is_cursor: false, // height same regardless
+                            is_selected: false,
+                            color_cache: &mut color_cache,
+                        };
+                        let height =
+                            render_parsed_template(&parsed_template, agent, &mut ctx).len();
+
+                        // Check if row matches this agent item block
+                        if row >= visual_index && row < visual_index + height {
+                            return Some(*original_idx);
+                        }
+
+                        visual_index += height;
+                    }
                 }
             }
         }
@@ -589,7 +643,22 @@ fn render_placeholder<'a, 'b>(
         }
         "pid" => Span::styled(agent.pid.to_string(), ctx.state.styles.dimmed),
         "uptime" => Span::styled(agent.uptime_str(), ctx.state.styles.dimmed),
+        "status_time" => Span::styled(agent.time_in_status_str(), ctx.state.styles.dimmed),
+        "status_age" => Span::styled(agent.status_age_text(), ctx.state.styles.dimmed),
+        "output_age" => Span::styled(agent.output_age_str(), ctx.state.styles.dimmed),
         "path" => Span::styled(agent.abbreviated_path(), ctx.state.styles.header),
+        "branch" => match &agent.git {
+            Some(git) => Span::styled(git.branch.as_str(), ctx.state.styles.highlight),
+            None => Span::raw(""),
+        },
+        "git_status" => match &agent.git {
+            Some(git) => Span::styled(git.status_text(), ctx.state.styles.processing),
+            None => Span::raw(""),
+        },
+        "queue" => match ctx.state.queued_prompt_count(&agent.id) {
+            0 => Span::raw(""),
+            n => Span::styled(format!("[{} queued]", n), ctx.state.styles.highlight),
+        },
         "status_text" => {
             let (text, style) = match &agent.status {
                 AgentStatus::Idle { label } => {
@@ -599,6 +668,9 @@ fn render_placeholder<'a, 'b>(
                 AgentStatus::AwaitingApproval { .. } => {
                     ("Waiting", ctx.state.styles.awaiting_approval)
                 }
+                AgentStatus::Error { .. } if agent.status_rule.as_deref() == Some("stalled") => {
+                    ("Stalled", ctx.state.styles.error)
+                }
                 AgentStatus::Error { .. } => ("Error", ctx.state.styles.error),
                 AgentStatus::Unknown => ("Unknown", ctx.state.styles.unknown),
             };
@@ -618,6 +690,17 @@ fn render_placeholder<'a, 'b>(
                 Span::raw("")
             }
         }
+        "tokens" => match agent.tokens {
+            Some(tokens) => Span::styled(
+                format!("{} tok", format_tokens(tokens)),
+                ctx.state.styles.dimmed,
+            ),
+            None => Span::raw(""),
+        },
+        "cost" => match agent.cost {
+            Some(cost) => Span::styled(format!("${:.2}", cost), ctx.state.styles.dimmed),
+            None => Span::raw(""),
+        },
         "subagents" => Span::raw(""), // Handled separately
         _ => Span::raw(format!("{{{}}}", name)),
     }
@@ -629,32 +712,13 @@ fn render_subagents<'a>(
     width: usize,
 ) -> Vec<Line<'a>> {
     let mut lines = Vec::new();
-    for subagent in &agent.subagents {
-        let (sub_char, sub_style) = match subagent.status {
-            SubagentStatus::Running => (state.spinner_frame(), state.styles.subagent_running),
-            SubagentStatus::Completed => (
-                state.config.indicators.subagent_completed.as_str(),
-                state.styles.subagent_completed,
-            ),
-            SubagentStatus::Failed => (
-                state.config.indicators.subagent_failed.as_str(),
-                state.styles.subagent_failed,
-            ),
-            SubagentStatus::Unknown => (
-                state.config.indicators.unknown.as_str(),
-                state.styles.unknown,
-            ),
-        };
-
-        let duration = if matches!(subagent.status, SubagentStatus::Running) {
-            format!(" ({})", subagent.duration_str())
-        } else {
-            String::new()
-        };
+    // Finished subagents are listed in the subagent log only
+    for subagent in agent.subagents.iter().filter(|s| s.is_running()) {
+        let duration = format!(" ({})", subagent.duration_str());
 
         let line = Line::from(vec![
             Span::raw("   "), // Indent
-            Span::styled(sub_char, sub_style),
+            Span::styled(state.spinner_frame(), state.styles.subagent_running),
             Span::raw(" "),
             Span::styled(
                 subagent.subagent_type.display_name(),
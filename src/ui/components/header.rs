@@ -1,3 +1,4 @@
+use crate::agents::format_tokens;
 use crate::app::AppState;
 use chrono::Local;
 use ratatui::{
@@ -62,6 +63,23 @@ impl HeaderWidget {
             spans.push(Span::styled(" ✓ ready ", state.styles.idle));
         }
 
+        // Usage summed over all agents reporting it
+        let (cost, tokens) = state.agents.usage_totals();
+        let usage: Vec<String> = [
+            cost.map(|c| format!("${:.2}", c)),
+            tokens.map(|t| format!("{} tok", format_tokens(t))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !usage.is_empty() {
+            spans.push(Span::styled("│", state.styles.dimmed));
+            spans.push(Span::styled(
+                format!(" Σ {} ", usage.join(" · ")),
+                state.styles.normal,
+            ));
+        }
+
         // System stats: CPU
         spans.push(Span::styled("│", state.styles.dimmed));
         let cpu_color = if state.system_stats.cpu_usage > 80.0 {
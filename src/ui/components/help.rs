@@ -72,6 +72,10 @@ impl HelpWidget {
                 KeyAction::SendNumber(n) => {
                     add_line("Actions", format!("  {:14} Send number {}", keys_str, n))
                 }
+                KeyAction::PickChoice => add_line(
+                    "Actions",
+                    format!("  {:14} Pick question choice(s)", keys_str),
+                ),
 
                 // View / Filters
                 KeyAction::TogglePaneTreeMode => {
@@ -80,6 +84,12 @@ impl HelpWidget {
                 KeyAction::ToggleSubagentLog => {
                     add_line("View", format!("  {:14} Toggle subagent log", keys_str))
                 }
+                KeyAction::ToggleZoom => {
+                    add_line("View", format!("  {:14} Live pane mirror", keys_str))
+                }
+                KeyAction::Passthrough => {
+                    add_line("View", format!("  {:14} Pass keys to pane", keys_str))
+                }
                 KeyAction::ToggleFilterActive => add_line(
                     "Filters",
                     format!("  {:14} Toggle active filter (Non-Idle)", keys_str),
@@ -103,6 +113,22 @@ impl HelpWidget {
                 KeyAction::NextTheme => {
                     add_line("View", format!("  {:14} Cycle color themes", keys_str))
                 }
+                KeyAction::ResendLast => {
+                    add_line("Actions", format!("  {:14} Resend last input", keys_str))
+                }
+                KeyAction::EditQueue => {
+                    add_line("Actions", format!("  {:14} Edit prompt queue", keys_str))
+                }
+                KeyAction::RunWorkflow(name) => add_line(
+                    "Actions",
+                    format!("  {:14} Run workflow: {}", keys_str, name),
+                ),
+                KeyAction::CancelWorkflows => {
+                    add_line("Actions", format!("  {:14} Stop workflows", keys_str))
+                }
+                KeyAction::Launch(name) => {
+                    add_line("Actions", format!("  {:14} Launch: {}", keys_str, name))
+                }
 
                 // Commands / Custom
                 KeyAction::RenameSession => {
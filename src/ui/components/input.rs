@@ -18,11 +18,13 @@ impl InputWidget {
         let cursor_pos = state.get_cursor_position();
         let is_focused = state.is_input_focused();
 
-        // Get target agent name
-        let target_name = state
-            .selected_agent()
-            .map(|a| a.abbreviated_path())
-            .unwrap_or_else(|| "None".to_string());
+        // Get target agent name (or the number of selected agents)
+        let target_name = state.broadcast_label().unwrap_or_else(|| {
+            state
+                .selected_agent()
+                .map(|a| a.abbreviated_path())
+                .unwrap_or_else(|| "None".to_string())
+        });
 
         let title = format!(" Input → {} ", target_name);
 
@@ -69,7 +71,7 @@ impl InputWidget {
                 return vec![Line::from(vec![
                     Span::styled("█", cursor_style),
                     Span::styled(
-                        " (Shift+Enter: newline, Enter: send, Esc: back)",
+                        " (Shift+Enter: newline, Enter: send, Up/C-r: history, Esc: back)",
                         hint_style,
                     ),
                 ])];
@@ -114,6 +114,16 @@ impl MenuTreeWidget {
                 ])
                 .alignment(ratatui::layout::Alignment::Center),
             );
+        } else if title == "History" {
+            block = block.title_bottom(
+                Line::from(vec![
+                    Span::styled("[Enter]", styles.footer_key),
+                    Span::raw(" Insert  "),
+                    Span::styled("[type]", styles.footer_key),
+                    Span::raw(" Search"),
+                ])
+                .alignment(ratatui::layout::Alignment::Center),
+            );
         } else {
             block = block.title_bottom(
                 Line::from(vec![
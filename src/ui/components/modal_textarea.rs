@@ -110,6 +110,22 @@ impl ModalTextareaState {
     pub fn get_text(&self) -> String {
         self.textarea.lines().join("\n")
     }
+
+    /// Replace the whole text, leaving the cursor at the end
+    pub fn set_text(&mut self, text: &str) {
+        self.textarea.select_all();
+        self.textarea.cut();
+        self.textarea.insert_str(text);
+    }
+
+    /// Whether the cursor is on the first / last line (history recall boundaries)
+    pub fn cursor_on_first_line(&self) -> bool {
+        self.textarea.cursor().0 == 0
+    }
+
+    pub fn cursor_on_last_line(&self) -> bool {
+        self.textarea.cursor().0 + 1 >= self.textarea.lines().len()
+    }
 }
 
 /// Widget for rendering modal textarea
@@ -198,7 +214,13 @@ impl ModalTextareaWidget {
                 Span::styled("[Esc]", styles.footer_key),
                 Span::raw(" Cancel  "),
                 Span::styled("[Ctrl+U/R]", styles.footer_key),
-                Span::raw(" Undo/Redo"),
+                Span::raw(" Undo/Redo  "),
+                Span::styled("[Ctrl+S]", styles.footer_key),
+                Span::raw(" History  "),
+                Span::styled("[Ctrl+O]", styles.footer_key),
+                Span::raw(" File  "),
+                Span::styled("[Ctrl+G]", styles.footer_key),
+                Span::raw(" Pane"),
             ])
         };
 
@@ -7,9 +7,10 @@ use ratatui::{
 };
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::agents::AgentStatus;
-use crate::app::AppState;
+use crate::agents::{AgentStatus, ApprovalType, MonitoredAgent};
+use crate::app::{AppState, KeyAction};
 use crate::parsers::ParserRegistry;
+use crate::ui::PaneMirror;
 
 /// Truncate a line to fit within max_width
 /// Returns (truncated_string, was_truncated)
@@ -39,6 +40,80 @@ fn truncate_line(line: &str, max_width: usize) -> (String, bool) {
     (format!("{}…", truncated), true)
 }
 
+/// Lines listing the choices of an agent's question, with the picker cursor
+/// and toggles when the choice picker is open for it
+fn choice_lines<'a>(agent: &MonitoredAgent, state: &'a AppState) -> Vec<Line<'a>> {
+    let AgentStatus::AwaitingApproval {
+        approval_type:
+            ApprovalType::UserQuestion {
+                choices,
+                multi_select,
+            },
+        ..
+    } = &agent.status
+    else {
+        return Vec::new();
+    };
+    if choices.is_empty() {
+        return Vec::new();
+    }
+
+    let picker = state
+        .choice_picker
+        .as_ref()
+        .filter(|p| p.agent_id == agent.id);
+    let title = if *multi_select {
+        "Choices (multi-select):"
+    } else {
+        "Choices:"
+    };
+    let mut lines = vec![Line::from(vec![Span::styled(
+        title,
+        state.styles.dimmed.add_modifier(Modifier::BOLD),
+    )])];
+
+    for (i, choice) in choices.iter().enumerate() {
+        let highlighted = picker.is_some_and(|p| p.cursor == i);
+        let marker = if highlighted { "▶" } else { " " };
+        let checkbox = match (multi_select, picker) {
+            (true, Some(p)) if p.marked.contains(&i) => "[x] ",
+            (true, _) => "[ ] ",
+            (false, _) => "",
+        };
+        let style = if highlighted {
+            state.styles.awaiting_approval.add_modifier(Modifier::BOLD)
+        } else {
+            state.styles.normal
+        };
+        lines.push(Line::from(vec![Span::styled(
+            format!("{}{}. {}{}", marker, i + 1, checkbox, choice),
+            style,
+        )]));
+    }
+
+    let hint = if picker.is_some() {
+        if *multi_select {
+            "↑↓ move · Space toggle · Enter answer · Esc cancel".to_string()
+        } else {
+            "↑↓ move · Enter answer · Esc cancel".to_string()
+        }
+    } else {
+        match state
+            .config
+            .key_bindings
+            .keys_for_action(&KeyAction::PickChoice)
+            .first()
+        {
+            Some(key) => format!("Press {} to pick", key),
+            None => String::new(),
+        }
+    };
+    if !hint.is_empty() {
+        lines.push(Line::from(vec![Span::styled(hint, state.styles.dimmed)]));
+    }
+    lines
+}
+
 /// Widget for previewing the selected pane content
 pub struct PanePreviewWidget;
 
@@ -76,7 +151,9 @@ impl PanePreviewWidget {
                 !summary.tasks.is_empty()
             };
 
-            let use_full_width = state.config.todo_full_width && has_todo;
+            // Question choices live in the right column, so keep it visible
+            let choices = choice_lines(agent, state);
+            let use_full_width = state.config.todo_full_width && has_todo && choices.is_empty();
 
             // Split into columns: TODO | Activity (if not full width)
             let columns = if use_full_width {
@@ -195,6 +272,55 @@ impl PanePreviewWidget {
                     }
                 }
 
+                if !choices.is_empty() {
+                    activity_lines.push(Line::from(""));
+                    activity_lines.extend(choices);
+                }
+
+                // Time in current state and recent transitions
+                activity_lines.push(Line::from(""));
+                activity_lines.push(Line::from(vec![Span::styled(
+                    format!("⏱ {}", agent.status_age_text()),
+                    state.styles.dimmed,
+                )]));
+                if !agent.transitions.is_empty() {
+                    activity_lines.push(Line::from(vec![Span::styled(
+                        &state.config.messages.label_history,
+                        state.styles.dimmed.add_modifier(Modifier::BOLD),
+                    )]));
+                    for transition in agent.transitions.iter().rev().take(3) {
+                        activity_lines.push(Line::from(vec![Span::styled(
+                            format!(" {}", transition.summary()),
+                            state.styles.dimmed,
+                        )]));
+                    }
+                }
+
+                let auto_approvals: Vec<_> = state
+                    .policy_decisions
+                    .iter()
+                    .rev()
+                    .filter(|d| d.agent_id == agent.id)
+                    .take(3)
+                    .collect();
+                if !auto_approvals.is_empty() {
+                    activity_lines.push(Line::from(vec![Span::styled(
+                        "Auto-approved:",
+                        state.styles.dimmed.add_modifier(Modifier::BOLD),
+                    )]));
+                    for decision in auto_approvals {
+                        let style = if decision.error.is_some() {
+                            state.styles.error
+                        } else {
+                            state.styles.dimmed
+                        };
+                        activity_lines.push(Line::from(vec![Span::styled(
+                            format!(" {} {}", decision.at.format("%H:%M:%S"), decision.rule),
+                            style,
+                        )]));
+                    }
+                }
+
                 let activity_paragraph = Paragraph::new(activity_lines);
                 frame.render_widget(activity_paragraph, columns[1]);
             }
@@ -279,6 +405,11 @@ impl PanePreviewWidget {
 
     /// Renders a detailed preview with syntax highlighting for diffs
     pub fn render_detailed(frame: &mut Frame, area: Rect, state: &AppState) {
+        if let Some(mirror) = state.mirror.as_ref().filter(|m| !m.target.is_empty()) {
+            Self::render_mirror(frame, area, state, mirror);
+            return;
+        }
+
         let agent = state.selected_visible_agent();
 
         // Calculate available lines (area height minus border)
@@ -366,4 +497,36 @@ impl PanePreviewWidget {
 
         frame.render_widget(paragraph, area);
     }
+
+    /// Renders the live mirror of the zoomed pane with its own colours
+    fn render_mirror(frame: &mut Frame, area: Rect, state: &AppState, mirror: &PaneMirror) {
+        let available_lines = area.height.saturating_sub(2) as usize;
+        let mut end = mirror.lines.len();
+        while end > 0 && mirror.lines[end - 1].width() == 0 {
+            end -= 1;
+        }
+        let start = end.saturating_sub(available_lines);
+
+        let (title, border_style) = if mirror.passthrough {
+            (
+                format!(
+                    " {} — PASSTHROUGH ({} to leave) ",
+                    mirror.target, state.config.passthrough_exit_key
+                ),
+                state.styles.border_focused,
+            )
+        } else {
+            (format!(" {} — live ", mirror.target), state.styles.border)
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(border_style);
+
+        let paragraph = Paragraph::new(mirror.lines[start..end].to_vec())
+            .style(state.styles.normal)
+            .block(block);
+        frame.render_widget(paragraph, area);
+    }
 }
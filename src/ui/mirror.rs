@@ -0,0 +1,290 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::text::Line;
+use std::time::{Duration, Instant};
+
+use super::ansi::ansi_to_lines;
+use super::app::key_string;
+use crate::app::AppState;
+use crate::audit::{AuditAction, AuditEntry, AuditSource};
+use crate::tmux::{KeyArg, TmuxBackend};
+
+/// Live mirror of the selected pane shown in place of the preview ("zoom")
+#[derive(Debug, Clone, Default)]
+pub struct PaneMirror {
+    /// Pane the lines were captured from
+    pub target: String,
+    /// Visible screen of the pane with its colours
+    pub lines: Vec<Line<'static>>,
+    /// Keys go to the pane until the exit key is pressed
+    pub passthrough: bool,
+    /// Agent in the pane during passthrough (by id, so another pane renumbered
+    /// to the same target is not mistaken for it)
+    pub agent_id: String,
+    /// Keys sent in the current passthrough (audited when it ends)
+    pub sent: Vec<KeyArg>,
+    /// Last capture (None = capture on the next refresh)
+    captured_at: Option<Instant>,
+}
+
+impl PaneMirror {
+    /// Captures again on the next refresh (after sending keys)
+    pub fn invalidate(&mut self) {
+        self.captured_at = None;
+    }
+}
+
+/// Sends a key press to the mirrored pane in passthrough mode, or ends
+/// passthrough on the exit key. Returns false if passthrough is not active.
+pub fn passthrough_key(state: &mut AppState, tmux: &dyn TmuxBackend, key: &KeyEvent) -> bool {
+    let exit_key = match state.config.passthrough_exit_key.as_str() {
+        "" => "C-q".to_string(),
+        key => key.to_string(),
+    };
+    let Some(mirror) = state.mirror.as_mut().filter(|m| m.passthrough) else {
+        return false;
+    };
+    if key_string(key.code, key.modifiers) == exit_key {
+        end_passthrough(state, None);
+        return true;
+    }
+    let Some(tmux_key) = tmux_key(key) else {
+        return true;
+    };
+    let target = mirror.target.clone();
+    match tmux.send_keys_many(&target, std::slice::from_ref(&tmux_key)) {
+        Ok(()) => {
+            mirror.sent.push(tmux_key);
+            mirror.invalidate();
+        }
+        Err(e) => end_passthrough(state, Some(format!("Failed to send keys: {}", e))),
+    }
+    true
+}
+
+/// Pastes text into the mirrored pane in passthrough mode. Returns false if
+/// passthrough is not active.
+pub fn passthrough_paste(state: &mut AppState, tmux: &dyn TmuxBackend, text: &str) -> bool {
+    let Some(mirror) = state.mirror.as_mut().filter(|m| m.passthrough) else {
+        return false;
+    };
+    let key = KeyArg::paste(text);
+    let target = mirror.target.clone();
+    match tmux.send_keys_many(&target, std::slice::from_ref(&key)) {
+        Ok(()) => {
+            mirror.sent.push(key);
+            mirror.invalidate();
+        }
+        Err(e) => state.set_error(format!("Failed to paste: {}", e)),
+    }
+    true
+}
+
+/// Leaves passthrough (the mirror stays), audits the keys sent and reports
+/// `error` if it ended because of one
+fn end_passthrough(state: &mut AppState, error: Option<String>) {
+    let Some(mirror) = state.mirror.as_mut() else {
+        return;
+    };
+    mirror.passthrough = false;
+    let sent = std::mem::take(&mut mirror.sent);
+    let target = mirror.target.clone();
+    if !sent.is_empty() {
+        state.audit(
+            AuditEntry::new(AuditSource::Ui, AuditAction::Keys, Some(&target))
+                .keys(&sent)
+                .detail("passthrough"),
+        );
+    }
+    match error {
+        Some(error) => state.set_error(error),
+        None => state.set_status(format!("Passthrough to {} ended", target)),
+    }
+}
+
+/// Re-captures the mirrored pane when `[timing] mirror_refresh_ms` has passed
+/// or the selection moved. Passthrough stays on its pane whatever is selected
+/// and ends when that pane's agent is gone. Returns true if the mirror changed.
+pub fn refresh_mirror(state: &mut AppState, tmux: &dyn TmuxBackend) -> bool {
+    let Some(mirror) = &state.mirror else {
+        return false;
+    };
+    let target = if mirror.passthrough {
+        let id = &mirror.agent_id;
+        if !state.agents.root_agents.iter().any(|a| &a.id == id) {
+            let error = format!("Passthrough ended: {} is gone", mirror.target);
+            end_passthrough(state, Some(error));
+            return true;
+        }
+        mirror.target.clone()
+    } else {
+        match state.selected_agent() {
+            Some(agent) => agent.target.clone(),
+            None => return false,
+        }
+    };
+    let interval = Duration::from_millis(state.config.timing.mirror_refresh_ms);
+    let Some(mirror) = &mut state.mirror else {
+        return false;
+    };
+    let due =
+        mirror.target != target || mirror.captured_at.is_none_or(|at| at.elapsed() >= interval);
+    if !due {
+        return false;
+    }
+
+    mirror.captured_at = Some(Instant::now());
+    let lines = match tmux.capture_styled(&target) {
+        Ok(screen) => ansi_to_lines(&screen),
+        Err(e) if mirror.passthrough => {
+            end_passthrough(state, Some(format!("Passthrough ended: {}", e)));
+            return true;
+        }
+        Err(e) => vec![Line::raw(format!("Failed to capture {}: {}", target, e))],
+    };
+    if mirror.target == target && mirror.lines == lines {
+        return false;
+    }
+    mirror.target = target;
+    mirror.lines = lines;
+    true
+}
+
+/// tmux key for a key press in passthrough mode; printable characters are
+/// typed literally so they are never read as key names
+pub fn tmux_key(key: &KeyEvent) -> Option<KeyArg> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+    let name = match key.code {
+        KeyCode::Char(c) if !ctrl && !alt => return Some(KeyArg::literal(c.to_string())),
+        KeyCode::Char(c) if ctrl => {
+            let prefix = if alt { "C-M-" } else { "C-" };
+            return Some(KeyArg::key(format!("{}{}", prefix, c.to_ascii_lowercase())));
+        }
+        KeyCode::Char(c) => return Some(KeyArg::key(format!("M-{}", c))),
+        KeyCode::Enter => "Enter",
+        KeyCode::Tab => "Tab",
+        KeyCode::BackTab => return Some(KeyArg::key("BTab")),
+        KeyCode::Backspace => "BSpace",
+        KeyCode::Esc => "Escape",
+        KeyCode::Delete => "DC",
+        KeyCode::Insert => "IC",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PPage",
+        KeyCode::PageDown => "NPage",
+        KeyCode::Up => "Up",
+        KeyCode::Down => "Down",
+        KeyCode::Left => "Left",
+        KeyCode::Right => "Right",
+        KeyCode::F(n) => &format!("F{}", n),
+        _ => return None,
+    };
+    Some(KeyArg::key(with_modifiers(name, ctrl, alt, shift)))
+}
+
+fn with_modifiers(name: &str, ctrl: bool, alt: bool, shift: bool) -> String {
+    let mut key = String::new();
+    if ctrl {
+        key.push_str("C-");
+    }
+    if alt {
+        key.push_str("M-");
+    }
+    if shift {
+        key.push_str("S-");
+    }
+    key.push_str(name);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::MonitoredAgent;
+    use crate::app::Config;
+    use crate::tmux::FakeTmux;
+
+    #[test]
+    fn test_mirror_follows_selection_and_maps_keys() {
+        let mut state = AppState::new(Config::default());
+        for (i, target) in ["main:0.1", "main:0.2"].into_iter().enumerate() {
+            let agent = MonitoredAgent::test_agent(target, 42 + i as u32);
+            state.agents.root_agents.push(agent);
+        }
+        state.update_visible_indices();
+        let tmux = FakeTmux::new();
+        tmux.add_pane("main:0.1", "claude").unwrap();
+        tmux.add_pane("main:0.2", "claude").unwrap();
+        tmux.script("main:0.1", ["\x1b[32m> ready\x1b[0m"]).unwrap();
+        tmux.script("main:0.2", ["other"]).unwrap();
+        tmux.capture_pane("main:0.1").unwrap();
+        tmux.capture_pane("main:0.2").unwrap();
+
+        assert!(!refresh_mirror(&mut state, &tmux));
+        state.mirror = Some(PaneMirror::default());
+        assert!(refresh_mirror(&mut state, &tmux));
+        let mirror = state.mirror.as_ref().unwrap();
+        assert_eq!(mirror.target, "main:0.1");
+        assert_eq!(mirror.lines[0].spans[0].content, "> ready");
+        // Unchanged screen within the refresh interval
+        assert!(!refresh_mirror(&mut state, &tmux));
+
+        state.select_next();
+        assert!(refresh_mirror(&mut state, &tmux));
+        assert_eq!(state.mirror.as_ref().unwrap().target, "main:0.2");
+
+        let key = |code, modifiers| tmux_key(&KeyEvent::new(code, modifiers));
+        assert_eq!(
+            key(KeyCode::Char(';'), KeyModifiers::NONE),
+            Some(KeyArg::literal(";"))
+        );
+        assert_eq!(
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(KeyArg::key("C-c"))
+        );
+        assert_eq!(
+            key(KeyCode::Esc, KeyModifiers::NONE),
+            Some(KeyArg::key("Escape"))
+        );
+        assert_eq!(
+            key(KeyCode::Up, KeyModifiers::SHIFT),
+            Some(KeyArg::key("S-Up"))
+        );
+        assert_eq!(key(KeyCode::Null, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_passthrough_stays_on_its_pane() {
+        let mut state = AppState::new(Config::default());
+        for (i, target) in ["main:0.1", "main:0.2"].into_iter().enumerate() {
+            let agent = MonitoredAgent::test_agent(target, 42 + i as u32);
+            state.agents.root_agents.push(agent);
+        }
+        state.update_visible_indices();
+        let tmux = FakeTmux::new();
+        tmux.add_pane("main:0.1", "claude").unwrap();
+        tmux.add_pane("main:0.2", "claude").unwrap();
+        tmux.script("main:0.1", ["> ready"]).unwrap();
+        tmux.capture_pane("main:0.1").unwrap();
+
+        state.start_passthrough();
+        assert!(refresh_mirror(&mut state, &tmux));
+        state.select_next();
+        refresh_mirror(&mut state, &tmux);
+        assert_eq!(state.mirror.as_ref().unwrap().target, "main:0.1");
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(passthrough_key(&mut state, &tmux, &enter));
+        assert_eq!(tmux.take_sent_keys()[0].target, "main:0.1");
+
+        // The agent is gone (another one took its target): passthrough ends
+        state.agents.root_agents[0] = MonitoredAgent::test_agent("main:0.1", 99);
+        refresh_mirror(&mut state, &tmux);
+        let mirror = state.mirror.as_ref().unwrap();
+        assert!(!mirror.passthrough);
+        assert!(mirror.sent.is_empty());
+        assert!(!passthrough_key(&mut state, &tmux, &enter));
+        assert!(state.last_message.as_ref().unwrap().text.contains("gone"));
+    }
+}
@@ -1,8 +1,11 @@
+mod ansi;
 mod app;
 pub mod components;
 mod layout;
+mod mirror;
 mod styles;
 
 pub use app::run_app;
 pub use layout::Layout;
+pub use mirror::PaneMirror;
 pub use styles::Styles;
@@ -0,0 +1,7 @@
+//! Small helpers shared by the tmux, monitor and app layers
+
+/// Escape a string for safe use in shell commands (single-quote escaping)
+pub fn shell_escape(s: &str) -> String {
+    // Replace single quotes with '\'' (end quote, escaped quote, start quote)
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
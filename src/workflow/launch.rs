@@ -0,0 +1,377 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::agents::{git, StatusKind};
+use crate::app::{template, AppState};
+use crate::audit::{AuditAction, AuditEntry, AuditSource};
+use crate::tmux::{NewPane, TmuxBackend};
+
+/// Time a launched agent has to become idle without `timeout_secs`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Highest `${N}` tried when looking for an unused window name
+const MAX_INDEX: usize = 999;
+
+/// A launched agent that hasn't become idle yet
+#[derive(Debug)]
+pub struct PendingLaunch {
+    launcher: String,
+    /// Target of the new pane
+    pub target: String,
+    /// Prompt sent once the agent is idle
+    prompt: Option<String>,
+    started: Instant,
+    timeout: Duration,
+}
+
+/// Expands a launcher template with the current agent's variables (if any) and `${N}`
+fn expand(state: &AppState, template: &str, n: usize) -> String {
+    let template = template.replace("${N}", &n.to_string());
+    match state.selected_agent() {
+        Some(agent) => template::expand_variables(&template, agent),
+        None => template::expand_env(&template),
+    }
+}
+
+/// Expands a leading `~/` to the home directory
+fn expand_home(path: String) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest).to_string_lossy().into_owned())
+            .unwrap_or(path),
+        None => path,
+    }
+}
+
+/// Starts a `[[launchers]]` entry in a new window (and worktree) and returns its target.
+/// The initial prompt is sent by `advance_launches` once the agent is idle.
+pub fn launch(state: &mut AppState, tmux_client: &dyn TmuxBackend, name: &str) -> Result<String> {
+    let launcher = state
+        .config
+        .launchers
+        .iter()
+        .find(|l| l.name == name)
+        .with_context(|| format!("No launcher named '{}'", name))?
+        .clone();
+
+    let session_template = match (&launcher.session, state.selected_agent()) {
+        (Some(session), _) => session.clone(),
+        (None, Some(agent)) => agent.session.clone(),
+        (None, None) => tmux_client
+            .get_current_session()?
+            .with_context(|| format!("Launcher '{}' needs a session (no agent selected)", name))?,
+    };
+
+    // Directory the agent starts in, or the repository a worktree is added to
+    let cwd = match &launcher.cwd {
+        Some(cwd) => Some(expand_home(expand(state, cwd, 1))),
+        None => state
+            .selected_agent()
+            .map(|a| a.path.clone())
+            .filter(|p| !p.is_empty()),
+    };
+    let repo_root = match &launcher.worktree {
+        Some(_) => Some(
+            cwd.as_deref()
+                .and_then(|dir| git::repo_root(Path::new(dir)))
+                .with_context(|| format!("Launcher '{}' needs a cwd in a git repository", name))?,
+        ),
+        None => None,
+    };
+
+    // Lowest ${N} whose window, worktree and branch names are not taken yet
+    let panes = tmux_client.list_panes()?;
+    let branches = repo_root
+        .as_deref()
+        .map(git::local_branches)
+        .unwrap_or_default();
+    let uses_index = [
+        Some(&session_template),
+        launcher.window.as_ref(),
+        launcher.worktree.as_ref(),
+        launcher.branch.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|t| t.contains("${N}"));
+    let max_index = if uses_index { MAX_INDEX } else { 1 };
+    let (session, window, worktree) = (1..=max_index)
+        .map(|n| {
+            let worktree =
+                repo_root
+                    .as_ref()
+                    .zip(launcher.worktree.as_deref())
+                    .map(|(root, template)| {
+                        let path = root.join(expand_home(expand(state, template, n)));
+                        let branch = match &launcher.branch {
+                            Some(branch) => expand(state, branch, n),
+                            None => path
+                                .file_name()
+                                .map(|f| f.to_string_lossy().into_owned())
+                                .unwrap_or_default(),
+                        };
+                        (path, branch)
+                    });
+            (
+                expand(state, &session_template, n),
+                launcher.window.as_deref().map(|w| expand(state, w, n)),
+                worktree,
+            )
+        })
+        .find(|(session, window, worktree)| {
+            let window_taken = panes.iter().any(|p| {
+                &p.session == session && window.as_ref().is_none_or(|w| &p.window_name == w)
+            });
+            let worktree_taken = worktree
+                .as_ref()
+                .is_some_and(|(path, branch)| path.exists() || branches.contains(branch));
+            !uses_index || !(window_taken || worktree_taken)
+        })
+        .context("No free window name")?;
+
+    let cwd = match (&worktree, &repo_root) {
+        (Some((path, branch)), Some(root)) => {
+            git::add_worktree(root, path, branch)?;
+            Some(path.to_string_lossy().into_owned())
+        }
+        _ => cwd,
+    };
+
+    let pane = NewPane {
+        session,
+        window,
+        cwd,
+        env: launcher.env.clone().into_iter().collect(),
+        command: launcher.command.clone(),
+    };
+    let result = tmux_client.new_pane(&pane);
+    if let (Err(_), Some((path, branch)), Some(root)) = (&result, &worktree, &repo_root) {
+        // Don't leave a worktree behind that no agent runs in
+        if let Err(e) = git::remove_worktree(root, path, branch) {
+            warn!("Failed to remove worktree {}: {:#}", path.display(), e);
+        }
+    }
+    let target = result.as_ref().ok().cloned();
+    state.audit(
+        AuditEntry::new(AuditSource::Ui, AuditAction::Launch, target.as_deref())
+            .detail(match &worktree {
+                Some((path, branch)) => format!(
+                    "{}: {} (worktree {} on {})",
+                    launcher.name,
+                    launcher.command,
+                    path.display(),
+                    branch
+                ),
+                None => format!("{}: {}", launcher.name, launcher.command),
+            })
+            .result(&result),
+    );
+    let target = result?;
+
+    state.pending_launches.push(PendingLaunch {
+        launcher: launcher.name.clone(),
+        target: target.clone(),
+        prompt: launcher.initial_prompt.clone(),
+        started: Instant::now(),
+        timeout: launcher
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT),
+    });
+    Ok(target)
+}
+
+/// Sends the initial prompt to launched agents that became idle, reporting
+/// launches that timed out. Returns true if any launch finished.
+pub fn advance_launches(state: &mut AppState, tmux_client: &dyn TmuxBackend) -> bool {
+    if state.pending_launches.is_empty() {
+        return false;
+    }
+    let mut changed = false;
+    let mut launches = std::mem::take(&mut state.pending_launches);
+    launches.retain(|launch| {
+        let result = advance(state, tmux_client, launch);
+        match result {
+            Ok(false) => true,
+            Ok(true) => {
+                state.set_status(format!(
+                    "✓ Launched {} in {}",
+                    launch.launcher, launch.target
+                ));
+                changed = true;
+                false
+            }
+            Err(e) => {
+                state.set_error(format!(
+                    "Launch {} in {} failed: {:#}",
+                    launch.launcher, launch.target, e
+                ));
+                changed = true;
+                false
+            }
+        }
+    });
+    state.pending_launches = launches;
+    changed
+}
+
+/// Returns true once the agent is idle and the prompt (if any) was sent
+fn advance(
+    state: &mut AppState,
+    tmux_client: &dyn TmuxBackend,
+    launch: &PendingLaunch,
+) -> Result<bool> {
+    let agent = state
+        .agents
+        .root_agents
+        .iter()
+        .find(|a| a.target == launch.target);
+    let Some(agent) = agent.filter(|a| a.status.kind() == StatusKind::Idle) else {
+        if launch.started.elapsed() <= launch.timeout {
+            return Ok(false);
+        }
+        match agent {
+            Some(_) => bail!("timed out waiting for idle"),
+            None => bail!("no agent detected in the new pane"),
+        }
+    };
+
+    let Some(prompt) = &launch.prompt else {
+        return Ok(true);
+    };
+    let text = template::expand_variables(prompt, agent);
+    let keys = state.get_agent_keys(agent).expand_input(&text);
+    let result = tmux_client.send_keys_many(&launch.target, &keys);
+    state.audit(
+        AuditEntry::new(AuditSource::Ui, AuditAction::Input, Some(&launch.target))
+            .keys(&keys)
+            .detail(launch.launcher.clone())
+            .result(&result),
+    );
+    result?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{AgentStatus, MonitoredAgent};
+    use crate::app::config::LauncherConfig;
+    use crate::app::Config;
+    use crate::tmux::{FakeAction, FakeTmux};
+
+    #[test]
+    fn test_launch_numbers_windows_and_sends_prompt_when_idle() {
+        let tmux = FakeTmux::new();
+        tmux.add_pane("agents:0.0", "claude").unwrap();
+        tmux.set_current_session(Some("agents"));
+
+        let mut state = AppState::new(Config {
+            launchers: vec![LauncherConfig {
+                name: "review".to_string(),
+                command: "claude --model opus".to_string(),
+                cwd: Some("/src/app".to_string()),
+                session: None,
+                window: Some("review-${N}".to_string()),
+                worktree: None,
+                branch: None,
+                env: [("REVIEW".to_string(), "1".to_string())].into(),
+                initial_prompt: Some("Review ${WINDOW_NAME}".to_string()),
+                timeout_secs: None,
+            }],
+            ..Default::default()
+        });
+
+        let first = launch(&mut state, &tmux, "review").unwrap();
+        let second = launch(&mut state, &tmux, "review").unwrap();
+        assert_eq!(
+            (first.as_str(), second.as_str()),
+            ("agents:1.0", "agents:2.0")
+        );
+        let actions = tmux.take_actions();
+        assert_eq!(
+            actions[1],
+            FakeAction::NewPane(
+                "agents:2.0".to_string(),
+                NewPane {
+                    session: "agents".to_string(),
+                    window: Some("review-2".to_string()),
+                    cwd: Some("/src/app".to_string()),
+                    env: vec![("REVIEW".to_string(), "1".to_string())],
+                    command: "claude --model opus".to_string(),
+                }
+            )
+        );
+        assert!(launch(&mut state, &tmux, "missing").is_err());
+
+        // Nothing is sent until the new agent is monitored and idle
+        assert!(!advance_launches(&mut state, &tmux));
+        let mut agent = MonitoredAgent::test_agent("agents:1.0", 2001);
+        agent.window_name = "review-1".to_string();
+        agent.path = "/src/app".to_string();
+        agent.status = AgentStatus::Processing {
+            activity: "Starting".to_string(),
+        };
+        state.agents.root_agents.push(agent);
+        assert!(!advance_launches(&mut state, &tmux));
+
+        state.agents.root_agents[0].status = AgentStatus::Idle { label: None };
+        assert!(advance_launches(&mut state, &tmux));
+        let sent = tmux.take_sent_keys();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].target, "agents:1.0");
+        assert!(sent[0]
+            .keys
+            .iter()
+            .any(|k| k.text().contains("Review review-1")));
+        assert_eq!(state.pending_launches.len(), 1);
+        assert_eq!(state.pending_launches[0].target, "agents:2.0");
+    }
+
+    #[test]
+    fn test_launch_in_new_worktree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("app");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let ok = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap()
+                .success();
+            assert!(ok, "git {:?}", args);
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        let tmux = FakeTmux::new();
+        let mut state = AppState::new(Config {
+            launchers: vec![LauncherConfig {
+                name: "feature".to_string(),
+                command: "claude".to_string(),
+                cwd: Some(repo.to_string_lossy().into_owned()),
+                session: Some("features".to_string()),
+                window: None,
+                worktree: Some("../app-${N}".to_string()),
+                branch: Some("agent/${N}".to_string()),
+                env: Default::default(),
+                initial_prompt: None,
+                timeout_secs: None,
+            }],
+            ..Default::default()
+        });
+
+        // app-1 is taken, so the launcher picks app-2 / agent/2
+        std::fs::create_dir(tmp.path().join("app-1")).unwrap();
+        launch(&mut state, &tmux, "feature").unwrap();
+        let worktree = repo.join("../app-2");
+        assert_eq!(git::git_branch(&worktree).as_deref(), Some("agent/2"));
+        let FakeAction::NewPane(_, pane) = &tmux.take_actions()[0] else {
+            panic!("expected a new pane");
+        };
+        assert_eq!(pane.cwd, Some(worktree.to_string_lossy().into_owned()));
+    }
+}
@@ -0,0 +1,5 @@
+mod launch;
+mod runner;
+
+pub use launch::{advance_launches, launch, PendingLaunch};
+pub use runner::{advance_workflows, cancel_workflows, start_workflow, Workflow, WorkflowRun};
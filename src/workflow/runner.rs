@@ -0,0 +1,553 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::agents::{MonitoredAgent, StatusKind};
+use crate::app::config::{WorkflowConfig, WorkflowStepConfig};
+use crate::app::{template, AppState};
+use crate::audit::{AuditAction, AuditEntry, AuditSource};
+use crate::tmux::{KeyArg, TmuxBackend};
+
+/// Timeout of `wait` and `command` steps without `timeout_secs`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Steps a run may take in one pass before yielding (guards against `goto` loops)
+const MAX_STEPS_PER_PASS: usize = 32;
+
+#[derive(Debug, Clone)]
+enum Step {
+    Send(String),
+    Keys(Vec<KeyArg>),
+    Number(u8),
+    Approve,
+    Reject,
+    Wait { kind: StatusKind, timeout: Duration },
+    Command { command: String, timeout: Duration },
+    Report(String),
+    Branch { pattern: Regex, goto: usize },
+    Goto(usize),
+    Mark,
+    Stop,
+}
+
+impl Step {
+    /// Short description for progress messages
+    fn describe(&self) -> String {
+        match self {
+            Step::Send(text) => format!("send \"{}\"", text.lines().next().unwrap_or_default()),
+            Step::Keys(keys) => {
+                let names: Vec<_> = keys.iter().map(KeyArg::text).collect();
+                format!("keys {}", names.join(" "))
+            }
+            Step::Number(n) => format!("number {}", n),
+            Step::Approve => "approve".to_string(),
+            Step::Reject => "reject".to_string(),
+            Step::Wait { kind, .. } => format!("waiting for {}", kind),
+            Step::Command { command, .. } => format!("running {}", command),
+            Step::Report(_) => "report".to_string(),
+            Step::Branch { pattern, .. } => format!("branch on /{}/", pattern),
+            Step::Goto(_) => "goto".to_string(),
+            Step::Mark => "mark".to_string(),
+            Step::Stop => "stop".to_string(),
+        }
+    }
+}
+
+fn parse_status_kind(value: &str) -> Result<StatusKind> {
+    [
+        StatusKind::Idle,
+        StatusKind::Processing,
+        StatusKind::AwaitingApproval,
+        StatusKind::Error,
+    ]
+    .into_iter()
+    .find(|kind| kind.as_str() == value)
+    .with_context(|| {
+        format!(
+            "unknown status '{}', use idle, processing, awaiting_approval or error",
+            value
+        )
+    })
+}
+
+/// A validated `[[workflows]]` entry
+#[derive(Debug, Clone)]
+pub struct Workflow {
+    pub name: String,
+    steps: Vec<Step>,
+}
+
+impl Workflow {
+    /// Checks the steps and resolves `goto` marks
+    pub fn compile(config: &WorkflowConfig) -> Result<Self> {
+        let marks: HashMap<&str, usize> = config
+            .steps
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.mark.as_deref().map(|m| (m, i)))
+            .collect();
+        let steps = config
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                compile_step(step, &marks)
+                    .with_context(|| format!("workflow '{}' step {}", config.name, i + 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name: config.name.clone(),
+            steps,
+        })
+    }
+}
+
+fn compile_step(step: &WorkflowStepConfig, marks: &HashMap<&str, usize>) -> Result<Step> {
+    let actions = [
+        step.send.is_some(),
+        step.keys.is_some(),
+        step.number.is_some(),
+        step.approve,
+        step.reject,
+        step.wait.is_some(),
+        step.command.is_some(),
+        step.report.is_some(),
+        step.branch.is_some() || step.goto.is_some(),
+        step.mark.is_some(),
+        step.stop,
+    ];
+    match actions.iter().filter(|&&set| set).count() {
+        0 => bail!("no action set"),
+        1 => {}
+        _ => bail!("more than one action set"),
+    }
+
+    let timeout = step
+        .timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT);
+    let goto = match &step.goto {
+        Some(mark) => Some(
+            *marks
+                .get(mark.as_str())
+                .with_context(|| format!("no step with mark '{}'", mark))?,
+        ),
+        None => None,
+    };
+
+    Ok(if let Some(text) = &step.send {
+        Step::Send(text.clone())
+    } else if let Some(keys) = &step.keys {
+        Step::Keys(keys.split_whitespace().map(KeyArg::key).collect())
+    } else if let Some(n) = step.number {
+        Step::Number(n)
+    } else if step.approve {
+        Step::Approve
+    } else if step.reject {
+        Step::Reject
+    } else if let Some(kind) = &step.wait {
+        Step::Wait {
+            kind: parse_status_kind(kind)?,
+            timeout,
+        }
+    } else if let Some(command) = &step.command {
+        Step::Command {
+            command: command.clone(),
+            timeout,
+        }
+    } else if let Some(text) = &step.report {
+        Step::Report(text.clone())
+    } else if let Some(pattern) = &step.branch {
+        Step::Branch {
+            pattern: Regex::new(pattern)
+                .with_context(|| format!("invalid branch regex '{}'", pattern))?,
+            goto: goto.context("branch needs a goto mark")?,
+        }
+    } else if let Some(goto) = goto {
+        Step::Goto(goto)
+    } else if step.mark.is_some() {
+        Step::Mark
+    } else {
+        Step::Stop
+    })
+}
+
+/// A workflow in progress on one agent
+#[derive(Debug)]
+pub struct WorkflowRun {
+    workflow: Workflow,
+    /// Target of the agent the workflow runs on
+    pub target: String,
+    /// Id of that agent; the run ends when it is gone, even if another agent
+    /// starts in the same pane
+    pub agent_id: String,
+    /// Index of the current step
+    step: usize,
+    /// When the current step began
+    step_started: Instant,
+    /// When keys were last sent; a following wait needs a status change after it
+    sent_at: Option<Instant>,
+    /// Process of a running `command` step
+    child: Option<Child>,
+}
+
+impl WorkflowRun {
+    fn new(workflow: Workflow, target: String, agent_id: String) -> Self {
+        Self {
+            workflow,
+            target,
+            agent_id,
+            step: 0,
+            step_started: Instant::now(),
+            sent_at: None,
+            child: None,
+        }
+    }
+
+    /// Progress label, e.g. "test [2/5] main:0.1"
+    fn label(&self) -> String {
+        format!(
+            "{} [{}/{}] {}",
+            self.workflow.name,
+            (self.step + 1).min(self.workflow.steps.len()),
+            self.workflow.steps.len(),
+            self.target
+        )
+    }
+
+    /// Kills and reaps the process of a running `command` step
+    fn kill_child(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn jump(&mut self, step: usize) {
+        self.step = step;
+        self.step_started = Instant::now();
+    }
+
+    fn send(
+        &mut self,
+        state: &mut AppState,
+        tmux_client: &dyn TmuxBackend,
+        action: AuditAction,
+        keys: Vec<KeyArg>,
+    ) -> Result<()> {
+        let result = tmux_client.send_keys_many(&self.target, &keys);
+        state.audit(
+            AuditEntry::new(AuditSource::Workflow, action, Some(&self.target))
+                .keys(&keys)
+                .detail(self.workflow.name.clone())
+                .result(&result),
+        );
+        result?;
+        self.sent_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Runs steps until one has to wait. Returns true when the workflow is done.
+    fn advance(&mut self, state: &mut AppState, tmux_client: &dyn TmuxBackend) -> Result<bool> {
+        for _ in 0..MAX_STEPS_PER_PASS {
+            let Some(step) = self.workflow.steps.get(self.step).cloned() else {
+                return Ok(true);
+            };
+            let Some(index) = state
+                .agents
+                .root_agents
+                .iter()
+                .position(|a| a.id == self.agent_id)
+            else {
+                bail!("agent is gone");
+            };
+            let agent: &MonitoredAgent = &state.agents.root_agents[index];
+
+            match step {
+                Step::Send(text) => {
+                    let text = template::expand_variables(&text, agent);
+                    let keys = state.get_agent_keys(agent).expand_input(&text);
+                    self.send(state, tmux_client, AuditAction::Input, keys)?;
+                }
+                Step::Keys(keys) => self.send(state, tmux_client, AuditAction::Keys, keys)?,
+                Step::Number(n) => {
+                    let keys = state.get_agent_keys(agent).expand_number(n);
+                    self.send(state, tmux_client, AuditAction::Number, keys)?;
+                }
+                Step::Approve => {
+                    let plan = state.plan_approvals(&[index]);
+                    if let Some((_, rule)) = plan.blocked.first() {
+                        bail!("approval blocked by policy '{}'", rule);
+                    }
+                    if let Some((_, rule)) = plan.confirm.first() {
+                        bail!("approval needs confirmation (policy '{}')", rule);
+                    }
+                    if plan.approve.is_empty() {
+                        bail!("agent is not awaiting approval");
+                    }
+                    let keys = KeyArg::names(&state.get_agent_keys(agent).approve);
+                    self.send(state, tmux_client, AuditAction::Approve, keys)?;
+                }
+                Step::Reject => {
+                    let keys = KeyArg::names(&state.get_agent_keys(agent).reject);
+                    self.send(state, tmux_client, AuditAction::Reject, keys)?;
+                }
+                Step::Wait { kind, timeout } => {
+                    // After sending, the status must have changed since (the screen
+                    // still shows the old status until the agent reacts)
+                    let changed = self.sent_at.is_none_or(|t| agent.status_since >= t);
+                    if agent.status.kind() == kind && changed {
+                        self.sent_at = None;
+                    } else if self.step_started.elapsed() > timeout {
+                        bail!("timed out waiting for {}", kind);
+                    } else {
+                        return Ok(false);
+                    }
+                }
+                Step::Command { command, timeout } => match &mut self.child {
+                    None => {
+                        let expanded = template::expand_shell_variables(&command, agent);
+                        let mut cmd = Command::new("bash");
+                        cmd.args(["-c", &expanded])
+                            .stdin(Stdio::null())
+                            .stdout(Stdio::null())
+                            .stderr(Stdio::null());
+                        if !agent.path.is_empty() {
+                            cmd.current_dir(&agent.path);
+                        }
+                        let spawned = cmd
+                            .spawn()
+                            .with_context(|| format!("failed to run {}", expanded));
+                        state.audit(
+                            AuditEntry::new(
+                                AuditSource::Workflow,
+                                AuditAction::Command,
+                                Some(&self.target),
+                            )
+                            .detail(expanded)
+                            .result(&spawned),
+                        );
+                        self.child = Some(spawned?);
+                        return Ok(false);
+                    }
+                    Some(child) => match child.try_wait()? {
+                        Some(status) => {
+                            self.child = None;
+                            if !status.success() {
+                                bail!("command failed ({})", status);
+                            }
+                        }
+                        None if self.step_started.elapsed() > timeout => {
+                            self.kill_child();
+                            bail!("command timed out");
+                        }
+                        None => return Ok(false),
+                    },
+                },
+                Step::Report(text) => {
+                    let text = template::expand_variables(&text, agent);
+                    state.set_status(format!("{}: {}", self.workflow.name, text));
+                }
+                Step::Branch { pattern, goto } => {
+                    if pattern.is_match(&agent.status.short_text()) {
+                        self.jump(goto);
+                        continue;
+                    }
+                }
+                Step::Goto(goto) => {
+                    self.jump(goto);
+                    continue;
+                }
+                Step::Mark => {}
+                Step::Stop => return Ok(true),
+            }
+            self.jump(self.step + 1);
+
+            // Report the step that is coming up
+            if let Some(next) = self.workflow.steps.get(self.step) {
+                if !matches!(next, Step::Report(_)) {
+                    state.set_status(format!("Workflow {}: {}", self.label(), next.describe()));
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Drop for WorkflowRun {
+    /// A run that is stopped, fails or loses its agent takes its command with it
+    fn drop(&mut self) {
+        self.kill_child();
+    }
+}
+
+/// Starts a workflow from `[[workflows]]` on every agent in the operation set
+pub fn start_workflow(state: &mut AppState, name: &str) -> Result<usize> {
+    let config = state
+        .config
+        .workflows
+        .iter()
+        .find(|w| w.name == name)
+        .with_context(|| format!("No workflow named '{}'", name))?;
+    let workflow = Workflow::compile(config)?;
+    let agents: Vec<(String, String)> = state
+        .get_operation_indices()
+        .into_iter()
+        .filter_map(|idx| state.agents.get_agent(idx))
+        .map(|a| (a.target.clone(), a.id.clone()))
+        .collect();
+    for (target, id) in &agents {
+        if state.workflow_runs.iter().any(|r| &r.agent_id == id) {
+            bail!("A workflow is already running on {}", target);
+        }
+    }
+    for (target, id) in &agents {
+        state.workflow_runs.push(WorkflowRun::new(
+            workflow.clone(),
+            target.clone(),
+            id.clone(),
+        ));
+    }
+    Ok(agents.len())
+}
+
+/// Advances all running workflows, reporting finished and failed runs.
+/// Returns true if any run made progress.
+pub fn advance_workflows(state: &mut AppState, tmux_client: &dyn TmuxBackend) -> bool {
+    if state.workflow_runs.is_empty() {
+        return false;
+    }
+    let mut changed = false;
+    let mut runs = std::mem::take(&mut state.workflow_runs);
+    runs.retain_mut(|run| {
+        let step = run.step;
+        let result = run.advance(state, tmux_client);
+        changed |= run.step != step || !matches!(result, Ok(false));
+        match result {
+            Ok(false) => true,
+            Ok(true) => {
+                state.set_status(format!(
+                    "✓ Workflow {} finished on {}",
+                    run.workflow.name, run.target
+                ));
+                false
+            }
+            Err(e) => {
+                state.set_error(format!("Workflow {} failed: {:#}", run.label(), e));
+                false
+            }
+        }
+    });
+    state.workflow_runs = runs;
+    changed
+}
+
+/// Stops all running workflows (killing their commands)
+pub fn cancel_workflows(state: &mut AppState) {
+    let count = state.workflow_runs.len();
+    state.workflow_runs.clear();
+    if count > 0 {
+        state.set_status(format!("Stopped {} workflow run(s)", count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentStatus;
+    use crate::app::Config;
+    use crate::tmux::FakeTmux;
+
+    fn step(f: impl FnOnce(&mut WorkflowStepConfig)) -> WorkflowStepConfig {
+        let mut step = WorkflowStepConfig::default();
+        f(&mut step);
+        step
+    }
+
+    #[test]
+    fn test_workflow_waits_branches_and_reports() {
+        let workflow = WorkflowConfig {
+            name: "test".to_string(),
+            description: None,
+            steps: vec![
+                step(|s| s.send = Some("run the tests".to_string())),
+                step(|s| s.wait = Some("idle".to_string())),
+                step(|s| {
+                    s.branch = Some("(?i)fail".to_string());
+                    s.goto = Some("fix".to_string());
+                }),
+                step(|s| s.report = Some("green".to_string())),
+                step(|s| s.stop = true),
+                step(|s| s.mark = Some("fix".to_string())),
+                step(|s| s.send = Some("fix them".to_string())),
+            ],
+        };
+        let mut bad = workflow.clone();
+        bad.steps[2].goto = Some("nowhere".to_string());
+        assert!(Workflow::compile(&bad).is_err());
+        bad.steps[2] = step(|s| {
+            s.send = Some("x".to_string());
+            s.stop = true;
+        });
+        assert!(Workflow::compile(&bad).is_err());
+
+        let mut state = AppState::new(Config {
+            workflows: vec![workflow],
+            ..Default::default()
+        });
+        state
+            .agents
+            .root_agents
+            .push(MonitoredAgent::test_agent("main:0.1", 42));
+        state.agents.root_agents[0].status = AgentStatus::Idle { label: None };
+        state.update_visible_indices();
+        let client = FakeTmux::new();
+        client.add_pane("main:0.1", "claude").unwrap();
+
+        assert_eq!(start_workflow(&mut state, "test").unwrap(), 1);
+        assert!(start_workflow(&mut state, "test").is_err());
+
+        // Sends, then waits: the agent is still idle from before the send
+        advance_workflows(&mut state, &client);
+        assert_eq!(
+            client.take_sent_keys()[0].keys[0],
+            KeyArg::paste("run the tests")
+        );
+        advance_workflows(&mut state, &client);
+        assert_eq!(state.workflow_runs.len(), 1);
+
+        // Idle again with a failure label: branches to the fix step and finishes
+        let agent = &mut state.agents.root_agents[0];
+        agent.status = AgentStatus::Idle {
+            label: Some("Tests failed".to_string()),
+        };
+        agent.status_since = Instant::now();
+        advance_workflows(&mut state, &client);
+        assert_eq!(
+            client.take_sent_keys()[0].keys[0],
+            KeyArg::paste("fix them")
+        );
+        assert!(state.workflow_runs.is_empty());
+        assert!(state
+            .last_message
+            .as_ref()
+            .unwrap()
+            .text
+            .contains("finished"));
+
+        // Another agent started in the pane: the run stops instead of sending to it
+        assert_eq!(start_workflow(&mut state, "test").unwrap(), 1);
+        state.agents.root_agents[0] = MonitoredAgent::test_agent("main:0.1", 43);
+        advance_workflows(&mut state, &client);
+        assert!(client.take_sent_keys().is_empty());
+        assert!(state.workflow_runs.is_empty());
+        assert!(state
+            .last_message
+            .as_ref()
+            .unwrap()
+            .text
+            .contains("agent is gone"));
+    }
+}